@@ -0,0 +1,65 @@
+#import bevy_pbr::mesh_functions::{get_world_from_local, mesh_position_local_to_clip}
+
+struct HealthBar {
+    position: vec2<f32>,
+    width: f32,
+    fill: f32,
+    integrity: f32,
+};
+
+@group(2) @binding(0) var<storage, read> bars: array<HealthBar>;
+@group(2) @binding(1) var<uniform> background: vec4<f32>;
+@group(2) @binding(2) var<uniform> integrity_color: vec4<f32>;
+@group(2) @binding(3) var<uniform> height: f32;
+
+// Each slot has 8 vertices: a unit quad for the health bar, then one with z = 1 for the integrity bar.
+const VERTICES_PER_SLOT: u32 = 8u;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) position: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    // Distance along the bar from 0 to 1.
+    @location(0) u: f32,
+    @location(1) fill: f32,
+    @location(2) color: vec4<f32>,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let bar = bars[vertex.vertex_index / VERTICES_PER_SLOT];
+    var fill = bar.fill;
+    // From red to green as health goes up.
+    var color = vec4<f32>(1. - fill, fill, 0.1, 1.);
+    var offset = vec2<f32>(0.);
+    if vertex.position.z > 0.5 {
+        fill = bar.integrity;
+        color = integrity_color;
+        offset.y = -1.5 * height;
+    }
+    // Collapse hidden bars so they don't cover any pixels.
+    var size = vec2<f32>(bar.width, height);
+    if fill < 0. {
+        size = vec2<f32>(0.);
+    }
+    let local = vec4<f32>(bar.position + offset + vertex.position.xy * size, 0., 1.);
+
+    var out: VertexOutput;
+    out.position = mesh_position_local_to_clip(get_world_from_local(vertex.instance_index), local);
+    out.u = vertex.position.x;
+    out.fill = fill;
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    if in.u > in.fill {
+        return background;
+    }
+    return in.color;
+}
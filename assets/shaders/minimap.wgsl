@@ -7,14 +7,22 @@
 struct GridEntry {
     visibility: f32,
     team_presence: array<f32, NUM_TEAMS>,
+    // One more than the team of a ghost in the cell, or zero.
+    ghost: f32,
 }
 struct MinimapUiMaterial {
     @location(0) colors: array<vec4<f32>, NUM_TEAMS>,
     @location(1) size: GridSize,
     @location(2) camera_position: vec2<f32>,
     @location(3) viewport_size: vec2<f32>,
+    @location(4) ping_position: vec2<f32>,
+    @location(5) ping_strength: f32,
 }
 
+// Radius in cells the alert ping expands to.
+const PING_RADIUS: f32 = 24.;
+const PING_WIDTH: f32 = 1.5;
+
 @group(1) @binding(0)
 var<uniform> input: MinimapUiMaterial;
 @group(1) @binding(1)
@@ -28,6 +36,10 @@ fn get_team_presence(row: u32, col: u32) -> vec3<f32> {
     let arr = grid[grid_index(input.size, row, col)].team_presence;
     return vec3<f32>(arr[0], arr[1], arr[2]);
 }
+fn get_ghost(row: u32, col: u32) -> u32 {
+    return u32(grid[grid_index(input.size, row, col)].ghost);
+}
+
 @fragment
 fn fragment(in: UiVertexOutput) -> @location(0) vec4<f32> {
 
@@ -82,6 +94,25 @@ fn fragment(in: UiVertexOutput) -> @location(0) vec4<f32> {
     }
 
     output_color *= visibility;
+
+    // Mark where enemy heads and gemstones were last seen.
+    if visibility < 1. {
+        var ghost = max(get_ghost(row, col), get_ghost(row + 1u, col));
+        ghost = max(ghost, get_ghost(row, col + 1u));
+        ghost = max(ghost, get_ghost(row - 1u, col));
+        ghost = max(ghost, get_ghost(row, col - 1u));
+        if ghost > 0u {
+            output_color += 0.5 * input.colors[ghost - 1u];
+        }
+    }
+    // Ring expanding from the latest alert.
+    if input.ping_strength > 0. {
+        let radius = (1. - input.ping_strength) * PING_RADIUS;
+        let ring = abs(length(g - input.ping_position) - radius);
+        if ring < PING_WIDTH {
+            output_color += vec4<f32>(1., 0.8, 0.2, 0.) * input.ping_strength;
+        }
+    }
     output_color += camera_brightness;
     output_color.a = 0.9;
     return output_color;
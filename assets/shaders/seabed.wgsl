@@ -0,0 +1,51 @@
+#import "shaders/perlin_noise_2d.wgsl"::{perlin_noise_2d}
+#import "shaders/grid.wgsl"::{GridSize, grid_index, grid_coords};
+#import bevy_pbr::{mesh_view_bindings::globals, forward_io::VertexOutput}
+
+@group(2) @binding(0) var<uniform> size: GridSize;
+@group(2) @binding(1) var<storage, read> grid: array<u32>;
+@group(2) @binding(2) var<storage, read> drift: array<vec2<f32>>;
+
+const KELP: vec4<f32> = vec4<f32>(0.1, 0.5, 0.2, 0.5);
+const VENT: vec4<f32> = vec4<f32>(0.9, 0.4, 0.1, 0.5);
+const CURRENT: vec4<f32> = vec4<f32>(0.4, 0.8, 1.0, 0.25);
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    let g = grid_coords(size, mesh.world_position.xy);
+    let row = u32(g.y);
+    let col = u32(g.x);
+    if (row >= size.rows || col >= size.cols) {
+        return vec4<f32>(0.);
+    }
+    let i = grid_index(size, row, col);
+
+    switch (grid[i]) {
+        // Kelp: patchy fronds.
+        case 1u: {
+            let noise = perlin_noise_2d(g * 3.);
+            var output_color = KELP;
+            output_color.a *= smoothstep(-0.2, 0.4, noise);
+            return output_color;
+        }
+        // Vent: slow pulsing glow.
+        case 2u: {
+            let noise = perlin_noise_2d(g * 2. + globals.time * 0.2);
+            var output_color = VENT;
+            output_color.a *= 0.6 + 0.4 * sin(globals.time * 2. + noise * 3.);
+            return output_color;
+        }
+        // Current: stripes moving along the drift direction.
+        case 3u: {
+            let d = drift[i];
+            let strength = length(d);
+            let along = dot(g, d / max(strength, 0.001));
+            var output_color = CURRENT;
+            output_color.a *= strength * smoothstep(0.6, 1., sin((along - globals.time * strength) * 6.));
+            return output_color;
+        }
+        default: {
+            return vec4<f32>(0.);
+        }
+    }
+}
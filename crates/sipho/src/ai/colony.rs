@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use crate::prelude::*;
+
+use super::{AiCommand, AiController, AiDifficulty, AiUnit, AiView};
+
+/// Tuning that separates the difficulty levels.
+#[derive(Clone, Debug)]
+pub struct AiProfile {
+    /// Seconds between decisions.
+    pub think_interval: f32,
+    /// Zooids grown per decision, if there is enough food.
+    pub spawns_per_think: usize,
+    /// Zooids kept in arms before spawning free zooids.
+    pub max_linked: usize,
+    /// Fraction of new zooids that are shockers.
+    pub shocker_ratio: f32,
+    /// Minimum army size before attacking.
+    pub attack_size: usize,
+    /// Defend against enemies within this distance of a head.
+    pub defend_radius: f32,
+    /// Re-target the whole army on the weakest nearby enemy every decision.
+    pub focus_fire: bool,
+    /// Pull back units below this fraction of their health.
+    pub retreat_health: f32,
+}
+impl From<AiDifficulty> for AiProfile {
+    fn from(difficulty: AiDifficulty) -> Self {
+        match difficulty {
+            AiDifficulty::Easy => Self {
+                think_interval: 2.0,
+                spawns_per_think: 1,
+                max_linked: 30,
+                shocker_ratio: 0.0,
+                attack_size: 40,
+                defend_radius: 400.0,
+                focus_fire: false,
+                retreat_health: 0.0,
+            },
+            AiDifficulty::Normal => Self {
+                think_interval: 1.0,
+                spawns_per_think: 2,
+                max_linked: 70,
+                shocker_ratio: 0.1,
+                attack_size: 25,
+                defend_radius: 800.0,
+                focus_fire: false,
+                retreat_health: 0.0,
+            },
+            AiDifficulty::Hard | AiDifficulty::Expert => Self {
+                think_interval: 0.25,
+                spawns_per_think: 4,
+                max_linked: 40,
+                shocker_ratio: 0.25,
+                attack_size: 15,
+                defend_radius: 1200.0,
+                focus_fire: true,
+                retreat_health: 0.35,
+            },
+        }
+    }
+}
+
+/// Grows a colony around its head, defends it and attacks once the army is large enough.
+pub struct ColonyAi {
+    pub profile: AiProfile,
+    timer: Timer,
+    spawned: usize,
+    shockers: usize,
+}
+impl ColonyAi {
+    pub fn new(profile: AiProfile) -> Self {
+        Self {
+            timer: Timer::from_seconds(profile.think_interval, TimerMode::Repeating),
+            profile,
+            spawned: 0,
+            shockers: 0,
+        }
+    }
+
+    /// Spend the head's food on new zooids.
+    fn grow(&mut self, view: &AiView, head: &AiUnit, commands: &mut Vec<AiCommand>) {
+        let mut food = head.food;
+        let mut linked = view
+            .units
+            .iter()
+            .filter(|unit| unit.object != Object::HEAD && unit.attachments > 0)
+            .count();
+        for _ in 0..self.profile.spawns_per_think {
+            let object = if (self.shockers as f32)
+                < self.profile.shocker_ratio * (self.spawned + 1) as f32
+            {
+                Object::SHOCKER
+            } else {
+                Object::WORKER
+            };
+            let cost = view.food_cost(object);
+            if food < cost {
+                break;
+            }
+            food -= cost;
+            if linked < self.profile.max_linked {
+                linked += 1;
+                commands.push(AiCommand::Grow {
+                    head: head.entity,
+                    object,
+                });
+            } else {
+                commands.push(AiCommand::Spawn {
+                    head: head.entity,
+                    object,
+                });
+            }
+            self.spawned += 1;
+            if object == Object::SHOCKER {
+                self.shockers += 1;
+            }
+        }
+    }
+
+    /// Retreat, defend, attack or rally the army.
+    fn fight(&self, view: &AiView, head: &AiUnit, commands: &mut Vec<AiCommand>) {
+        let (hurt, army): (Vec<&AiUnit>, Vec<&AiUnit>) = view
+            .army()
+            .partition(|unit| view.health_fraction(unit) < self.profile.retreat_health);
+
+        let retreating: Vec<Entity> = hurt
+            .iter()
+            .filter(|unit| !matches!(unit.objective, Objective::FollowEntity(_)))
+            .map(|unit| unit.entity)
+            .collect();
+        if !retreating.is_empty() {
+            commands.push(AiCommand::Move {
+                units: retreating,
+                position: head.position,
+            });
+        }
+
+        // Only re-target units that are not busy, unless focusing fire.
+        let available: Vec<Entity> = army
+            .iter()
+            .filter(|unit| {
+                self.profile.focus_fire
+                    || matches!(unit.objective, Objective::Idle | Objective::FollowEntity(_))
+            })
+            .map(|unit| unit.entity)
+            .collect();
+        if available.is_empty() {
+            return;
+        }
+
+        let threat = view
+            .nearest_enemy(head.position)
+            .filter(|enemy| enemy.position.distance(head.position) < self.profile.defend_radius);
+        if let Some(threat) = threat {
+            commands.push(AiCommand::Attack {
+                units: available,
+                target: threat.entity,
+            });
+        } else if army.len() >= self.profile.attack_size {
+            let Some(target) = view
+                .enemies
+                .iter()
+                .copied()
+                .find(|enemy| enemy.object == Object::HEAD)
+                .or_else(|| view.nearest_enemy(head.position))
+            else {
+                return;
+            };
+            if self.profile.focus_fire {
+                let weakest = view
+                    .enemies
+                    .iter()
+                    .copied()
+                    .filter(|enemy| {
+                        enemy.position.distance(target.position) < self.profile.defend_radius
+                    })
+                    .min_by_key(|enemy| enemy.health)
+                    .unwrap_or(target);
+                commands.push(AiCommand::Attack {
+                    units: available,
+                    target: weakest.entity,
+                });
+            } else {
+                commands.push(AiCommand::AttackMove {
+                    units: available,
+                    position: target.position,
+                });
+            }
+        } else {
+            // Gather idle units near the head.
+            let idle: Vec<Entity> = army
+                .iter()
+                .filter(|unit| {
+                    unit.objective == Objective::Idle
+                        && unit.position.distance(head.position) > self.profile.defend_radius / 2.
+                })
+                .map(|unit| unit.entity)
+                .collect();
+            if !idle.is_empty() {
+                commands.push(AiCommand::Move {
+                    units: idle,
+                    position: head.position,
+                });
+            }
+        }
+    }
+}
+impl AiController for ColonyAi {
+    fn update(&mut self, view: &AiView, commands: &mut Vec<AiCommand>) {
+        self.timer.tick(Duration::from_secs_f32(view.delta));
+        if !self.timer.just_finished() {
+            return;
+        }
+        let Some(head) = view.heads().max_by_key(|head| head.food) else {
+            return;
+        };
+        self.grow(view, head, commands);
+        self.fight(view, head, commands);
+    }
+}
@@ -0,0 +1,365 @@
+use crate::prelude::*;
+use bevy::utils::HashSet;
+use sipho_core::grid::fog::TeamVisibility;
+
+/// Decision making for a computer player.
+/// Controllers only see the match through an `AiView` and act through the same commands as a player.
+pub trait AiController: Send + Sync + 'static {
+    fn update(&mut self, view: &AiView, commands: &mut Vec<AiCommand>);
+}
+
+/// State of an object as seen by a computer player.
+#[derive(Debug, Clone)]
+pub struct AiUnit {
+    pub entity: Entity,
+    pub object: Object,
+    pub team: Team,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub health: i32,
+    pub objective: Objective,
+    /// Number of objects this one is attached to.
+    pub attachments: usize,
+    /// Food stored by consumers.
+    pub food: usize,
+    /// Stealth state, if the object is hidden from enemies that don't detect it.
+    pub stealth: Option<Stealth>,
+}
+
+/// Read-only view of the match from one team's perspective.
+pub struct AiView<'a> {
+    pub team: Team,
+    /// Seconds since the last update.
+    pub delta: f32,
+    pub configs: &'a ObjectConfigs,
+    pub visibility: &'a Grid2<TeamVisibility>,
+    /// Objects a head can grow, from the head's controls.
+    pub buildable: Vec<Object>,
+    /// Objects owned by the team.
+    pub units: Vec<&'a AiUnit>,
+    /// Objects of other teams that are not hidden by fog or stealth.
+    pub enemies: Vec<&'a AiUnit>,
+    /// Neutral objects that are not hidden by fog.
+    pub neutrals: Vec<&'a AiUnit>,
+    /// Enemy heads and gemstones where the team last saw them.
+    pub ghosts: Vec<(Entity, &'a Ghost)>,
+}
+impl<'a> AiView<'a> {
+    pub fn new(
+        team: Team,
+        delta: f32,
+        units: &'a [AiUnit],
+        visibility: &'a Grid2<TeamVisibility>,
+        detection: &Grid2<TeamDetection>,
+        ghosts: &'a Ghosts,
+        configs: &'a ObjectConfigs,
+    ) -> Self {
+        let mut buildable: Vec<Object> = configs
+            .get(&Object::HEAD)
+            .map(|config| {
+                config
+                    .controls
+                    .values()
+                    .filter_map(|&action| ObjectBuilder::get_buildable_object(action))
+                    .collect()
+            })
+            .unwrap_or_default();
+        buildable.sort();
+        let mut view = Self {
+            team,
+            delta,
+            configs,
+            visibility,
+            buildable,
+            units: Vec::new(),
+            enemies: Vec::new(),
+            neutrals: Vec::new(),
+            ghosts: ghosts.team(team).collect(),
+        };
+        for unit in units {
+            if unit.team == team {
+                view.units.push(unit);
+                continue;
+            }
+            if !Self::can_see(
+                team,
+                unit.position,
+                unit.stealth.as_ref(),
+                visibility,
+                detection,
+            ) {
+                continue;
+            }
+            if unit.team == Team::None {
+                view.neutrals.push(unit);
+            } else {
+                view.enemies.push(unit);
+            }
+        }
+        view
+    }
+
+    /// True if the team can see an object at the position, through fog of war and stealth.
+    pub fn can_see(
+        team: Team,
+        position: Vec2,
+        stealth: Option<&Stealth>,
+        visibility: &Grid2<TeamVisibility>,
+        detection: &Grid2<TeamDetection>,
+    ) -> bool {
+        let Some(rowcol) = visibility.to_rowcol(position) else {
+            return false;
+        };
+        let hidden =
+            stealth.is_some_and(|stealth| !stealth.is_revealed_to(rowcol, team, detection));
+        visibility.is_visible(team, rowcol) && !hidden
+    }
+
+    /// True if the team can currently see the position.
+    pub fn is_visible(&self, position: Vec2) -> bool {
+        self.visibility
+            .to_rowcol(position)
+            .is_some_and(|rowcol| self.visibility.is_visible(self.team, rowcol))
+    }
+
+    /// True if the team has seen the position at some point.
+    pub fn is_explored(&self, position: Vec2) -> bool {
+        self.visibility
+            .to_rowcol(position)
+            .is_some_and(|rowcol| self.visibility.is_explored(rowcol, self.team))
+    }
+
+    /// Bounds of the playable world.
+    pub fn bounds(&self) -> Aabb2 {
+        self.visibility.world2d_bounds()
+    }
+
+    /// Food a head spends to grow the object. Unconfigured objects can't be afforded.
+    pub fn food_cost(&self, object: Object) -> usize {
+        self.configs
+            .get(&object)
+            .map_or(usize::MAX, |config| config.food_cost)
+    }
+
+    /// Rough fighting value of an object with the given health.
+    pub fn strength(&self, object: Object, health: i32) -> f32 {
+        let config = &self.configs[&object];
+        let damage = config
+            .interactions
+            .values()
+            .map(|interaction| interaction.damage_amount)
+            .max()
+            .unwrap_or_default();
+        health.max(0) as f32 * (1. + damage.max(0) as f32)
+    }
+
+    /// Heads owned by the team.
+    pub fn heads(&self) -> impl Iterator<Item = &'a AiUnit> + '_ {
+        self.units
+            .iter()
+            .copied()
+            .filter(|unit| unit.object == Object::HEAD)
+    }
+
+    /// Units that can attack and are not part of an arm.
+    pub fn army(&self) -> impl Iterator<Item = &'a AiUnit> + '_ {
+        self.units
+            .iter()
+            .copied()
+            .filter(|unit| unit.attachments == 0 && self.configs[&unit.object].can_attack())
+    }
+
+    /// Fraction of its maximum health the unit has left.
+    pub fn health_fraction(&self, unit: &AiUnit) -> f32 {
+        let max_health = self.configs[&unit.object].health.max(1);
+        unit.health as f32 / max_health as f32
+    }
+
+    /// Closest visible enemy to the position.
+    pub fn nearest_enemy(&self, position: Vec2) -> Option<&'a AiUnit> {
+        self.enemies.iter().copied().min_by(|a, b| {
+            let d1 = a.position.distance_squared(position);
+            let d2 = b.position.distance_squared(position);
+            d1.total_cmp(&d2)
+        })
+    }
+}
+
+/// Commands a computer player can issue, mirroring the player's controls.
+#[derive(Debug, Clone)]
+pub enum AiCommand {
+    /// Grow a zooid on the head's shortest arm, like `ControlAction::Grow`.
+    Grow { head: Entity, object: Object },
+    /// Spawn a free zooid next to the head.
+    Spawn { head: Entity, object: Object },
+    /// Move units to a position, like `ControlAction::Move`.
+    Move { units: Vec<Entity>, position: Vec2 },
+    /// Move units to a position while attacking enemies on the way, like `ControlAction::AttackMove`.
+    AttackMove { units: Vec<Entity>, position: Vec2 },
+    /// Attack an entity, like `ControlAction::Interact` on an enemy.
+    Attack { units: Vec<Entity>, target: Entity },
+    /// Clear the units' objectives.
+    Stop { units: Vec<Entity> },
+}
+
+/// A command issued by the computer player of a team.
+#[derive(Event, Debug)]
+pub struct AiCommandEvent {
+    pub team: Team,
+    pub command: AiCommand,
+}
+impl AiCommandEvent {
+    /// Apply commands to the team's objects, through the same functions as the player's controls.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
+        mut events: EventReader<Self>,
+        mut heads: Query<(&mut ZooidHead, &Team, &Velocity)>,
+        units: Query<(&Team, &Object, &AttachedTo, &Position)>,
+        targets: Query<(&Team, &Position, Option<&Stealth>)>,
+        attached_to: Query<&AttachedTo>,
+        positions: Query<&Position>,
+        mut objectives: Query<&mut Objectives>,
+        mut commands: ObjectCommands,
+        configs: Res<ObjectConfigs>,
+        obstacles: Res<Grid2<Obstacle>>,
+        formation: Res<Formation>,
+        visibility: Res<Grid2<TeamVisibility>>,
+        detection: Res<Grid2<TeamDetection>>,
+        mut elastic_events: EventWriter<SpawnElasticEvent>,
+        mut audio: EventWriter<AudioEvent>,
+    ) {
+        for event in events.read() {
+            let (objective, waypoint, position) = match &event.command {
+                AiCommand::Grow { head, object } | AiCommand::Spawn { head, object } => {
+                    let Ok((mut zooid_head, team, velocity)) = heads.get_mut(*head) else {
+                        continue;
+                    };
+                    if *team != event.team {
+                        continue;
+                    }
+                    if let AiCommand::Grow { .. } = event.command {
+                        zooid_head.grow(
+                            *head,
+                            *object,
+                            velocity,
+                            team,
+                            &attached_to,
+                            &positions,
+                            &configs,
+                            &mut commands,
+                            &mut elastic_events,
+                            &mut audio,
+                        );
+                    } else {
+                        ZooidHead::bud(
+                            *head,
+                            *object,
+                            velocity,
+                            team,
+                            &positions,
+                            &configs,
+                            &mut commands,
+                        );
+                    }
+                    continue;
+                }
+                AiCommand::Move { position, .. } | AiCommand::AttackMove { position, .. } => {
+                    // Don't spawn waypoints in obstacles.
+                    if !Waypoint::can_place(&obstacles, *position) {
+                        continue;
+                    }
+                    let waypoint = commands
+                        .commands
+                        .spawn((
+                            Name::new("AiWaypoint"),
+                            AiWaypoint,
+                            CarriedBy::default(),
+                            Position(*position),
+                        ))
+                        .id();
+                    let objective = if let AiCommand::Move { .. } = event.command {
+                        Objective::FollowEntity(waypoint)
+                    } else {
+                        Objective::AttackFollowEntity(waypoint)
+                    };
+                    (objective, Some(waypoint), *position)
+                }
+                AiCommand::Attack { target, .. } => {
+                    let Ok((&team, position, stealth)) = targets.get(*target) else {
+                        continue;
+                    };
+                    // Controllers only get to attack what `AiView` shows them.
+                    if team != event.team
+                        && !AiView::can_see(
+                            event.team,
+                            position.0,
+                            stealth,
+                            &visibility,
+                            &detection,
+                        )
+                    {
+                        continue;
+                    }
+                    (Objective::AttackFollowEntity(*target), None, position.0)
+                }
+                AiCommand::Stop { .. } => (Objective::Idle, None, Vec2::ZERO),
+            };
+
+            let (AiCommand::Move {
+                units: entities, ..
+            }
+            | AiCommand::AttackMove {
+                units: entities, ..
+            }
+            | AiCommand::Attack {
+                units: entities, ..
+            }
+            | AiCommand::Stop { units: entities }) = &event.command
+            else {
+                continue;
+            };
+            Waypoint::order(
+                entities.iter().filter_map(|&entity| {
+                    let (&team, &object, attached_to, position) = units.get(entity).ok()?;
+                    (team == event.team).then_some((entity, object, attached_to.len(), position.0))
+                }),
+                &objective,
+                waypoint,
+                position,
+                *formation,
+                &mut objectives,
+                &obstacles,
+                &mut commands.commands,
+            );
+        }
+    }
+}
+
+/// Invisible waypoint followed by computer controlled units.
+#[derive(Component, Debug)]
+pub struct AiWaypoint;
+impl AiWaypoint {
+    /// Despawn waypoints that are no longer followed.
+    pub fn cleanup(
+        all_objectives: Query<&Objectives>,
+        waypoints: Query<Entity, With<AiWaypoint>>,
+        mut commands: Commands,
+    ) {
+        if waypoints.is_empty() {
+            return;
+        }
+        let mut followed_entities = HashSet::new();
+        for objectives in all_objectives.iter() {
+            for objective in objectives.iter() {
+                if let Some(entity) = objective.get_followed_entity() {
+                    followed_entities.insert(entity);
+                }
+            }
+        }
+        for entity in waypoints.iter() {
+            if !followed_entities.contains(&entity) {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
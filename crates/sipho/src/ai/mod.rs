@@ -0,0 +1,165 @@
+use crate::prelude::*;
+use bevy::utils::HashMap;
+use sipho_core::grid::fog::TeamVisibility;
+
+mod colony;
+mod controller;
+mod strategic;
+
+pub use {
+    colony::{AiProfile, ColonyAi},
+    controller::{AiCommand, AiCommandEvent, AiController, AiUnit, AiView, AiWaypoint},
+    strategic::{StrategicAi, StrategicProfile},
+};
+
+/// Plugin for computer controlled teams.
+pub struct AiPlugin;
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AiDifficulty>()
+            .register_type::<Option<AiDifficulty>>()
+            .register_type::<HashMap<Team, AiDifficulty>>()
+            .register_type::<AiSettings>()
+            .init_resource::<AiSettings>()
+            .init_resource::<AiPlayers>()
+            .add_event::<AiCommandEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    (AiPlayers::update, AiCommandEvent::update)
+                        .chain()
+                        .in_set(FixedUpdateStage::AI),
+                    AiWaypoint::cleanup.in_set(FixedUpdateStage::Cleanup),
+                )
+                    .in_set(GameStateSet::Running),
+            );
+    }
+}
+
+/// Difficulty of a computer player.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Reflect, Debug, clap::ValueEnum)]
+pub enum AiDifficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    /// Scouts, harvests and attacks in waves.
+    Expert,
+}
+impl AiDifficulty {
+    /// Create the controller for this difficulty.
+    pub fn controller(self) -> Box<dyn AiController> {
+        match self {
+            Self::Expert => Box::new(StrategicAi::default()),
+            _ => Box::new(ColonyAi::new(AiProfile::from(self))),
+        }
+    }
+}
+
+/// Which teams are controlled by the computer. Set up per match.
+#[derive(Resource, Clone, Default, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct AiSettings {
+    pub teams: HashMap<Team, AiDifficulty>,
+}
+
+/// Running controller for each computer team.
+#[derive(Resource, Default)]
+pub struct AiPlayers {
+    controllers: HashMap<Team, Box<dyn AiController>>,
+}
+impl AiPlayers {
+    /// Control a team with a custom controller.
+    /// Replaced with the configured difficulty when `AiSettings` changes.
+    pub fn insert(&mut self, team: Team, controller: Box<dyn AiController>) {
+        self.controllers.insert(team, controller);
+    }
+
+    /// Let each controller decide on commands for its team.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
+        mut players: ResMut<Self>,
+        settings: Res<AiSettings>,
+        query: Query<(
+            Entity,
+            &Object,
+            &Team,
+            &Position,
+            &Velocity,
+            &Objectives,
+            &AttachedTo,
+            Option<&Health>,
+            Option<&Consumer>,
+            Option<&Stealth>,
+        )>,
+        visibility: Res<Grid2<TeamVisibility>>,
+        detection: Res<Grid2<TeamDetection>>,
+        ghosts: Res<Ghosts>,
+        configs: Res<ObjectConfigs>,
+        time: Res<Time>,
+        mut events: EventWriter<AiCommandEvent>,
+    ) {
+        if settings.is_changed() {
+            players.controllers = settings
+                .teams
+                .iter()
+                .map(|(&team, difficulty)| (team, difficulty.controller()))
+                .collect();
+        }
+        if players.controllers.is_empty() {
+            return;
+        }
+
+        let units: Vec<AiUnit> = query
+            .iter()
+            .map(
+                |(
+                    entity,
+                    &object,
+                    &team,
+                    position,
+                    velocity,
+                    objectives,
+                    attached_to,
+                    health,
+                    consumer,
+                    stealth,
+                )| {
+                    AiUnit {
+                        entity,
+                        object,
+                        team,
+                        position: position.0,
+                        velocity: velocity.0,
+                        health: health.map(|health| health.health).unwrap_or_default(),
+                        objective: objectives.last().clone(),
+                        attachments: attached_to.len(),
+                        food: consumer
+                            .map(|consumer| consumer.food_consumed())
+                            .unwrap_or_default(),
+                        stealth: stealth.cloned(),
+                    }
+                },
+            )
+            .collect();
+
+        let mut commands = Vec::new();
+        for (&team, controller) in players.controllers.iter_mut() {
+            let view = AiView::new(
+                team,
+                time.delta_seconds(),
+                &units,
+                &visibility,
+                &detection,
+                &ghosts,
+                &configs,
+            );
+            controller.update(&view, &mut commands);
+            events.send_batch(
+                commands
+                    .drain(..)
+                    .map(|command| AiCommandEvent { team, command }),
+            );
+        }
+    }
+}
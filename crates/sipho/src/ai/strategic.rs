@@ -0,0 +1,465 @@
+use std::time::Duration;
+
+use crate::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+
+use super::{AiCommand, AiController, AiUnit, AiView};
+
+/// Number of sectors along each axis used to plan scouting.
+const SECTORS: usize = 8;
+
+/// An object the AI saw at some point.
+#[derive(Debug, Clone)]
+struct Sighting {
+    object: Object,
+    team: Team,
+    position: Vec2,
+    health: i32,
+    /// Time of the last sighting.
+    seen: f32,
+}
+
+/// Tuning for `StrategicAi`.
+#[derive(Clone, Debug)]
+pub struct StrategicProfile {
+    /// Seconds between decisions.
+    pub think_interval: f32,
+    /// Zooids kept in arms to protect the head.
+    pub max_linked: usize,
+    /// Free zooids sent to break plankton and gemstones.
+    pub harvesters: usize,
+    /// Distance from the head to look for resources.
+    pub harvest_radius: f32,
+    /// Defend against enemies within this distance of a head.
+    pub defend_radius: f32,
+    /// Attack once our strength exceeds the enemy's by this factor.
+    pub attack_margin: f32,
+    /// Minimum number of units in a wave.
+    pub min_wave_size: usize,
+    /// Pull back units below this fraction of their health.
+    pub retreat_health: f32,
+    /// Seconds before a sighting is forgotten.
+    pub memory: f32,
+}
+impl Default for StrategicProfile {
+    fn default() -> Self {
+        Self {
+            think_interval: 0.5,
+            max_linked: 20,
+            harvesters: 4,
+            harvest_radius: 1500.0,
+            defend_radius: 800.0,
+            attack_margin: 1.25,
+            min_wave_size: 8,
+            retreat_health: 0.4,
+            memory: 60.0,
+        }
+    }
+}
+
+/// Expands its economy, scouts the map and attacks in waves once it is stronger than the enemy.
+pub struct StrategicAi {
+    pub profile: StrategicProfile,
+    timer: Timer,
+    elapsed: f32,
+    /// Enemies and resources seen through the fog.
+    sightings: HashMap<Entity, Sighting>,
+    /// Last time each sector was visited by one of our units.
+    explored: Vec<f32>,
+    scout: Option<Entity>,
+    scout_target: Option<usize>,
+    harvesters: HashSet<Entity>,
+    /// Head steered over food, and the food it was sent to.
+    harvest_target: Option<(Entity, Entity)>,
+    wave: HashSet<Entity>,
+    /// Number of each object grown so far, counted from units that appeared.
+    built: HashMap<Object, usize>,
+    /// Units owned at the last think, or `None` before the first one.
+    owned: Option<HashSet<Entity>>,
+}
+impl Default for StrategicAi {
+    fn default() -> Self {
+        Self::new(StrategicProfile::default())
+    }
+}
+impl StrategicAi {
+    pub fn new(profile: StrategicProfile) -> Self {
+        Self {
+            timer: Timer::from_seconds(profile.think_interval, TimerMode::Repeating),
+            profile,
+            elapsed: 0.,
+            sightings: HashMap::default(),
+            explored: vec![f32::NEG_INFINITY; SECTORS * SECTORS],
+            scout: None,
+            scout_target: None,
+            harvesters: HashSet::default(),
+            harvest_target: None,
+            wave: HashSet::default(),
+            built: HashMap::default(),
+            owned: None,
+        }
+    }
+
+    fn sector(bounds: &Aabb2, position: Vec2) -> usize {
+        let uv = (position - bounds.min) / (bounds.max - bounds.min);
+        let col = ((uv.x * SECTORS as f32) as usize).min(SECTORS - 1);
+        let row = ((uv.y * SECTORS as f32) as usize).min(SECTORS - 1);
+        row * SECTORS + col
+    }
+
+    fn sector_center(bounds: &Aabb2, sector: usize) -> Vec2 {
+        let (row, col) = (sector / SECTORS, sector % SECTORS);
+        let uv = (Vec2::new(col as f32, row as f32) + 0.5) / SECTORS as f32;
+        bounds.min + uv * (bounds.max - bounds.min)
+    }
+
+    /// Update what we know about the map from what is currently visible.
+    fn observe(&mut self, view: &AiView) {
+        let bounds = view.bounds();
+        for unit in view.units.iter() {
+            self.explored[Self::sector(&bounds, unit.position)] = self.elapsed;
+        }
+        for unit in view.enemies.iter().chain(view.neutrals.iter()) {
+            self.sightings.insert(
+                unit.entity,
+                Sighting {
+                    object: unit.object,
+                    team: unit.team,
+                    position: unit.position,
+                    health: unit.health,
+                    seen: self.elapsed,
+                },
+            );
+        }
+        // Forget objects that are gone from where we last saw them, or that we haven't seen in a while.
+        let elapsed = self.elapsed;
+        let memory = self.profile.memory;
+        self.sightings.retain(|_, sighting| {
+            sighting.seen == elapsed
+                || (elapsed - sighting.seen < memory && !view.is_visible(sighting.position))
+        });
+
+        // Count what was actually grown, since queued commands can fail.
+        if let Some(owned) = &self.owned {
+            for unit in view
+                .units
+                .iter()
+                .filter(|unit| !owned.contains(&unit.entity))
+            {
+                *self.built.entry(unit.object).or_default() += 1;
+            }
+        }
+        self.owned = Some(view.units.iter().map(|unit| unit.entity).collect());
+
+        let alive: HashMap<Entity, &AiUnit> =
+            view.units.iter().map(|&unit| (unit.entity, unit)).collect();
+        self.harvesters.retain(|entity| alive.contains_key(entity));
+        // Units leave the wave once they are done fighting.
+        self.wave.retain(|entity| {
+            alive
+                .get(entity)
+                .is_some_and(|unit| unit.objective != Objective::Idle)
+        });
+        if self.scout.is_some_and(|scout| !alive.contains_key(&scout)) {
+            self.scout = None;
+            self.scout_target = None;
+        }
+    }
+
+    /// Grow the buildable object we have invested the least food in.
+    fn grow(&mut self, view: &AiView, head: &AiUnit, commands: &mut Vec<AiCommand>) {
+        let mut food = head.food;
+        // Objects queued this think count towards the balance until they show up as units.
+        let mut planned = self.built.clone();
+        let mut linked = view
+            .units
+            .iter()
+            .filter(|unit| unit.object != Object::HEAD && unit.attachments > 0)
+            .count();
+        loop {
+            let Some(object) = view.buildable.iter().copied().min_by_key(|object| {
+                (planned.get(object).copied().unwrap_or_default() + 1)
+                    .saturating_mul(view.food_cost(*object))
+            }) else {
+                return;
+            };
+            let cost = view.food_cost(object);
+            if food < cost {
+                return;
+            }
+            food -= cost;
+            *planned.entry(object).or_default() += 1;
+            if linked < self.profile.max_linked {
+                linked += 1;
+                commands.push(AiCommand::Grow {
+                    head: head.entity,
+                    object,
+                });
+            } else {
+                commands.push(AiCommand::Spawn {
+                    head: head.entity,
+                    object,
+                });
+            }
+        }
+    }
+
+    /// Break nearby objects that drop food, like plankton and gemstones, and steer the head over the food.
+    fn harvest(
+        &mut self,
+        view: &AiView,
+        head: &AiUnit,
+        free: &[&AiUnit],
+        commands: &mut Vec<AiCommand>,
+    ) {
+        let nearest = |is_target: fn(&ObjectConfig) -> bool| {
+            // Gemstones are remembered as ghosts long after sightings are forgotten.
+            let sightings = self
+                .sightings
+                .iter()
+                .map(|(&entity, sighting)| (entity, sighting.object, sighting.position));
+            let ghosts = view
+                .ghosts
+                .iter()
+                .map(|&(entity, ghost)| (entity, ghost.object, ghost.position));
+            sightings
+                .chain(ghosts)
+                .filter(|(_, object, _)| view.configs.get(object).is_some_and(is_target))
+                .filter(|(_, _, position)| {
+                    position.distance(head.position) < self.profile.harvest_radius
+                })
+                .min_by(|(_, _, a), (_, _, b)| {
+                    let d1 = a.distance_squared(head.position);
+                    let d2 = b.distance_squared(head.position);
+                    d1.total_cmp(&d2)
+                })
+                .map(|(entity, _, position)| (entity, position))
+        };
+
+        // Only steer the head again once it has a new target, or it stopped short of the old one.
+        let food = nearest(|config| config.edible);
+        let target = food.map(|(entity, _)| (head.entity, entity));
+        if let Some((_, position)) = food {
+            if target != self.harvest_target || head.objective == Objective::Idle {
+                commands.push(AiCommand::Move {
+                    units: vec![head.entity],
+                    position,
+                });
+            }
+        }
+        self.harvest_target = target;
+
+        let Some((source, _)) = nearest(|config| !config.drops.is_empty()) else {
+            return;
+        };
+        let recruits: Vec<Entity> = free
+            .iter()
+            .filter(|unit| unit.object == Object::WORKER)
+            .filter(|unit| !self.wave.contains(&unit.entity) && Some(unit.entity) != self.scout)
+            .filter(|unit| !self.harvesters.contains(&unit.entity))
+            .take(
+                self.profile
+                    .harvesters
+                    .saturating_sub(self.harvesters.len()),
+            )
+            .map(|unit| unit.entity)
+            .collect();
+        self.harvesters.extend(recruits);
+        let idle: Vec<Entity> = free
+            .iter()
+            .filter(|unit| self.harvesters.contains(&unit.entity))
+            .filter(|unit| {
+                !matches!(
+                    unit.objective,
+                    Objective::AttackEntity(_) | Objective::AttackFollowEntity(_)
+                )
+            })
+            .map(|unit| unit.entity)
+            .collect();
+        if !idle.is_empty() {
+            commands.push(AiCommand::Attack {
+                units: idle,
+                target: source,
+            });
+        }
+    }
+
+    /// Send a scout to unexplored sectors first, then the sector we have the oldest knowledge of.
+    fn scout(&mut self, view: &AiView, free: &[&AiUnit], commands: &mut Vec<AiCommand>) {
+        if self.scout.is_none() {
+            self.scout = free
+                .iter()
+                .find(|unit| {
+                    !self.wave.contains(&unit.entity) && !self.harvesters.contains(&unit.entity)
+                })
+                .map(|unit| unit.entity);
+        }
+        let Some(scout) = self
+            .scout
+            .and_then(|scout| free.iter().find(|unit| unit.entity == scout))
+        else {
+            return;
+        };
+        let bounds = view.bounds();
+        let arrived = self
+            .scout_target
+            .is_some_and(|target| Self::sector(&bounds, scout.position) == target);
+        if self.scout_target.is_some() && !arrived && scout.objective != Objective::Idle {
+            return;
+        }
+        let is_explored = |sector| view.is_explored(Self::sector_center(&bounds, sector));
+        let target = (0..self.explored.len())
+            .min_by(|&a, &b| {
+                is_explored(a)
+                    .cmp(&is_explored(b))
+                    .then_with(|| self.explored[a].total_cmp(&self.explored[b]))
+                    .then_with(|| {
+                        let d1 = Self::sector_center(&bounds, a).distance_squared(scout.position);
+                        let d2 = Self::sector_center(&bounds, b).distance_squared(scout.position);
+                        d1.total_cmp(&d2)
+                    })
+            })
+            .unwrap();
+        self.scout_target = Some(target);
+        commands.push(AiCommand::Move {
+            units: vec![scout.entity],
+            position: Self::sector_center(&bounds, target),
+        });
+    }
+
+    /// Retreat damaged units, defend the head and attack in waves.
+    fn fight(
+        &mut self,
+        view: &AiView,
+        head: &AiUnit,
+        free: &[&AiUnit],
+        commands: &mut Vec<AiCommand>,
+    ) {
+        let (hurt, army): (Vec<&AiUnit>, Vec<&AiUnit>) = free
+            .iter()
+            .copied()
+            .filter(|unit| view.configs[&unit.object].can_attack())
+            .filter(|unit| {
+                Some(unit.entity) != self.scout && !self.harvesters.contains(&unit.entity)
+            })
+            .partition(|unit| view.health_fraction(unit) < self.profile.retreat_health);
+
+        let retreating: Vec<Entity> = hurt
+            .iter()
+            .filter(|unit| unit.position.distance(head.position) > self.profile.defend_radius / 2.)
+            .filter(|unit| !matches!(unit.objective, Objective::FollowEntity(_)))
+            .map(|unit| unit.entity)
+            .collect();
+        for entity in retreating.iter() {
+            self.wave.remove(entity);
+        }
+        if !retreating.is_empty() {
+            commands.push(AiCommand::Move {
+                units: retreating,
+                position: head.position,
+            });
+        }
+
+        // Defend the head with everything that is not already in a wave.
+        let threat = view
+            .nearest_enemy(head.position)
+            .filter(|enemy| enemy.position.distance(head.position) < self.profile.defend_radius);
+        if let Some(threat) = threat.map(|enemy| enemy.entity) {
+            let defenders: Vec<Entity> = army
+                .iter()
+                .chain(hurt.iter())
+                .filter(|unit| !self.wave.contains(&unit.entity))
+                .filter(|unit| unit.objective != Objective::AttackEntity(threat))
+                .map(|unit| unit.entity)
+                .collect();
+            if !defenders.is_empty() {
+                commands.push(AiCommand::Attack {
+                    units: defenders,
+                    target: threat,
+                });
+            }
+            return;
+        }
+
+        // Launch a wave once the rest of the army outnumbers what we know of the enemy.
+        if !self.wave.is_empty() {
+            return;
+        }
+        let own_strength: f32 = army
+            .iter()
+            .map(|unit| view.strength(unit.object, unit.health))
+            .sum();
+        let enemies: Vec<(&Entity, &Sighting)> = self
+            .sightings
+            .iter()
+            .filter(|(_, sighting)| sighting.team != Team::None)
+            .collect();
+        let enemy_strength: f32 = enemies
+            .iter()
+            .map(|(_, sighting)| view.strength(sighting.object, sighting.health))
+            .sum();
+        if army.len() < self.profile.min_wave_size
+            || own_strength <= enemy_strength * self.profile.attack_margin
+        {
+            return;
+        }
+        let target = enemies
+            .iter()
+            .find(|(_, sighting)| sighting.object == Object::HEAD)
+            .or_else(|| {
+                enemies.iter().min_by(|(_, a), (_, b)| {
+                    let d1 = a.position.distance_squared(head.position);
+                    let d2 = b.position.distance_squared(head.position);
+                    d1.total_cmp(&d2)
+                })
+            });
+        let Some((&target, _)) = target else {
+            // Head for where an enemy head was last seen.
+            let ghost = view
+                .ghosts
+                .iter()
+                .filter(|(_, ghost)| ghost.object == Object::HEAD)
+                .min_by(|(_, a), (_, b)| {
+                    let d1 = a.position.distance_squared(head.position);
+                    let d2 = b.position.distance_squared(head.position);
+                    d1.total_cmp(&d2)
+                });
+            if let Some((_, ghost)) = ghost {
+                self.wave = army.iter().map(|unit| unit.entity).collect();
+                commands.push(AiCommand::AttackMove {
+                    units: self.wave.iter().copied().collect(),
+                    position: ghost.position,
+                });
+            }
+            return;
+        };
+        self.wave = army.iter().map(|unit| unit.entity).collect();
+        commands.push(AiCommand::Attack {
+            units: self.wave.iter().copied().collect(),
+            target,
+        });
+    }
+}
+impl AiController for StrategicAi {
+    fn update(&mut self, view: &AiView, commands: &mut Vec<AiCommand>) {
+        self.elapsed += view.delta;
+        self.timer.tick(Duration::from_secs_f32(view.delta));
+        if !self.timer.just_finished() {
+            return;
+        }
+        self.observe(view);
+        let Some(head) = view.heads().max_by_key(|head| head.food) else {
+            return;
+        };
+        let free: Vec<&AiUnit> = view
+            .units
+            .iter()
+            .copied()
+            .filter(|unit| unit.object != Object::HEAD && unit.attachments == 0)
+            .collect();
+        self.grow(view, head, commands);
+        self.harvest(view, head, &free, commands);
+        self.scout(view, &free, commands);
+        self.fight(view, head, &free, commands);
+    }
+}
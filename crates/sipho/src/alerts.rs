@@ -0,0 +1,296 @@
+use std::collections::VecDeque;
+
+use crate::prelude::*;
+use bevy::{input::ButtonState, utils::HashMap};
+
+/// Plugin for alerting the player to events they might not see.
+pub struct AlertsPlugin;
+impl Plugin for AlertsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Alerts>()
+            .add_event::<AlertEvent>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        AlertEvent::on_damage,
+                        AlertEvent::on_death,
+                        AlertEvent::on_snap,
+                        AlertEvent::on_idle_heads,
+                    ),
+                    Alerts::update,
+                    Alerts::jump_to_alert,
+                )
+                    .chain()
+                    .before(CameraController::update_screen_control)
+                    .in_set(GameStateSet::Running),
+            );
+    }
+}
+
+/// Seconds between checks for idle heads.
+pub const IDLE_CHECK_SECONDS: f32 = 5.;
+/// Food an idle head holds before the player is alerted.
+pub const IDLE_FOOD: usize = 10;
+/// Seconds a notification is shown and the minimap pinged.
+pub const ALERT_SECONDS: f32 = 4.;
+/// Number of notifications shown at once.
+pub const MAX_NOTIFICATIONS: usize = 3;
+
+/// Kinds of events the player is alerted to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    /// Units are damaged outside the camera view.
+    UnderAttack,
+    /// A head died.
+    HeadLost,
+    /// A head is idle while holding food.
+    IdleHead,
+    /// An elastic was stretched until it snapped.
+    ElasticSnapped,
+}
+impl AlertKind {
+    /// Minimum seconds between two alerts of the kind.
+    pub fn throttle(self) -> f32 {
+        match self {
+            Self::UnderAttack => 10.,
+            Self::HeadLost => 2.,
+            Self::IdleHead => 30.,
+            Self::ElasticSnapped => 10.,
+        }
+    }
+
+    /// Cue played when the alert is raised.
+    pub fn sample(self) -> AudioSample {
+        match self {
+            Self::UnderAttack => AudioSample::Punch,
+            Self::HeadLost => AudioSample::RandomZap,
+            Self::IdleHead => AudioSample::RandomBubble,
+            Self::ElasticSnapped => AudioSample::Snap,
+        }
+    }
+
+    /// Notification text, used as a localization label.
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::UnderAttack => "Units under attack",
+            Self::HeadLost => "Head lost",
+            Self::IdleHead => "Idle head has food",
+            Self::ElasticSnapped => "Elastic snapped",
+        }
+    }
+}
+
+/// Sent when something happens that the player should know about.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct AlertEvent {
+    pub kind: AlertKind,
+    pub position: Vec2,
+}
+impl AlertEvent {
+    /// Alert when the player's units are damaged outside the camera view.
+    pub fn on_damage(
+        mut damages: EventReader<DamageEvent>,
+        damaged: Query<(&Team, &Position)>,
+        camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+        team_config: Res<TeamConfig>,
+        mut alerts: EventWriter<AlertEvent>,
+    ) {
+        let Ok((camera, camera_transform)) = camera.get_single() else {
+            return;
+        };
+        for event in damages.read() {
+            let Ok((&team, position)) = damaged.get(event.damaged) else {
+                continue;
+            };
+            if event.amount <= 0 || team != team_config.player_team {
+                continue;
+            }
+            let on_screen = camera
+                .world_to_ndc(camera_transform, position.extend(0.))
+                .is_some_and(|ndc| ndc.x.abs() <= 1. && ndc.y.abs() <= 1.);
+            if !on_screen {
+                alerts.send(AlertEvent {
+                    kind: AlertKind::UnderAttack,
+                    position: position.0,
+                });
+            }
+        }
+    }
+
+    /// Alert when one of the player's heads dies.
+    pub fn on_death(
+        mut deaths: EventReader<DeathEvent>,
+        team_config: Res<TeamConfig>,
+        mut alerts: EventWriter<AlertEvent>,
+    ) {
+        for death in deaths.read() {
+            if death.object == Object::HEAD && death.team == team_config.player_team {
+                alerts.send(AlertEvent {
+                    kind: AlertKind::HeadLost,
+                    position: death.position.0,
+                });
+            }
+        }
+    }
+
+    /// Alert when one of the player's elastics snaps.
+    pub fn on_snap(
+        mut snaps: EventReader<ElasticSnapEvent>,
+        teams: Query<&Team>,
+        team_config: Res<TeamConfig>,
+        mut alerts: EventWriter<AlertEvent>,
+    ) {
+        for snap in snaps.read() {
+            if teams
+                .get(snap.entities.0)
+                .is_ok_and(|&team| team == team_config.player_team)
+            {
+                alerts.send(AlertEvent {
+                    kind: AlertKind::ElasticSnapped,
+                    position: snap.position,
+                });
+            }
+        }
+    }
+
+    /// Periodically alert when the player's heads hold food without doing anything.
+    pub fn on_idle_heads(
+        mut alerts_state: ResMut<Alerts>,
+        heads: Query<(&Team, &Position, &Objectives, &Consumer), With<ZooidHead>>,
+        team_config: Res<TeamConfig>,
+        time: Res<Time>,
+        mut alerts: EventWriter<AlertEvent>,
+    ) {
+        if !alerts_state.idle_timer.tick(time.delta()).just_finished() {
+            return;
+        }
+        for (&team, position, objectives, consumer) in &heads {
+            if team == team_config.player_team
+                && *objectives.last() == Objective::Idle
+                && consumer.food_consumed() >= IDLE_FOOD
+            {
+                alerts.send(AlertEvent {
+                    kind: AlertKind::IdleHead,
+                    position: position.0,
+                });
+            }
+        }
+    }
+}
+
+/// An alert raised for the player.
+#[derive(Clone, Copy, Debug)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub position: Vec2,
+    /// Seconds since the alert was raised.
+    pub age: f32,
+}
+impl Alert {
+    /// Fraction of the alert's ping left, from one when raised to zero.
+    pub fn ping_strength(&self) -> f32 {
+        (1. - self.age / ALERT_SECONDS).max(0.)
+    }
+}
+
+/// Alerts raised for the player, throttled per kind.
+#[derive(Resource)]
+pub struct Alerts {
+    /// Most recent alerts, newest last.
+    pub notifications: VecDeque<Alert>,
+    /// Where the camera jumps to.
+    pub latest: Option<Alert>,
+    /// Seconds since each kind of alert was last raised.
+    since_raised: HashMap<AlertKind, f32>,
+    idle_timer: Timer,
+}
+impl Default for Alerts {
+    fn default() -> Self {
+        Self {
+            notifications: VecDeque::new(),
+            latest: None,
+            since_raised: HashMap::new(),
+            idle_timer: Timer::from_seconds(IDLE_CHECK_SECONDS, TimerMode::Repeating),
+        }
+    }
+}
+impl Alerts {
+    /// Raise alerts that aren't throttled, playing their cue.
+    pub fn update(
+        mut alerts: ResMut<Self>,
+        mut events: EventReader<AlertEvent>,
+        mut audio: EventWriter<AudioEvent>,
+        team_config: Res<TeamConfig>,
+        time: Res<Time>,
+    ) {
+        let alerts = &mut *alerts;
+        let delta = time.delta_seconds();
+        for since_raised in alerts.since_raised.values_mut() {
+            *since_raised += delta;
+        }
+        for alert in alerts
+            .notifications
+            .iter_mut()
+            .chain(alerts.latest.as_mut())
+        {
+            alert.age += delta;
+        }
+        alerts
+            .notifications
+            .retain(|alert| alert.age < ALERT_SECONDS);
+
+        // Observers don't control a team to be alerted about.
+        if team_config.observer {
+            events.clear();
+            return;
+        }
+        for &AlertEvent { kind, position } in events.read() {
+            if alerts
+                .since_raised
+                .get(&kind)
+                .is_some_and(|&since_raised| since_raised < kind.throttle())
+            {
+                continue;
+            }
+            alerts.since_raised.insert(kind, 0.);
+            let alert = Alert {
+                kind,
+                position,
+                age: 0.,
+            };
+            alerts.latest = Some(alert);
+            alerts.notifications.push_back(alert);
+            if alerts.notifications.len() > MAX_NOTIFICATIONS {
+                alerts.notifications.pop_front();
+            }
+            audio.send(AudioEvent {
+                sample: kind.sample(),
+                ..default()
+            });
+        }
+    }
+
+    /// Move the camera to the latest alert.
+    pub fn jump_to_alert(
+        alerts: Res<Self>,
+        mut controls: EventReader<ControlEvent>,
+        mut camera: Query<(&CameraController, &mut Transform), With<MainCamera>>,
+        mut camera_moves: EventWriter<CameraMoveEvent>,
+    ) {
+        let jump = controls.read().any(|control| {
+            control.action == ControlAction::JumpToAlert && control.state == ButtonState::Pressed
+        });
+        let (true, Some(alert), Ok((controller, mut transform))) =
+            (jump, alerts.latest, camera.get_single_mut())
+        else {
+            return;
+        };
+        // The camera is tilted, so it sits below what it looks at.
+        let position = alert.position - Vec2::Y * MainCamera::y_offset(transform.translation.z);
+        controller.set_position(&mut transform, position);
+        camera_moves.send(CameraMoveEvent {
+            position: transform.translation,
+        });
+    }
+}
@@ -0,0 +1,176 @@
+use rand::Rng;
+
+use super::{BehaviorContext, BehaviorLeaf, BehaviorStatus, Blackboard, BlackboardValue};
+use crate::prelude::*;
+
+/// Blackboard key for the entity being hunted or fled from.
+pub const TARGET: &str = "target";
+/// Blackboard key for the last known position of `TARGET`.
+pub const TARGET_POSITION: &str = "target_position";
+/// Blackboard key for the position patrols are centered on.
+pub const HOME: &str = "home";
+/// Blackboard key for the current patrol destination.
+pub const PATROL_POINT: &str = "patrol_point";
+
+fn status(condition: bool) -> BehaviorStatus {
+    if condition {
+        BehaviorStatus::Success
+    } else {
+        BehaviorStatus::Failure
+    }
+}
+
+/// Succeeds if an enemy is within the radius, storing it as the target.
+pub struct EnemyInRange(pub f32);
+impl BehaviorLeaf for EnemyInRange {
+    fn tick(
+        &mut self,
+        context: &mut BehaviorContext,
+        blackboard: &mut Blackboard,
+    ) -> BehaviorStatus {
+        // Neighbors are sorted by distance.
+        let Some(enemy) = context
+            .enemies
+            .first()
+            .filter(|enemy| enemy.distance_squared < self.0 * self.0)
+        else {
+            blackboard.remove(TARGET);
+            blackboard.remove(TARGET_POSITION);
+            return BehaviorStatus::Failure;
+        };
+        blackboard.set(TARGET, BlackboardValue::Entity(enemy.entity));
+        blackboard.set(
+            TARGET_POSITION,
+            BlackboardValue::Vec2(context.position + enemy.delta),
+        );
+        BehaviorStatus::Success
+    }
+}
+
+/// Succeeds if the entity has less than this fraction of its health.
+pub struct HealthBelow(pub f32);
+impl BehaviorLeaf for HealthBelow {
+    fn tick(
+        &mut self,
+        context: &mut BehaviorContext,
+        _blackboard: &mut Blackboard,
+    ) -> BehaviorStatus {
+        status(context.health < self.0)
+    }
+}
+
+/// Succeeds while the entity's attacks are on cooldown.
+pub struct OnCooldown;
+impl BehaviorLeaf for OnCooldown {
+    fn tick(
+        &mut self,
+        context: &mut BehaviorContext,
+        _blackboard: &mut Blackboard,
+    ) -> BehaviorStatus {
+        status(context.on_cooldown)
+    }
+}
+
+/// Succeeds if the entity is not attached to anything.
+pub struct Detached;
+impl BehaviorLeaf for Detached {
+    fn tick(
+        &mut self,
+        context: &mut BehaviorContext,
+        _blackboard: &mut Blackboard,
+    ) -> BehaviorStatus {
+        status(context.attachments == 0)
+    }
+}
+
+/// Attack the target.
+pub struct AttackTarget;
+impl BehaviorLeaf for AttackTarget {
+    fn tick(
+        &mut self,
+        context: &mut BehaviorContext,
+        blackboard: &mut Blackboard,
+    ) -> BehaviorStatus {
+        let Some(target) = blackboard.get_entity(TARGET) else {
+            return BehaviorStatus::Failure;
+        };
+        context.push_objective(Objective::AttackEntity(target));
+        BehaviorStatus::Success
+    }
+}
+
+/// Move away from the target.
+pub struct Flee {
+    pub force: f32,
+}
+impl BehaviorLeaf for Flee {
+    fn tick(
+        &mut self,
+        context: &mut BehaviorContext,
+        blackboard: &mut Blackboard,
+    ) -> BehaviorStatus {
+        let Some(target_position) = blackboard.get_vec2(TARGET_POSITION) else {
+            return BehaviorStatus::Failure;
+        };
+        if let Objective::AttackEntity(_) = context.objective() {
+            context.pop_objective();
+        }
+        let away = (context.position - target_position).normalize_or_zero();
+        *context.force += Force(away * self.force);
+        BehaviorStatus::Running
+    }
+}
+
+/// Wander between random points around where the entity was first seen.
+pub struct Patrol {
+    pub radius: f32,
+    pub force: f32,
+}
+impl BehaviorLeaf for Patrol {
+    fn tick(
+        &mut self,
+        context: &mut BehaviorContext,
+        blackboard: &mut Blackboard,
+    ) -> BehaviorStatus {
+        let home = blackboard.get_vec2(HOME).unwrap_or_else(|| {
+            blackboard.set(HOME, BlackboardValue::Vec2(context.position));
+            context.position
+        });
+        let point = match blackboard.get_vec2(PATROL_POINT) {
+            Some(point) if point.distance(context.position) > self.radius * 0.1 => point,
+            _ => {
+                let rng = &mut context.rng;
+                let point = home
+                    + Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU))
+                        * rng.gen_range(0.0..self.radius);
+                blackboard.set(PATROL_POINT, BlackboardValue::Vec2(point));
+                point
+            }
+        };
+        let toward = (point - context.position).normalize_or_zero();
+        *context.force += Force(toward * self.force);
+        BehaviorStatus::Running
+    }
+}
+
+/// Back away from the enemy being attacked, e.g. while the dash is on cooldown.
+pub struct Kite {
+    pub force: f32,
+}
+impl BehaviorLeaf for Kite {
+    fn tick(
+        &mut self,
+        context: &mut BehaviorContext,
+        _blackboard: &mut Blackboard,
+    ) -> BehaviorStatus {
+        let &Objective::AttackEntity(target) = context.objective() else {
+            return BehaviorStatus::Failure;
+        };
+        let Some(enemy) = context.enemies.iter().find(|enemy| enemy.entity == target) else {
+            return BehaviorStatus::Failure;
+        };
+        let away = -enemy.delta.normalize_or_zero();
+        *context.force += Force(away * self.force);
+        BehaviorStatus::Running
+    }
+}
@@ -0,0 +1,390 @@
+//! Behaviour trees let entities pick what to do each tick.
+//! Leaves read the entity's surroundings and act by pushing objectives or applying forces.
+//! Each tree is ticked from the root every frame, so higher priority branches can interrupt lower ones.
+
+use std::time::Duration;
+
+use crate::prelude::*;
+use bevy::{ecs::query::QueryData, utils::HashMap};
+use rand::{rngs::StdRng, Rng};
+
+pub mod leaves;
+
+pub struct BehaviorPlugin;
+impl Plugin for BehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            BehaviorTree::update
+                .in_set(FixedUpdateStage::AI)
+                .in_set(GameStateSet::Running)
+                .after(Objectives::set_objective)
+                .before(Objectives::update),
+        );
+    }
+}
+
+/// Result of ticking a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BehaviorStatus {
+    Success,
+    Failure,
+    Running,
+}
+
+/// Values shared between the nodes of one entity's tree.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlackboardValue {
+    Bool(bool),
+    Float(f32),
+    Vec2(Vec2),
+    Entity(Entity),
+}
+
+/// Per-entity memory for a behaviour tree.
+#[derive(Default, Debug)]
+pub struct Blackboard(HashMap<&'static str, BlackboardValue>);
+impl Blackboard {
+    pub fn set(&mut self, key: &'static str, value: BlackboardValue) {
+        self.0.insert(key, value);
+    }
+    pub fn remove(&mut self, key: &'static str) {
+        self.0.remove(key);
+    }
+    pub fn get_vec2(&self, key: &'static str) -> Option<Vec2> {
+        match self.0.get(key) {
+            Some(BlackboardValue::Vec2(value)) => Some(*value),
+            _ => None,
+        }
+    }
+    pub fn get_entity(&self, key: &'static str) -> Option<Entity> {
+        match self.0.get(key) {
+            Some(BlackboardValue::Entity(value)) => Some(*value),
+            _ => None,
+        }
+    }
+    pub fn get_float(&self, key: &'static str) -> Option<f32> {
+        match self.0.get(key) {
+            Some(BlackboardValue::Float(value)) => Some(*value),
+            _ => None,
+        }
+    }
+    pub fn get_bool(&self, key: &'static str) -> bool {
+        matches!(self.0.get(key), Some(BlackboardValue::Bool(true)))
+    }
+}
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct BehaviorQueryData {
+    entity: Entity,
+    tree: &'static mut BehaviorTree,
+    object: &'static Object,
+    position: &'static Position,
+    velocity: &'static Velocity,
+    health: &'static Health,
+    enemies: &'static EnemyNeighbors,
+    attached_to: &'static AttachedTo,
+    objectives: &'static mut Objectives,
+    force: &'static mut Force,
+    cooldown: Option<&'static Cooldown>,
+}
+
+/// What a leaf can see and change about its entity.
+pub struct BehaviorContext<'a> {
+    pub entity: Entity,
+    pub object: Object,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    /// Fraction of the maximum health left.
+    pub health: f32,
+    pub enemies: &'a EnemyNeighbors,
+    /// Number of objects this one is attached to.
+    pub attachments: usize,
+    /// True while attacks are on cooldown.
+    pub on_cooldown: bool,
+    /// Seconds since the last tick.
+    pub delta: f32,
+    /// Random numbers for this entity and tick.
+    pub rng: StdRng,
+    pub force: &'a mut Force,
+    objectives: &'a mut Objectives,
+    objectives_changed: bool,
+}
+impl BehaviorContext<'_> {
+    /// Current objective of the entity.
+    pub fn objective(&self) -> &Objective {
+        self.objectives.last()
+    }
+    /// Push an objective unless it is already the current one.
+    pub fn push_objective(&mut self, objective: Objective) {
+        if self.objectives.last() != &objective {
+            self.objectives.push(objective);
+            self.objectives_changed = true;
+        }
+    }
+    /// Drop the current objective.
+    pub fn pop_objective(&mut self) {
+        self.objectives_changed |= self.objectives.pop().is_some();
+    }
+}
+
+/// A leaf of a behaviour tree: a condition or an action.
+pub trait BehaviorLeaf: Send + Sync + 'static {
+    fn tick(
+        &mut self,
+        context: &mut BehaviorContext,
+        blackboard: &mut Blackboard,
+    ) -> BehaviorStatus;
+}
+
+/// A node in a behaviour tree.
+pub enum BehaviorNode {
+    /// Ticks children in order until one fails or is running.
+    Sequence(Vec<BehaviorNode>),
+    /// Ticks children in order until one succeeds or is running.
+    Selector(Vec<BehaviorNode>),
+    /// Swaps success and failure of the child.
+    Invert(Box<BehaviorNode>),
+    /// Fails without ticking the child until the cooldown after its last success is over.
+    Cooldown {
+        timer: Timer,
+        child: Box<BehaviorNode>,
+    },
+    Leaf(Box<dyn BehaviorLeaf>),
+}
+impl BehaviorNode {
+    pub fn sequence(children: impl IntoIterator<Item = BehaviorNode>) -> Self {
+        Self::Sequence(children.into_iter().collect())
+    }
+    pub fn selector(children: impl IntoIterator<Item = BehaviorNode>) -> Self {
+        Self::Selector(children.into_iter().collect())
+    }
+    pub fn invert(child: BehaviorNode) -> Self {
+        Self::Invert(Box::new(child))
+    }
+    pub fn cooldown(seconds: f32, child: BehaviorNode) -> Self {
+        let mut timer = Timer::from_seconds(seconds, TimerMode::Once);
+        // Start ready.
+        timer.tick(timer.duration());
+        Self::Cooldown {
+            timer,
+            child: Box::new(child),
+        }
+    }
+    pub fn leaf(leaf: impl BehaviorLeaf) -> Self {
+        Self::Leaf(Box::new(leaf))
+    }
+
+    pub fn tick(
+        &mut self,
+        context: &mut BehaviorContext,
+        blackboard: &mut Blackboard,
+    ) -> BehaviorStatus {
+        match self {
+            Self::Sequence(children) => {
+                for child in children.iter_mut() {
+                    let status = child.tick(context, blackboard);
+                    if status != BehaviorStatus::Success {
+                        return status;
+                    }
+                }
+                BehaviorStatus::Success
+            }
+            Self::Selector(children) => {
+                for child in children.iter_mut() {
+                    let status = child.tick(context, blackboard);
+                    if status != BehaviorStatus::Failure {
+                        return status;
+                    }
+                }
+                BehaviorStatus::Failure
+            }
+            Self::Invert(child) => match child.tick(context, blackboard) {
+                BehaviorStatus::Success => BehaviorStatus::Failure,
+                BehaviorStatus::Failure => BehaviorStatus::Success,
+                BehaviorStatus::Running => BehaviorStatus::Running,
+            },
+            Self::Cooldown { timer, child } => {
+                timer.tick(Duration::from_secs_f32(context.delta));
+                if !timer.finished() {
+                    return BehaviorStatus::Failure;
+                }
+                let status = child.tick(context, blackboard);
+                if status == BehaviorStatus::Success {
+                    timer.reset();
+                }
+                status
+            }
+            Self::Leaf(leaf) => leaf.tick(context, blackboard),
+        }
+    }
+}
+
+/// Behaviour tree and memory of an entity.
+#[derive(Component)]
+pub struct BehaviorTree {
+    pub root: BehaviorNode,
+    pub blackboard: Blackboard,
+}
+impl BehaviorTree {
+    pub fn new(root: BehaviorNode) -> Self {
+        Self {
+            root,
+            blackboard: Blackboard::default(),
+        }
+    }
+
+    pub fn update(
+        mut query: Query<BehaviorQueryData>,
+        configs: Res<ObjectConfigs>,
+        time: Res<Time>,
+        mut rng: ResMut<GameRng>,
+    ) {
+        let seed = rng.gen();
+        query.par_iter_mut().for_each(|mut item| {
+            let max_health = configs[item.object].health.max(1);
+            let mut context = BehaviorContext {
+                entity: item.entity,
+                object: *item.object,
+                position: item.position.0,
+                velocity: item.velocity.0,
+                health: item.health.health as f32 / max_health as f32,
+                enemies: item.enemies,
+                attachments: item.attached_to.len(),
+                on_cooldown: item.cooldown.is_some(),
+                delta: time.delta_seconds(),
+                rng: GameRng::for_entity(seed, item.entity),
+                force: &mut item.force,
+                // Only flag objectives as changed when a leaf actually changes them.
+                objectives: item.objectives.bypass_change_detection(),
+                objectives_changed: false,
+            };
+            let tree = &mut *item.tree;
+            tree.root.tick(&mut context, &mut tree.blackboard);
+            if context.objectives_changed {
+                item.objectives.set_changed();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Leaf that returns a fixed status and counts its ticks on the blackboard.
+    struct Stub {
+        status: BehaviorStatus,
+        key: &'static str,
+    }
+    impl BehaviorLeaf for Stub {
+        fn tick(
+            &mut self,
+            _context: &mut BehaviorContext,
+            blackboard: &mut Blackboard,
+        ) -> BehaviorStatus {
+            let ticks = blackboard.get_float(self.key).unwrap_or_default();
+            blackboard.set(self.key, BlackboardValue::Float(ticks + 1.));
+            self.status
+        }
+    }
+
+    fn stub(status: BehaviorStatus, key: &'static str) -> BehaviorNode {
+        BehaviorNode::leaf(Stub { status, key })
+    }
+
+    /// Tick the node once with an empty context.
+    fn tick(node: &mut BehaviorNode, blackboard: &mut Blackboard, delta: f32) -> BehaviorStatus {
+        let enemies = EnemyNeighbors::default();
+        let mut force = Force::default();
+        let mut objectives = Objectives::default();
+        let mut context = BehaviorContext {
+            entity: Entity::PLACEHOLDER,
+            object: Object::WORKER,
+            position: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+            health: 1.,
+            enemies: &enemies,
+            attachments: 0,
+            on_cooldown: false,
+            delta,
+            rng: GameRng::seeded(0).0,
+            force: &mut force,
+            objectives: &mut objectives,
+            objectives_changed: false,
+        };
+        node.tick(&mut context, blackboard)
+    }
+
+    fn ticks(blackboard: &Blackboard, key: &'static str) -> f32 {
+        blackboard.get_float(key).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_sequence() {
+        use BehaviorStatus::*;
+        let mut blackboard = Blackboard::default();
+        let mut node =
+            BehaviorNode::sequence([stub(Success, "a"), stub(Running, "b"), stub(Success, "c")]);
+        assert_eq!(tick(&mut node, &mut blackboard, 0.), Running);
+        assert_eq!(ticks(&blackboard, "b"), 1.);
+        assert_eq!(ticks(&blackboard, "c"), 0.);
+
+        let mut node = BehaviorNode::sequence([stub(Failure, "d"), stub(Success, "e")]);
+        assert_eq!(tick(&mut node, &mut blackboard, 0.), Failure);
+        assert_eq!(ticks(&blackboard, "e"), 0.);
+
+        let mut node = BehaviorNode::sequence([stub(Success, "f"), stub(Success, "g")]);
+        assert_eq!(tick(&mut node, &mut blackboard, 0.), Success);
+        assert_eq!(ticks(&blackboard, "g"), 1.);
+    }
+
+    #[test]
+    fn test_selector() {
+        use BehaviorStatus::*;
+        let mut blackboard = Blackboard::default();
+        let mut node =
+            BehaviorNode::selector([stub(Failure, "a"), stub(Success, "b"), stub(Success, "c")]);
+        assert_eq!(tick(&mut node, &mut blackboard, 0.), Success);
+        assert_eq!(ticks(&blackboard, "a"), 1.);
+        assert_eq!(ticks(&blackboard, "c"), 0.);
+
+        let mut node = BehaviorNode::selector([stub(Running, "d"), stub(Success, "e")]);
+        assert_eq!(tick(&mut node, &mut blackboard, 0.), Running);
+        assert_eq!(ticks(&blackboard, "e"), 0.);
+
+        let mut node = BehaviorNode::selector([stub(Failure, "f"), stub(Failure, "g")]);
+        assert_eq!(tick(&mut node, &mut blackboard, 0.), Failure);
+        assert_eq!(ticks(&blackboard, "g"), 1.);
+    }
+
+    #[test]
+    fn test_invert() {
+        use BehaviorStatus::*;
+        let mut blackboard = Blackboard::default();
+        for (status, inverted) in [(Success, Failure), (Failure, Success), (Running, Running)] {
+            let mut node = BehaviorNode::invert(stub(status, "a"));
+            assert_eq!(tick(&mut node, &mut blackboard, 0.), inverted);
+        }
+    }
+
+    #[test]
+    fn test_cooldown() {
+        use BehaviorStatus::*;
+        let mut blackboard = Blackboard::default();
+        let mut node = BehaviorNode::cooldown(1., stub(Success, "a"));
+        // Starts ready, then skips the child until the cooldown is over.
+        assert_eq!(tick(&mut node, &mut blackboard, 0.), Success);
+        assert_eq!(tick(&mut node, &mut blackboard, 0.5), Failure);
+        assert_eq!(ticks(&blackboard, "a"), 1.);
+        assert_eq!(tick(&mut node, &mut blackboard, 0.5), Success);
+        assert_eq!(ticks(&blackboard, "a"), 2.);
+
+        // Failures don't restart the cooldown.
+        let mut node = BehaviorNode::cooldown(1., stub(Failure, "b"));
+        assert_eq!(tick(&mut node, &mut blackboard, 0.), Failure);
+        assert_eq!(tick(&mut node, &mut blackboard, 0.), Failure);
+        assert_eq!(ticks(&blackboard, "b"), 2.);
+    }
+}
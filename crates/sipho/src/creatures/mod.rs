@@ -26,14 +26,15 @@ pub fn spawn_random(
     mut commands: ObjectCommands,
     obstacles: Res<Grid2<Obstacle>>,
     mut elastics: EventWriter<SpawnElasticEvent>,
+    mut rng: ResMut<GameRng>,
 ) {
     let bounds = grid_spec.world2d_bounds_eps();
 
     // Spawn plankton
     for _ in 0..3500 {
         let position = Position::new(
-            rand::thread_rng().gen_range(bounds.min.x..bounds.max.x),
-            rand::thread_rng().gen_range(bounds.min.y..bounds.max.y),
+            rng.gen_range(bounds.min.x..bounds.max.x),
+            rng.gen_range(bounds.min.y..bounds.max.y),
         );
         let rowcol = obstacles.to_rowcol(position.0).unwrap();
         if !obstacles.is_clear(rowcol) {
@@ -41,7 +42,7 @@ pub fn spawn_random(
         }
 
         commands.spawn(ObjectSpec {
-            object: Object::Plankton,
+            object: Object::PLANKTON,
             team: Team::None,
             position,
             ..default()
@@ -51,19 +52,19 @@ pub fn spawn_random(
     // Spawn snakes
     for _ in 0..150 {
         let position = Position::new(
-            rand::thread_rng().gen_range(bounds.min.x..bounds.max.x),
-            rand::thread_rng().gen_range(bounds.min.y..bounds.max.y),
+            rng.gen_range(bounds.min.x..bounds.max.x),
+            rng.gen_range(bounds.min.y..bounds.max.y),
         );
         snake::spawn_snake(position, &mut commands, &mut elastics);
     }
     // Spawn gemstones
     for _ in 0..150 {
         let position = Position::new(
-            rand::thread_rng().gen_range(bounds.min.x..bounds.max.x),
-            rand::thread_rng().gen_range(bounds.min.y..bounds.max.y),
+            rng.gen_range(bounds.min.x..bounds.max.x),
+            rng.gen_range(bounds.min.y..bounds.max.y),
         );
         commands.spawn(ObjectSpec {
-            object: Object::GemStone,
+            object: Object::GEM_STONE,
             team: Team::None,
             position,
             ..default()
@@ -71,11 +72,12 @@ pub fn spawn_random(
     }
 }
 
+#[derive(Debug, Clone, Copy, Reflect)]
 pub enum CreatureType {
     Snake,
 }
 
-#[derive(Event)]
+#[derive(Event, Debug, Clone, Reflect)]
 pub struct SpawnCreatureEvent {
     pub creature_type: CreatureType,
     pub position: Position,
@@ -1,6 +1,28 @@
-use crate::prelude::*;
+use crate::{
+    behavior::{leaves::*, BehaviorNode, BehaviorTree},
+    prelude::*,
+};
 use bevy::prelude::*;
 
+/// Snakes flee when hurt, hunt nearby enemies and otherwise patrol around where they spawned.
+pub fn snake_behavior() -> BehaviorNode {
+    BehaviorNode::selector([
+        BehaviorNode::sequence([
+            BehaviorNode::leaf(HealthBelow(0.5)),
+            BehaviorNode::leaf(EnemyInRange(300.0)),
+            BehaviorNode::leaf(Flee { force: 1.0 }),
+        ]),
+        BehaviorNode::sequence([
+            BehaviorNode::leaf(EnemyInRange(400.0)),
+            BehaviorNode::leaf(AttackTarget),
+        ]),
+        BehaviorNode::leaf(Patrol {
+            radius: 500.0,
+            force: 0.3,
+        }),
+    ])
+}
+
 pub fn spawn_snake(
     position: Position,
     commands: &mut ObjectCommands,
@@ -9,37 +31,41 @@ pub fn spawn_snake(
     let team = Team::None;
     let specs = vec![
         ObjectSpec {
-            object: Object::Shocker,
+            object: Object::SHOCKER,
             position: position + Position::new(0.0, 0.0),
             ..default()
         },
         ObjectSpec {
-            object: Object::Plankton,
+            object: Object::PLANKTON,
             position: position + Position::new(2.0, 10.0),
             ..default()
         },
         ObjectSpec {
-            object: Object::Plankton,
+            object: Object::PLANKTON,
             position: position + Position::new(-2.0, 20.0),
             ..default()
         },
         ObjectSpec {
-            object: Object::Plankton,
+            object: Object::PLANKTON,
             position: position + Position::new(0.0, 30.0),
             ..default()
         },
         ObjectSpec {
-            object: Object::Plankton,
+            object: Object::PLANKTON,
             position: position + Position::new(2.0, 40.0),
             ..default()
         },
         ObjectSpec {
-            object: Object::Plankton,
+            object: Object::PLANKTON,
             position: position + Position::new(-2.0, 50.0),
             ..default()
         },
     ];
     let entities = commands.spawn_batch(specs)?;
+    commands
+        .commands
+        .entity(entities[0])
+        .insert(BehaviorTree::new(snake_behavior()));
     for pair in entities.windows(2) {
         if let &[e1, e2] = pair {
             elastics.send(SpawnElasticEvent {
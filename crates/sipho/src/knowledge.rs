@@ -0,0 +1,159 @@
+use crate::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use sipho_core::grid::fog::{FogConfig, TeamVisibility};
+
+/// Plugin for what each team knows about the map beyond what it currently sees.
+pub struct KnowledgePlugin;
+impl Plugin for KnowledgePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Ghosts>()
+            .add_systems(
+                FixedUpdate,
+                Ghosts::update
+                    .in_set(FixedUpdateStage::PreDespawn)
+                    .in_set(GameStateSet::Running)
+                    .after(Grid2::<TeamVisibility>::update),
+            )
+            .add_systems(
+                Update,
+                GhostMarker::update
+                    .run_if(resource_changed::<Ghosts>.or_else(resource_changed::<FogConfig>))
+                    .in_set(GameStateSet::Running),
+            );
+    }
+}
+
+/// Objects that leave a ghost where they were last seen.
+pub const GHOSTED_OBJECTS: [Object; 2] = [Object::HEAD, Object::GEM_STONE];
+
+/// Last known state of an object a team has seen.
+#[derive(Debug, Clone)]
+pub struct Ghost {
+    pub object: Object,
+    pub team: Team,
+    pub position: Vec2,
+    pub rowcol: RowCol,
+    /// True if the object is currently in sight, so the ghost is up to date.
+    pub visible: bool,
+}
+
+/// Ghosts of enemy heads and gemstones per observing team.
+/// Ghosts stay where the object was last seen until the team looks there again.
+#[derive(Resource, Default, Debug)]
+pub struct Ghosts(HashMap<Team, HashMap<Entity, Ghost>>);
+impl Ghosts {
+    /// Ghosts known to the team.
+    pub fn team(&self, team: Team) -> impl Iterator<Item = (Entity, &Ghost)> + '_ {
+        self.0
+            .get(&team)
+            .into_iter()
+            .flat_map(|ghosts| ghosts.iter().map(|(&entity, ghost)| (entity, ghost)))
+    }
+
+    /// Refresh ghosts of objects in sight and forget ones whose last known cell is empty now.
+    /// Only flags the resource as changed when a ghost is added, moved, hidden or cleared, so markers update rarely.
+    pub fn update(
+        mut ghosts: ResMut<Self>,
+        query: Query<(Entity, &Object, &Team, &Position, &GridEntity)>,
+        visibility: Res<Grid2<TeamVisibility>>,
+    ) {
+        let mut changed = false;
+        let all_ghosts = ghosts.bypass_change_detection();
+        for observer in enum_iterator::all::<Team>().filter(|&team| team != Team::None) {
+            let known = all_ghosts.0.entry(observer).or_default();
+            let mut in_sight = HashSet::new();
+            for (entity, &object, &team, position, grid_entity) in &query {
+                if team == observer || !GHOSTED_OBJECTS.contains(&object) {
+                    continue;
+                }
+                let Some(rowcol) = grid_entity.rowcol else {
+                    continue;
+                };
+                if !visibility.is_visible(observer, rowcol) {
+                    continue;
+                }
+                in_sight.insert(entity);
+                let ghost = Ghost {
+                    object,
+                    team,
+                    position: position.0,
+                    rowcol,
+                    visible: true,
+                };
+                match known.get_mut(&entity) {
+                    Some(known_ghost)
+                        if known_ghost.visible && known_ghost.position == ghost.position => {}
+                    Some(known_ghost) => {
+                        *known_ghost = ghost;
+                        changed = true;
+                    }
+                    None => {
+                        known.insert(entity, ghost);
+                        changed = true;
+                    }
+                }
+            }
+            for (entity, ghost) in known.iter_mut() {
+                if ghost.visible && !in_sight.contains(entity) {
+                    ghost.visible = false;
+                    changed = true;
+                }
+            }
+            let count = known.len();
+            known
+                .retain(|_, ghost| ghost.visible || !visibility.is_visible(observer, ghost.rowcol));
+            changed |= known.len() != count;
+        }
+        if changed {
+            ghosts.set_changed();
+        }
+    }
+}
+
+/// Marker showing the player where a ghost was last seen.
+#[derive(Component)]
+pub struct GhostMarker(pub Entity);
+impl GhostMarker {
+    /// Spawn, move and despawn markers to match the player's ghosts that are out of sight.
+    pub fn update(
+        mut commands: Commands,
+        mut markers: Query<(Entity, &Self, &mut Transform)>,
+        ghosts: Res<Ghosts>,
+        fog_config: Res<FogConfig>,
+        configs: Res<ObjectConfigs>,
+        assets: Res<ObjectAssets>,
+    ) {
+        let mut hidden: HashMap<Entity, &Ghost> = ghosts
+            .team(fog_config.player_team)
+            .filter(|(_, ghost)| !ghost.visible && !fog_config.reveal_all)
+            .collect();
+        for (marker, &Self(entity), mut transform) in &mut markers {
+            match hidden.remove(&entity) {
+                Some(ghost) => {
+                    transform.translation = ghost.position.extend(transform.translation.z);
+                }
+                None => commands.entity(marker).despawn_recursive(),
+            }
+        }
+        for (entity, ghost) in hidden {
+            let config = &configs[&ghost.object];
+            let Some(mesh) = assets.object_meshes.get(&ghost.object) else {
+                continue;
+            };
+            commands.spawn((
+                Name::new("GhostMarker"),
+                Self(entity),
+                PbrBundle {
+                    mesh: mesh.clone(),
+                    material: assets.ghost_material.clone(),
+                    transform: Transform {
+                        translation: ghost.position.extend(config.zindex),
+                        scale: Vec3::splat(config.radius),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ));
+        }
+    }
+}
@@ -1,16 +1,26 @@
+pub mod ai;
+pub mod alerts;
 pub mod ambience;
+pub mod behavior;
 pub mod camera;
 pub mod creatures;
+pub mod knowledge;
 pub mod objectives;
 pub mod objects;
+pub mod scenario;
 pub mod scene;
+pub mod scripting;
+pub mod spectator;
+pub mod stats;
 pub mod terrain;
 pub mod ui;
 
 pub mod prelude {
     pub use crate::{
+        knowledge::{Ghost, Ghosts},
         objectives::{
-            Cooldown, Navigator, Objective, ObjectiveConfig, ObjectiveDebugger, Objectives, Stunned,
+            Cooldown, Formation, FormationSlot, Navigator, Objective, ObjectiveConfig,
+            ObjectiveDebugger, Objectives, Stunned,
         },
         objects::*,
         ui::{Selectable, Selected, Waypoint},
@@ -22,26 +32,76 @@ pub mod prelude {
     pub use sipho_vfx::prelude::*;
 }
 
+use bevy::{
+    app::PluginGroupBuilder,
+    log::LogPlugin,
+    render::{
+        settings::{RenderCreation, WgpuSettings},
+        RenderPlugin,
+    },
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
 use prelude::*;
 
-pub struct SiphonowarPlugin;
+#[derive(Default)]
+pub struct SiphonowarPlugin {
+    /// Run without a window or GPU, e.g. for simulating matches.
+    pub headless: bool,
+}
+impl SiphonowarPlugin {
+    fn default_plugins(&self) -> PluginGroupBuilder {
+        let plugins = DefaultPlugins
+            .set(AssetPlugin {
+                watch_for_changes_override: Some(!self.headless),
+                ..default()
+            })
+            .set(ImagePlugin::default_linear());
+        if !self.headless {
+            return plugins.set(window::custom_plugin());
+        }
+        plugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .set(RenderPlugin {
+                render_creation: RenderCreation::Automatic(WgpuSettings {
+                    backends: None,
+                    ..default()
+                }),
+                ..default()
+            })
+            .disable::<WinitPlugin>()
+            // Headless apps may be created several times per process, which the logger doesn't support.
+            .disable::<LogPlugin>()
+    }
+}
 impl Plugin for SiphonowarPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
-            DefaultPlugins
-                .set(AssetPlugin {
-                    watch_for_changes_override: Some(true),
-                    ..default()
-                })
-                .set(window::custom_plugin())
-                .set(ImagePlugin::default_linear()),
+            self.default_plugins(),
             // OutlinePlugin,
             CorePlugin,
             camera::CameraPlugin,
-            objects::ObjectsPlugin,
-            creatures::CreaturePlugin,
-            objectives::ObjectivePlugin,
-            scene::LoadableScenePlugin,
+            // Plugin tuples are limited to 15 elements.
+            (
+                objects::ObjectsPlugin,
+                ai::AiPlugin,
+                behavior::BehaviorPlugin,
+                creatures::CreaturePlugin,
+                objectives::ObjectivePlugin,
+            ),
+            (
+                scene::LoadableScenePlugin,
+                scenario::ScenarioPlugin,
+                scripting::ScriptingPlugin,
+                stats::StatsPlugin,
+                knowledge::KnowledgePlugin,
+                spectator::SpectatorPlugin,
+                alerts::AlertsPlugin,
+            ),
             ui::UiPlugin,
             sipho_vfx::VfxPlugin,
             sipho_sfx::SiphoSfxPlugin,
@@ -0,0 +1,223 @@
+use bevy::utils::HashMap;
+
+use crate::prelude::*;
+
+/// Fraction of the max formation force applied per cell away from the slot.
+const FORMATION_FORCE: f32 = 0.5;
+
+pub struct FormationPlugin;
+impl Plugin for FormationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Formation>()
+            .register_type::<FormationSlot>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    Formation::update
+                        .run_if(TeamConfig::is_playing)
+                        .in_set(FixedUpdateStage::Spawn),
+                    FormationSlot::update_force.in_set(FixedUpdateStage::PostPhysics),
+                )
+                    .in_set(GameStateSet::Running),
+            );
+    }
+}
+
+/// Shape a group takes when given a move order.
+#[derive(Resource, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Formation {
+    /// Side by side, facing the move direction.
+    #[default]
+    Line,
+    /// Rows and columns in a square.
+    Box,
+    /// A triangle with its tip pointing in the move direction.
+    Wedge,
+}
+impl Formation {
+    /// The formation after this one, when cycling from the HUD.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Line => Self::Box,
+            Self::Box => Self::Wedge,
+            Self::Wedge => Self::Line,
+        }
+    }
+
+    /// Number of units in each rank, front to back.
+    pub fn ranks(self, count: usize) -> Vec<usize> {
+        let mut ranks = Vec::new();
+        let mut remaining = count;
+        while remaining > 0 {
+            let size = match self {
+                Self::Line => remaining,
+                Self::Box => (count as f32).sqrt().ceil() as usize,
+                Self::Wedge => ranks.len() + 1,
+            }
+            .min(remaining);
+            ranks.push(size);
+            remaining -= size;
+        }
+        ranks
+    }
+
+    /// Slot offsets as (forward, lateral), ordered front to back then left to right.
+    /// Offsets are centered on zero.
+    pub fn slots(self, count: usize, spacing: f32) -> Vec<Vec2> {
+        let mut slots = Vec::with_capacity(count);
+        for (rank, size) in self.ranks(count).into_iter().enumerate() {
+            for i in 0..size {
+                slots.push(Vec2::new(-(rank as f32), i as f32 - (size - 1) as f32 / 2.));
+            }
+        }
+        let center = slots.iter().sum::<Vec2>() / count.max(1) as f32;
+        slots
+            .into_iter()
+            .map(|slot| (slot - center) * spacing)
+            .collect()
+    }
+
+    /// Assign each unit a world space offset from the destination.
+    /// Units at the front of the group take the front slots so paths don't cross.
+    pub fn assign(
+        self,
+        units: &[(Entity, Vec2)],
+        destination: Vec2,
+        spacing: f32,
+    ) -> Vec<(Entity, Vec2)> {
+        if units.is_empty() {
+            return Vec::new();
+        }
+        let center = units.iter().map(|(_, position)| *position).sum::<Vec2>() / units.len() as f32;
+        let forward = (destination - center).try_normalize().unwrap_or(Vec2::Y);
+        let lateral = forward.perp();
+
+        let mut units = units.to_vec();
+        units.sort_by(|(_, a), (_, b)| forward.dot(*b).total_cmp(&forward.dot(*a)));
+        let mut offsets = Vec::with_capacity(units.len());
+        let mut slots = self.slots(units.len(), spacing).into_iter();
+        let mut start = 0;
+        for size in self.ranks(units.len()) {
+            let rank = &mut units[start..start + size];
+            rank.sort_by(|(_, a), (_, b)| lateral.dot(*a).total_cmp(&lateral.dot(*b)));
+            for &(entity, _) in rank.iter() {
+                let slot = slots.next().unwrap();
+                offsets.push((entity, forward * slot.x + lateral * slot.y));
+            }
+            start += size;
+        }
+        offsets
+    }
+
+    /// Cycle the formation when the HUD button is pressed.
+    pub fn update(mut formation: ResMut<Self>, mut control_events: EventReader<ControlEvent>) {
+        for control in control_events.read() {
+            if control.is_pressed(ControlAction::Formation) {
+                *formation = formation.next();
+            }
+        }
+    }
+}
+
+/// Where a unit stands relative to the waypoint it is moving to with its group.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct FormationSlot {
+    /// Entity followed by the whole group.
+    pub anchor: Entity,
+    /// Offset from the anchor in world space.
+    pub offset: Vec2,
+}
+impl FormationSlot {
+    /// Offset to apply when following the given entity.
+    pub fn offset_for(slot: Option<&Self>, entity: Entity) -> Vec2 {
+        match slot {
+            Some(slot) if slot.anchor == entity => slot.offset,
+            _ => Vec2::ZERO,
+        }
+    }
+
+    /// Pull units toward their slot around the group's center so the group keeps its shape while travelling.
+    pub fn update_force(
+        mut query: Query<(&Self, &Objectives, &Position, &mut Force), Without<Stunned>>,
+        spec: Res<GridSpec>,
+    ) {
+        let is_member = |slot: &Self, objectives: &Objectives| {
+            matches!(
+                objectives.last(),
+                Objective::FollowEntity(entity) | Objective::AttackFollowEntity(entity)
+                    if *entity == slot.anchor
+            )
+        };
+
+        let mut centers: HashMap<Entity, (Vec2, f32)> = HashMap::new();
+        for (slot, objectives, position, _) in query.iter() {
+            if is_member(slot, objectives) {
+                let (sum, count) = centers.entry(slot.anchor).or_default();
+                *sum += position.0 - slot.offset;
+                *count += 1.;
+            }
+        }
+
+        for (slot, objectives, position, mut force) in query.iter_mut() {
+            if !is_member(slot, objectives) {
+                continue;
+            }
+            let (sum, count) = centers[&slot.anchor];
+            if count < 2. {
+                continue;
+            }
+            let delta = sum / count + slot.offset - position.0;
+            *force += Force((delta / spec.width).clamp_length_max(1.) * FORMATION_FORCE);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slots() {
+        assert_eq!(Formation::Line.ranks(5), vec![5]);
+        assert_eq!(Formation::Box.ranks(5), vec![3, 2]);
+        assert_eq!(Formation::Wedge.ranks(6), vec![1, 2, 3]);
+
+        assert_eq!(
+            Formation::Line.slots(3, 10.),
+            vec![Vec2::new(0., -10.), Vec2::ZERO, Vec2::new(0., 10.)]
+        );
+        for formation in [Formation::Line, Formation::Box, Formation::Wedge] {
+            for count in 0..10 {
+                let slots = formation.slots(count, 10.);
+                assert_eq!(slots.len(), count);
+                // Slots are centered on the destination.
+                assert!(slots.iter().sum::<Vec2>().length() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_assign() {
+        assert!(Formation::Wedge.assign(&[], Vec2::X, 10.).is_empty());
+
+        let front = Entity::from_raw(0);
+        let left = Entity::from_raw(1);
+        let right = Entity::from_raw(2);
+        let units = [
+            (left, Vec2::new(0., 5.)),
+            (front, Vec2::new(10., 0.)),
+            (right, Vec2::new(0., -5.)),
+        ];
+        let offsets: HashMap<Entity, Vec2> = Formation::Wedge
+            .assign(&units, Vec2::new(100., 0.), 10.)
+            .into_iter()
+            .collect();
+        assert_eq!(offsets.len(), 3);
+        // The unit ahead takes the tip of the wedge, and the others keep their side.
+        assert!(offsets[&front].x > offsets[&left].x);
+        assert!(offsets[&front].x > offsets[&right].x);
+        assert!(offsets[&left].y > 0.);
+        assert!(offsets[&right].y < 0.);
+    }
+}
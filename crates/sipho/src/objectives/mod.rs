@@ -7,6 +7,7 @@ pub mod config;
 pub mod cooldown;
 pub mod dash_attacker;
 pub mod debug;
+pub mod formation;
 pub mod navigator;
 pub mod objective;
 pub mod shock_attacker;
@@ -17,6 +18,7 @@ pub use {
     cooldown::Cooldown,
     dash_attacker::DashAttacker,
     debug::ObjectiveDebugger,
+    formation::{Formation, FormationSlot},
     navigator::Navigator,
     objective::{Objective, Objectives},
     stun::Stunned,
@@ -45,6 +47,7 @@ impl Plugin for ObjectivePlugin {
                 dash_attacker::DashAttackerPlugin,
                 shock_attacker::ShockAttackerPlugin,
                 cooldown::CooldownPlugin,
+                formation::FormationPlugin,
                 stun::StunPlugin,
             ));
     }
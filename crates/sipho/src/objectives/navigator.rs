@@ -1,4 +1,4 @@
-use bevy::utils::{Entry, HashMap};
+use bevy::utils::{Entry, HashMap, HashSet};
 
 use crate::prelude::*;
 
@@ -7,7 +7,11 @@ impl Plugin for NavigatorPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Navigator>().add_systems(
             FixedUpdate,
-            (Navigator::update, Navigator::update_force)
+            (
+                Navigator::update_clearance,
+                Navigator::update,
+                Navigator::update_force,
+            )
                 .chain()
                 .in_set(FixedUpdateStage::PostPhysics)
                 .in_set(GameStateSet::Running),
@@ -22,21 +26,90 @@ pub struct Navigator {
     pub target: Vec2,
     pub slow_factor: f32,
     pub target_radius: f32,
+    /// Cells of clearance needed to fit along the path, see `ClearanceMap`.
+    pub clearance: u8,
+    /// Lower clearance used while stuck somewhere narrower than `clearance`, see `ClearanceMap::relax`.
+    pub relaxed: Option<u8>,
 }
 impl Navigator {
+    /// Clearance to compute and follow flows with.
+    pub fn path_clearance(&self) -> u8 {
+        self.relaxed.unwrap_or(self.clearance)
+    }
+
+    /// Size each navigator by the footprint of the organism it belongs to.
+    /// Objects tied by elastics move together, so they avoid gaps the whole organism can't fit through.
+    /// Navigators stuck in a tighter spot relax their clearance until they reach open space.
+    pub fn update_clearance(
+        mut navigators: Query<(Entity, &mut Navigator, &Position)>,
+        bodies: Query<(&Position, &Transform, Option<&AttachedTo>)>,
+        spec: Res<GridSpec>,
+        clearance_map: Res<ClearanceMap>,
+    ) {
+        let mut clearances: HashMap<Entity, u8> = HashMap::new();
+        for (entity, mut navigator, position) in navigators.iter_mut() {
+            if let Some(source) = spec.to_rowcol(position.0) {
+                navigator.relaxed =
+                    clearance_map.relax(navigator.clearance, navigator.relaxed, source);
+            }
+            if let Some(&clearance) = clearances.get(&entity) {
+                navigator.clearance = clearance;
+                continue;
+            }
+
+            // Flood through elastics to find the whole organism.
+            let mut organism = vec![entity];
+            let mut visited = HashSet::new();
+            visited.insert(entity);
+            let mut i = 0;
+            while i < organism.len() {
+                if let Ok((_, _, Some(attached_to))) = bodies.get(organism[i]) {
+                    for &other in attached_to.iter() {
+                        if visited.insert(other) {
+                            organism.push(other);
+                        }
+                    }
+                }
+                i += 1;
+            }
+
+            let members: Vec<(Vec2, f32)> = organism
+                .iter()
+                .filter_map(|&member| bodies.get(member).ok())
+                .map(|(position, transform, _)| (position.0, transform.scale.x))
+                .collect();
+            let center = members.iter().map(|(position, _)| *position).sum::<Vec2>()
+                / members.len().max(1) as f32;
+            let radius = members
+                .iter()
+                .map(|(position, radius)| position.distance(center) + radius)
+                .fold(0., f32::max);
+
+            let clearance = ClearanceMap::clearance_for(radius, spec.width);
+            for member in organism {
+                clearances.insert(member, clearance);
+            }
+            navigator.clearance = clearance;
+        }
+    }
+
     pub fn update(
         query: Query<(&Navigator, &Position)>,
         mut grid: ResMut<NavigationGrid2>,
         spec: Res<GridSpec>,
         obstacles: Res<Grid2<Obstacle>>,
+        clearances: Res<ClearanceMap>,
+        seabed: Res<Grid2<Seabed>>,
+        currents: Res<CurrentField>,
+        graph: Res<NavigationGraph>,
         mut event_writer: EventWriter<NavigationCostEvent>,
     ) {
-        let mut destinations: HashMap<RowCol, Vec<RowCol>> = HashMap::new();
+        let mut destinations: HashMap<NavigationKey, Vec<RowCol>> = HashMap::new();
         for (navigator, position) in query.iter() {
             let source = spec.to_rowcol(position.0);
             let destination = spec.to_rowcol(navigator.target);
             if let (Some(source), Some(destination)) = (source, destination) {
-                match destinations.entry(destination) {
+                match destinations.entry((destination, navigator.path_clearance())) {
                     Entry::Occupied(o) => o.into_mut(),
                     Entry::Vacant(v) => v.insert(Vec::with_capacity(1)),
                 }
@@ -45,18 +118,31 @@ impl Navigator {
         }
 
         // Populate the grid.
-        for (&destination, sources) in destinations.iter() {
-            grid.compute_flow(destination, sources, &obstacles, &spec, &mut event_writer)
+        for (&key, sources) in destinations.iter() {
+            let (_, clearance) = key;
+            grid.compute_flow(
+                key,
+                sources,
+                &obstacles,
+                &graph,
+                &spec,
+                &mut event_writer,
+                |from, to| {
+                    clearances
+                        .fits(from, clearance)
+                        .then(|| seabed.weight(from, to) * currents.weight(from, to))
+                },
+            )
         }
 
         // Remove old cells where there is no objective leading to that destination.
-        let rowcols_to_remove: Vec<RowCol> = grid
+        let keys_to_remove: Vec<NavigationKey> = grid
             .keys()
-            .filter(|&destination| !destinations.contains_key(destination))
+            .filter(|&key| !destinations.contains_key(key))
             .copied()
             .collect();
-        for rowcol in rowcols_to_remove {
-            grid.remove(&rowcol);
+        for key in keys_to_remove {
+            grid.remove(&key);
         }
     }
 
@@ -78,8 +164,10 @@ impl Navigator {
     ) {
         for (object, navigator, mut transform, position, velocity, mut force) in query.iter_mut() {
             let config = configs.get(object).unwrap();
-            if let Some(target_rowcol) = spec.to_rowcol(navigator.target) {
-                if let Some(flow_grid) = grid.get(&target_rowcol) {
+            let source = spec.to_rowcol(position.0);
+            if let (Some(source), Some(target_rowcol)) = (source, spec.to_rowcol(navigator.target))
+            {
+                if let Some(flow_grid) = grid.get(&(target_rowcol, navigator.path_clearance())) {
                     let target_cell_center = flow_grid.grid.to_world_position(target_rowcol);
                     let flow_force =
                         flow_grid.grid.flow_force5(position.0) * config.nav_flow_factor;
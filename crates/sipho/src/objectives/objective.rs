@@ -13,6 +13,7 @@ use super::{dash_attacker::DashAttacker, navigator::Navigator, shock_attacker::S
 pub struct ObjectivesQueryData {
     entity: Entity,
     navigator: Option<&'static mut Navigator>,
+    slot: Option<&'static FormationSlot>,
 }
 
 /// Represents the objective of the owning entity.
@@ -32,7 +33,6 @@ impl Objective {
     /// When this objective is added, remove existing components.
     pub fn try_add_components(
         &self,
-        object: Object,
         components: &mut ObjectivesQueryDataItem,
         targets: &Query<(&Position, &CarriedBy, Option<&PathToHeadFollower>)>,
         commands: &mut Commands,
@@ -45,9 +45,11 @@ impl Objective {
             Self::FollowEntity(entity) | Self::AttackFollowEntity(entity) => {
                 let (position, _carried_by, _path_follower) = targets.get(*entity)?;
                 commands.insert(Navigator {
-                    target: position.0,
+                    target: position.0 + FormationSlot::offset_for(components.slot, *entity),
                     slow_factor: 1.0,
                     target_radius: config.objective.repell_radius,
+                    clearance: 1,
+                    relaxed: None,
                 });
             }
             Self::AttackEntity(entity) => {
@@ -60,29 +62,33 @@ impl Objective {
                         return Err(Error::Default);
                     }
                 }
-                match object {
-                    Object::Shocker => {
+                match config.attacker {
+                    Some(AttackerKind::Shock) => {
                         commands.insert((
                             Navigator {
                                 target: position.0,
                                 slow_factor: 1.0,
                                 target_radius: config.attack_radius,
+                                clearance: 1,
+                                relaxed: None,
                             },
                             ShockAttacker { ..default() },
                         ));
                     }
-                    Object::Worker => {
+                    Some(AttackerKind::Dash) => {
                         commands.insert((
                             Navigator {
                                 target: position.0,
                                 slow_factor: 0.0,
                                 target_radius: config.attack_radius,
+                                clearance: 1,
+                                relaxed: None,
                             },
                             DashAttacker { ..default() },
                         ));
                     }
-                    _ => {
-                        unreachable!()
+                    None => {
+                        return Err(Error::Default);
                     }
                 }
             }
@@ -100,8 +106,9 @@ impl Objective {
             Self::Idle => {}
             Self::FollowEntity(entity) | Self::AttackFollowEntity(entity) => {
                 let (position, _carried_by, _path_follower) = targets.get(*entity)?;
+                let offset = FormationSlot::offset_for(components.slot, *entity);
                 if let Some(ref mut navigator) = components.navigator {
-                    navigator.target = position.0;
+                    navigator.target = position.0 + offset;
                 }
             }
             Self::AttackEntity(entity) => {
@@ -153,25 +160,29 @@ impl Objectives {
     pub fn set_objective(
         mut query: Query<UpdateObjectiveQueryData>,
         others: Query<UpdateObjectiveNeighborQueryData>,
+        configs: Res<ObjectConfigs>,
     ) {
         for mut object in &mut query {
             if let Some(neighbor) = object.enemy_neighbors.first() {
                 let other = others.get(neighbor.entity).unwrap();
                 // An object should only attack a neighbor if that neighbor is not being carried.
-                let object_can_attack = object.object.can_attack();
-                let other_can_be_attacked = neighbor.object.can_be_attacked()
-                    && (other.path_follower.is_none()
-                        || other.path_follower.unwrap().target.is_none());
-                if object_can_attack && other_can_be_attacked {
+                let config = &configs[object.object];
+                let other_can_be_attacked =
+                    other.path_follower.is_none() || other.path_follower.unwrap().target.is_none();
+                if config.can_attack() && other_can_be_attacked {
                     // If already attacking an entity but we are now closer to different entity, attack the new closest
                     // entity.
-                    if *object.object == Object::Worker && object.attached_to.len() >= 2 {
+                    if config
+                        .max_attack_attachments
+                        .is_some_and(|max| object.attached_to.len() >= max)
+                    {
                         continue;
                     }
-                    // Free floating can not attack food.
-                    if neighbor.object == Object::Food
-                        && (object.path_to_head.head.is_none() || *object.object == Object::Shocker)
-                    {
+                    // Only carriers attached to a head can bring food to it.
+                    let edible = configs
+                        .get(&neighbor.object)
+                        .is_some_and(|config| config.edible);
+                    if edible && (object.path_to_head.head.is_none() || !config.carrier) {
                         continue;
                     }
                     match object.objectives.bypass_change_detection().last_mut() {
@@ -201,7 +212,6 @@ impl Objectives {
             loop {
                 let result = if objectives.is_changed() {
                     objectives.last().try_add_components(
-                        *object,
                         &mut components,
                         &targets,
                         &mut commands,
@@ -5,7 +5,7 @@ use crate::prelude::*;
 pub struct StunPlugin;
 impl Plugin for StunPlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<Cooldown>().add_systems(
+        app.register_type::<Stunned>().add_systems(
             FixedUpdate,
             Stunned::update
                 .in_set(FixedUpdateStage::AI)
@@ -14,22 +14,22 @@ impl Plugin for StunPlugin {
     }
 }
 
-/// Cooldown tag component.
+/// Stunned tag component.
 #[derive(Component, Reflect, Debug, Deref, DerefMut, Default)]
 #[reflect(Component)]
 #[component(storage = "SparseSet")]
 pub struct Stunned(pub Timer);
 impl Stunned {
-    /// Remove completed cooldowns.
-    fn update(mut query: Query<(Entity, &mut Cooldown)>, time: Res<Time>, mut commands: Commands) {
-        for (entity, mut cooldown) in query.iter_mut() {
-            cooldown.tick(time.delta());
-            if cooldown.finished() {
-                commands.entity(entity).remove::<Cooldown>();
+    /// Remove completed stuns.
+    fn update(mut query: Query<(Entity, &mut Stunned)>, time: Res<Time>, mut commands: Commands) {
+        for (entity, mut stunned) in query.iter_mut() {
+            stunned.tick(time.delta());
+            if stunned.finished() {
+                commands.entity(entity).remove::<Stunned>();
             }
         }
     }
-    /// Creates a new cooldown with a given duration.
+    /// Creates a new stun with a given duration.
     pub fn new(duration: Duration) -> Self {
         Self(Timer::new(duration, TimerMode::Once))
     }
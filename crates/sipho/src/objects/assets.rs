@@ -9,6 +9,8 @@ pub struct ObjectAssets {
     pub connector_mesh: Handle<Mesh>,
     pub team_materials: Vec<TeamMaterials>,
     pub builder_material: Handle<StandardMaterial>,
+    /// Material for markers of objects last seen through the fog.
+    pub ghost_material: Handle<StandardMaterial>,
     pub food_material: Handle<StandardMaterial>,
     pub crystal_material: Handle<StandardMaterial>,
 }
@@ -16,38 +18,31 @@ impl ObjectAssets {
     pub fn get_team_material(&self, team: Team) -> TeamMaterials {
         self.team_materials.get(team as usize).unwrap().clone()
     }
+
+    /// Load meshes for each object type from its config.
+    pub fn update_meshes(
+        mut assets: ResMut<Self>,
+        configs: Res<ObjectConfigs>,
+        asset_server: Res<AssetServer>,
+        mut load_state: ResMut<AssetLoadState>,
+    ) {
+        if !configs.is_changed() {
+            return;
+        }
+        for (&object, config) in configs.iter() {
+            if config.mesh.is_empty() {
+                continue;
+            }
+            let mesh: Handle<Mesh> = asset_server.load(config.mesh.clone());
+            load_state.track(&mesh);
+            assets.object_meshes.insert(object, mesh);
+        }
+    }
 }
 impl FromWorld for ObjectAssets {
     fn from_world(world: &mut World) -> Self {
         Self {
-            object_meshes: {
-                let mut meshes = HashMap::new();
-                meshes.insert(
-                    Object::Worker,
-                    world.load_asset("models/zooids/worker/worker.glb#Mesh0/Primitive0"),
-                );
-                meshes.insert(Object::Head, meshes[&Object::Worker].clone());
-                meshes.insert(Object::Plankton, meshes[&Object::Worker].clone());
-                meshes.insert(Object::Food, meshes[&Object::Worker].clone());
-                meshes.insert(
-                    Object::Gem,
-                    world.load_asset("models/minerals/Gem.glb#Mesh0/Primitive0"),
-                );
-                meshes.insert(
-                    Object::GemStone,
-                    world.load_asset("models/minerals/GemStone.glb#Mesh0/Primitive0"),
-                );
-                meshes.insert(
-                    Object::Shocker,
-                    world.load_asset("models/zooids/shocker/shocker.glb#Mesh0/Primitive0"),
-                );
-                meshes.insert(
-                    Object::Armor,
-                    world.load_asset("models/zooids/armor/armor.glb#Mesh0/Primitive0"),
-                );
-                meshes
-            },
-
+            object_meshes: HashMap::new(),
             connector_mesh: world.load_asset("models/connector/connector.gltf#Mesh0/Primitive0"),
             team_materials: {
                 let mut materials = world.assets::<StandardMaterial>();
@@ -62,6 +57,12 @@ impl FromWorld for ObjectAssets {
                 alpha_mode: AlphaMode::Blend,
                 ..default()
             }),
+            ghost_material: world.append_asset(StandardMaterial {
+                base_color: Color::srgba(0.8, 0.8, 1.0, 0.25),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }),
             food_material: world.append_asset(StandardMaterial {
                 base_color: SEA_GREEN.into(),
                 emissive: SEA_GREEN.into(),
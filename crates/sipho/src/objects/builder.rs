@@ -12,6 +12,7 @@ impl Plugin for ObjectBuilderPlugin {
             .add_systems(
                 FixedUpdate,
                 ObjectBuilder::update
+                    .run_if(TeamConfig::is_playing)
                     .in_set(FixedUpdateStage::Spawn)
                     .in_set(GameStateSet::Running),
             );
@@ -45,7 +46,7 @@ impl Default for ObjectBuilderBundle {
                 visibility: Visibility::Hidden,
                 ..default()
             },
-            object: Object::BuilderPreview,
+            object: Object::BUILDER_PREVIEW,
             team: Team::None,
             position: Position::ZERO,
             velocity: Velocity::ZERO,
@@ -96,10 +97,14 @@ impl ObjectBuilderQueryDataItem<'_> {
         // If not already in this object state, switch to this object.
         if self.builder.bypass_change_detection().object == Some(object) {
         } else {
-            let config = configs.get(&object).unwrap();
+            let (Some(config), Some(mesh)) =
+                (configs.get(&object), assets.object_meshes.get(&object))
+            else {
+                return;
+            };
             self.builder.object = Some(object);
             *self.visibility = Visibility::Visible;
-            *self.mesh = assets.object_meshes[&object].clone();
+            *self.mesh = mesh.clone();
             self.transform.scale = Vec3::splat(config.radius * 1.2);
         }
     }
@@ -220,12 +225,10 @@ impl ObjectBuilder {
     }
 
     pub fn get_buildable_object(action: ControlAction) -> Option<Object> {
-        Some(match action {
-            ControlAction::Worker => Object::Worker,
-            ControlAction::Shocker => Object::Shocker,
-            ControlAction::Armor => Object::Armor,
-            _ => return None,
-        })
+        match action {
+            ControlAction::Build(object) => Some(object),
+            _ => None,
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -265,8 +268,12 @@ impl ObjectBuilder {
                         *frame_count = 0;
                         if let Some(neighbor) = elastic_builder.builder.neighbor {
                             if let Ok((_position, path_to_head)) = objects.get(neighbor) {
+                                // Food spent from the attached head, as for growing the object.
+                                let cost = object_configs
+                                    .get(&object)
+                                    .map_or(usize::MAX, |config| config.food_cost);
                                 if let Some(head) = path_to_head.head {
-                                    if commands.try_consume(head, 1).is_ok() {
+                                    if commands.try_consume(head, cost).is_ok() {
                                         if let Some(entity_commands) = commands.spawn(ObjectSpec {
                                             object,
                                             position: Position(event.position),
@@ -7,7 +7,8 @@ use bevy_bundletree::*;
 
 use super::{neighbors::NeighborsBundle, object_tree::ObjectTree};
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Reflect)]
+#[reflect(Default)]
 pub struct ObjectSpec {
     pub object: Object,
     pub position: Position,
@@ -38,6 +39,7 @@ pub struct ObjectBundle {
     pub grid_raycast_target: GridRaycastTarget,
     pub name: Name,
     pub fog_entity: FogEntity,
+    pub vision: Vision,
     pub selectable: Selectable,
 }
 impl ObjectBundle {
@@ -46,7 +48,6 @@ impl ObjectBundle {
         time.elapsed().as_secs_f32() / time.wrap_period().as_secs_f32()
     }
     pub fn new(config: &ObjectConfig, spec: ObjectSpec, time: &Time) -> Self {
-        let name: &'static str = spec.object.into();
         Self {
             object: spec.object,
             team: spec.team,
@@ -55,7 +56,7 @@ impl ObjectBundle {
                 scale: Vec3::splat(config.radius),
                 translation: spec
                     .position
-                    .extend(spec.object.zindex() + 0.1 * Self::random_offset(time)),
+                    .extend(config.zindex + 0.1 * Self::random_offset(time)),
                 ..default()
             },
             physics: PhysicsBundle {
@@ -68,7 +69,11 @@ impl ObjectBundle {
                 ..default()
             },
             health: Health::new(config.health),
-            name: Name::new(name),
+            vision: Vision {
+                radius: config.vision_radius,
+                detection_radius: config.detection_radius,
+            },
+            name: Name::new(spec.object.name()),
             neighbors: NeighborsBundle {
                 grid_entity: GridEntity {
                     publish_events: true,
@@ -106,19 +111,23 @@ impl ObjectCommands<'_, '_> {
         Ok(())
     }
     pub fn spawn(&mut self, spec: ObjectSpec) -> Option<EntityCommands> {
-        let config = &self.configs[&spec.object];
+        let Some(config) = self.configs.get(&spec.object) else {
+            warn!("Can't spawn {:?}, it has no config.", spec.object);
+            return None;
+        };
+        // Meshes are loaded once configs are, so they may still be missing on the first frames.
+        let Some(mesh) = self.assets.object_meshes.get(&spec.object).cloned() else {
+            warn!("Can't spawn {:?}, its mesh isn't loaded.", spec.object);
+            return None;
+        };
         let team_material = self.assets.get_team_material(spec.team);
-        if let Some(rowcol) = self.obstacles.to_rowcol(spec.position.0) {
-            if self.obstacles[rowcol] != Obstacle::Empty {
-                return None;
-            }
+        if self.obstacles.is_blocked(spec.position.0) {
+            return None;
         }
-        let primary_material = if matches!(spec.object, Object::Gem | Object::GemStone) {
-            self.assets.crystal_material.clone()
-        } else {
-            team_material.primary.clone()
+        let primary_material = match config.material {
+            ObjectMaterial::Crystal => self.assets.crystal_material.clone(),
+            ObjectMaterial::Team => team_material.primary.clone(),
         };
-        let mesh = self.assets.object_meshes[&spec.object].clone();
         let bundle_tree = ObjectTree::new(
             spec,
             mesh,
@@ -127,7 +136,14 @@ impl ObjectCommands<'_, '_> {
             config,
             &self.time,
         );
-        Some(self.commands.spawn_tree(bundle_tree))
+        let mut entity_commands = self.commands.spawn_tree(bundle_tree);
+        for &component in &config.components {
+            component.insert(&mut entity_commands);
+        }
+        if config.stealth {
+            entity_commands.insert(Stealth::default());
+        }
+        Some(entity_commands)
     }
     pub fn spawn_batch(&mut self, specs: Vec<ObjectSpec>) -> Option<Vec<Entity>> {
         let mut entities = Vec::with_capacity(specs.len());
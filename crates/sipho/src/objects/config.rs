@@ -1,7 +1,8 @@
+use crate::objectives::{shock_attacker::ShockAttacker, DashAttacker};
 use crate::prelude::*;
 use bevy::utils::HashMap;
-use enum_iterator::all;
 use sipho_core::inputs::InputAction;
+use std::time::Duration;
 
 pub struct ObjectConfigPlugin;
 impl Plugin for ObjectConfigPlugin {
@@ -13,10 +14,19 @@ impl Plugin for ObjectConfigPlugin {
             .register_type::<HashMap<InputAction, ControlAction>>()
             .register_type::<HashMap<Object, ObjectConfig>>()
             .register_type::<HashMap<Object, InteractionConfig>>()
+            .register_type::<Option<InteractionConfig>>()
+            .register_type::<Vec<Object>>()
+            .register_type::<Option<usize>>()
             .register_type::<ObjectConfig>()
+            .register_type::<AttackerKind>()
+            .register_type::<Option<AttackerKind>>()
+            .register_type::<ObjectMaterial>()
+            .register_type::<ObjectComponent>()
+            .register_type::<Vec<ObjectComponent>>()
             .register_type::<ObjectConfigs>()
             .register_type::<InteractionConfigs>()
             .add_systems(OnExit(GameState::Loading), ObjectConfigs::setup)
+            .add_systems(Update, ObjectAssets::update_meshes)
             .insert_resource(ObjectConfigs::default());
     }
 }
@@ -52,8 +62,55 @@ impl Default for InteractionConfig {
 #[reflect(Resource)]
 pub struct ObjectConfigs(pub HashMap<Object, ObjectConfig>);
 
+/// How an object attacks its target.
+#[derive(Clone, Copy, Reflect, Debug, PartialEq, Eq)]
+pub enum AttackerKind {
+    /// Dash into the target.
+    Dash,
+    /// Shock the target from a distance.
+    Shock,
+}
+impl AttackerKind {
+    /// Time between attacks.
+    pub fn cooldown(self) -> Duration {
+        match self {
+            Self::Dash => DashAttacker::ATTACK_COOLDOWN,
+            Self::Shock => ShockAttacker::ATTACK_COOLDOWN,
+        }
+    }
+}
+
+/// Material used for an object's primary mesh.
+#[derive(Clone, Copy, Reflect, Debug, Default, PartialEq, Eq)]
+pub enum ObjectMaterial {
+    /// Primary material of the object's team.
+    #[default]
+    Team,
+    Crystal,
+}
+
+/// Component added to an object on spawn, on top of `ObjectBundle`.
+/// Selects which systems drive objects of a type.
+#[derive(Clone, Copy, Reflect, Debug, PartialEq, Eq)]
+pub enum ObjectComponent {
+    /// Outline in the team's background color.
+    Background,
+    /// Tracks the nearest allied head.
+    NearestHead,
+    /// Piped along the organism to be consumed by its head.
+    PathToHeadFollower,
+    /// Worker behavior, backing off while attacks are on cooldown.
+    Worker,
+    /// Grows the organism and spawns new zooids.
+    Head,
+    /// Stores consumed food.
+    Consumer,
+    /// Drifting plankton the player can spawn.
+    Plankton,
+}
+
 #[derive(Clone, Reflect, Debug)]
-/// Specifies stats per object type.
+/// Specifies stats, capabilities and presentation per object type.
 pub struct ObjectConfig {
     pub physics_material: PhysicsMaterial,
     pub neighbor_radius: f32,
@@ -67,7 +124,51 @@ pub struct ObjectConfig {
     pub idle_speed: f32,
     pub spawn_cost: i32,
     pub interactions: InteractionConfigs,
+    /// Interaction with types missing from `interactions`.
+    /// Its damage is the object's base damage.
+    #[reflect(default)]
+    pub default_interaction: Option<InteractionConfig>,
     pub controls: ObjectControlConfig,
+    /// Components that select the object's behavior.
+    #[reflect(default)]
+    pub components: Vec<ObjectComponent>,
+    /// Asset path of the object's mesh.
+    #[reflect(default)]
+    pub mesh: String,
+    #[reflect(default)]
+    pub material: ObjectMaterial,
+    #[reflect(default)]
+    pub zindex: f32,
+    /// How the object attacks, if it can attack at all.
+    #[reflect(default)]
+    pub attacker: Option<AttackerKind>,
+    /// True if the object can carry other objects.
+    #[reflect(default)]
+    pub carrier: bool,
+    /// True if the object can be carried.
+    #[reflect(default)]
+    pub carriable: bool,
+    /// Radius in cells revealed through the fog of war.
+    #[reflect(default)]
+    pub vision_radius: u16,
+    /// Radius in cells in which hidden enemies are detected.
+    #[reflect(default)]
+    pub detection_radius: u16,
+    /// True if the object is hidden from enemies that don't detect it.
+    #[reflect(default)]
+    pub stealth: bool,
+    /// Food a head spends to grow the object.
+    #[reflect(default)]
+    pub food_cost: usize,
+    /// True if heads consume the object.
+    #[reflect(default)]
+    pub edible: bool,
+    /// Objects spawned where the object dies.
+    #[reflect(default)]
+    pub drops: Vec<Object>,
+    /// Stops attacking once attached to this many objects, so links inside a limb hold their place.
+    #[reflect(default)]
+    pub max_attack_attachments: Option<usize>,
 }
 impl Default for ObjectConfig {
     fn default() -> Self {
@@ -83,14 +184,23 @@ impl Default for ObjectConfig {
             health: 1,
             idle_speed: 0.5,
             spawn_cost: 4,
-            interactions: InteractionConfigs({
-                let mut interactions = HashMap::new();
-                for object in all::<Object>() {
-                    interactions.insert(object, InteractionConfig::default());
-                }
-                interactions
-            }),
+            interactions: InteractionConfigs::default(),
+            default_interaction: None,
             controls: ObjectControlConfig::default(),
+            components: Vec::default(),
+            mesh: String::default(),
+            material: ObjectMaterial::default(),
+            zindex: zindex::ZOOIDS_MIN,
+            attacker: None,
+            carrier: false,
+            carriable: false,
+            vision_radius: 0,
+            detection_radius: 0,
+            stealth: false,
+            food_cost: 1,
+            edible: false,
+            drops: Vec::default(),
+            max_attack_attachments: None,
         }
     }
 }
@@ -101,17 +211,61 @@ impl ObjectConfig {
     pub fn in_radius(&self, distance_squared: f32) -> bool {
         distance_squared <= self.neighbor_radius * self.neighbor_radius
     }
+    /// Returns true if an object can attack.
+    pub fn can_attack(&self) -> bool {
+        self.attacker.is_some()
+    }
 }
 
 impl ObjectConfigs {
     /// Setup object config.
+    /// Panics if a pair of configured types has no interaction, so new types can't silently deal no damage.
     pub fn setup(mut configs: ResMut<ObjectConfigs>) {
-        // Initialize defaults
-        for object in all::<Object>() {
-            let config = configs.entry(object).or_insert(default());
-            for other in all::<Object>() {
-                config.interactions.entry(other).or_insert(default());
+        let missing = configs.missing_interactions();
+        assert!(
+            missing.is_empty(),
+            "Object configs have no interaction for {}. Add them to `interactions` or set a `default_interaction`.",
+            missing
+                .iter()
+                .map(|(object, other)| format!("{} with {}", object, other))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        // The builder preview isn't configured but is seen by neighbors.
+        configs.entry(Object::BUILDER_PREVIEW).or_default();
+        // Fill in interactions with types that the config doesn't mention.
+        let objects: Vec<Object> = configs.keys().copied().collect();
+        for config in configs.values_mut() {
+            let default_interaction = config.default_interaction.clone().unwrap_or_default();
+            for &other in &objects {
+                // Nothing interacts with the preview.
+                if other == Object::BUILDER_PREVIEW {
+                    config.interactions.entry(other).or_default();
+                    continue;
+                }
+                config
+                    .interactions
+                    .entry(other)
+                    .or_insert_with(|| default_interaction.clone());
             }
         }
     }
+
+    /// Pairs of configured types with neither an interaction nor a default interaction, sorted by name.
+    pub fn missing_interactions(&self) -> Vec<(Object, Object)> {
+        let mut missing: Vec<(Object, Object)> = self
+            .iter()
+            .filter(|(_, config)| config.default_interaction.is_none())
+            .flat_map(|(&object, config)| {
+                self.keys()
+                    .filter(|other| !config.interactions.contains_key(*other))
+                    .map(move |&other| (object, other))
+            })
+            .filter(|&(object, other)| {
+                object != Object::BUILDER_PREVIEW && other != Object::BUILDER_PREVIEW
+            })
+            .collect();
+        missing.sort_by_key(|&(object, other)| (object.name(), other.name()));
+        missing
+    }
 }
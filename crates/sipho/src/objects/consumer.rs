@@ -1,6 +1,6 @@
 use std::f32::consts::PI;
 
-use crate::prelude::*;
+use crate::{prelude::*, stats::MatchStats};
 
 use super::neighbors::EnemyCollisions;
 
@@ -49,7 +49,7 @@ impl Consumer {
     }
 
     pub fn gems_consumed(&self) -> usize {
-        self.food_indicators.len()
+        self.gem_indicators.len()
     }
 
     pub fn update(
@@ -60,58 +60,68 @@ impl Consumer {
             &Position,
             &EnemyCollisions,
             &Transform,
+            &Team,
         )>,
         mut damage_events: EventWriter<DamageEvent>,
         mut audio: EventWriter<AudioEvent>,
         mut commands: Commands,
         assets: Res<ObjectAssets>,
+        configs: Res<ObjectConfigs>,
+        mut stats: ResMut<MatchStats>,
     ) {
-        for (entity, mut consumer, mut mass, position, colliders, transform) in query.iter_mut() {
+        for (entity, mut consumer, mut mass, position, colliders, transform, &team) in
+            query.iter_mut()
+        {
             for neighbor in colliders.iter() {
-                if matches!(neighbor.object, Object::Food | Object::Gem) {
-                    let consumed = consumer.food_consumed() as f32;
-                    let min_radius = 3.0;
-                    let max_radius = 30.0;
-                    let radius = min_radius.lerp(max_radius, (consumed / 30.).min(1.));
-                    let child_position = radius
-                        * Vec2::from_angle(consumer.food_consumed() as f32 * PI * 0.6).normalize();
-                    let indicator = commands
-                        .spawn(PbrBundle {
-                            mesh: assets.object_meshes[&neighbor.object].clone(),
-                            material: if neighbor.object == Object::Food {
-                                assets.food_material.clone()
-                            } else {
-                                assets.crystal_material.clone()
-                            },
-                            transform: Transform {
-                                translation: child_position.extend(15.0 - radius / 4.)
-                                    / transform.scale,
-                                scale: transform.scale.recip() * 7.,
-                                ..default()
-                            },
+                let Some(config) = configs.get(&neighbor.object).filter(|config| config.edible)
+                else {
+                    continue;
+                };
+                let consumed = consumer.food_consumed() as f32;
+                let min_radius = 3.0;
+                let max_radius = 30.0;
+                let radius = min_radius.lerp(max_radius, (consumed / 30.).min(1.));
+                let child_position = radius
+                    * Vec2::from_angle(consumer.food_consumed() as f32 * PI * 0.6).normalize();
+                let indicator = commands
+                    .spawn(PbrBundle {
+                        mesh: assets
+                            .object_meshes
+                            .get(&neighbor.object)
+                            .cloned()
+                            .unwrap_or_default(),
+                        material: match config.material {
+                            ObjectMaterial::Crystal => assets.crystal_material.clone(),
+                            ObjectMaterial::Team => assets.food_material.clone(),
+                        },
+                        transform: Transform {
+                            translation: child_position.extend(15.0 - radius / 4.)
+                                / transform.scale,
+                            scale: transform.scale.recip() * 7.,
                             ..default()
-                        })
-                        .id();
-                    if neighbor.object == Object::Food {
-                        consumer.food_indicators.push(indicator);
-                    }
-                    if neighbor.object == Object::Gem {
-                        consumer.gem_indicators.push(indicator);
-                    }
-                    commands.entity(entity).add_child(indicator);
-                    audio.send(AudioEvent {
-                        sample: AudioSample::RandomBubble,
-                        position: Some(position.0),
+                        },
                         ..default()
-                    });
-                    damage_events.send(DamageEvent {
-                        damager: entity,
-                        damaged: neighbor.entity,
-                        amount: 1,
-                        velocity: Velocity::ZERO,
-                        stun: false,
-                    });
+                    })
+                    .id();
+                if neighbor.object == Object::GEM {
+                    consumer.gem_indicators.push(indicator);
+                } else {
+                    consumer.food_indicators.push(indicator);
                 }
+                commands.entity(entity).add_child(indicator);
+                stats.gather(team);
+                audio.send(AudioEvent {
+                    sample: AudioSample::RandomBubble,
+                    position: Some(position.0),
+                    ..default()
+                });
+                damage_events.send(DamageEvent {
+                    damager: entity,
+                    damaged: neighbor.entity,
+                    amount: 1,
+                    velocity: Velocity::ZERO,
+                    stun: false,
+                });
             }
             let count = (consumer.food_consumed() + consumer.gems_consumed()) as f32;
             *mass = Mass(1.0 + (2. * count / (count + 1.0)));
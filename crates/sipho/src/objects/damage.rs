@@ -1,33 +1,46 @@
 use std::time::Duration;
 
-use rand::random;
+use rand::Rng;
 
 use crate::{objectives::Stunned, prelude::*};
+use sipho_core::grid::detection::ATTACK_REVEAL_SECONDS;
 
 pub struct DamagePlugin;
 impl Plugin for DamagePlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<DamageEvent>().add_systems(
-            FixedUpdate,
-            (DamageEvent::update, Health::death)
-                .chain()
-                .in_set(FixedUpdateStage::AccumulateForces)
-                .in_set(GameStateSet::Running),
-        );
+        app.add_event::<DamageEvent>()
+            .add_event::<DeathEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    Health::default_max.in_set(FixedUpdateStage::PostSpawn),
+                    (DamageEvent::update, Health::death)
+                        .chain()
+                        .in_set(FixedUpdateStage::AccumulateForces),
+                )
+                    .in_set(GameStateSet::Running),
+            );
     }
 }
 
+/// Distance over which several objects dropped by a dying object scatter.
+pub const DROP_SCATTER: f32 = 100.0;
+
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct Health {
     pub health: i32,
     pub damageable: bool,
+    /// Health the object spawned with.
+    #[reflect(default)]
+    pub max: i32,
 }
 impl Default for Health {
     fn default() -> Self {
         Self {
             health: 1,
             damageable: true,
+            max: 1,
         }
     }
 }
@@ -35,49 +48,86 @@ impl Health {
     pub fn new(amount: i32) -> Self {
         Self {
             health: amount,
+            max: amount,
             ..default()
         }
     }
+
+    /// Remaining health from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        let max = self.max.max(self.health).max(1);
+        self.health.max(0) as f32 / max as f32
+    }
+
+    /// Returns true if the object has lost health.
+    pub fn is_damaged(&self) -> bool {
+        self.health < self.max
+    }
     pub fn damage(&mut self, amount: i32) {
         self.health -= amount;
     }
 
+    /// Health saved before max health was tracked loads with a max of zero.
+    /// Use the configured health instead.
+    pub fn default_max(
+        mut objects: Query<(&Object, &mut Health), Added<Health>>,
+        configs: Res<ObjectConfigs>,
+    ) {
+        for (object, mut health) in &mut objects {
+            if health.max > 0 {
+                continue;
+            }
+            if let Some(config) = configs.get(object) {
+                health.max = config.health;
+            }
+        }
+    }
+
     /// System for objects dying.
     pub fn death(
         mut objects: Query<(Entity, &Object, &Health, &Position, &Team, &Visibility)>,
         mut object_commands: ObjectCommands,
         mut firework_events: EventWriter<FireworkSpec>,
         mut audio: EventWriter<AudioEvent>,
+        mut death_events: EventWriter<DeathEvent>,
+        mut rng: ResMut<GameRng>,
+        configs: Res<ObjectConfigs>,
     ) {
         for (entity, object, health, position, team, vis) in &mut objects {
             if health.health <= 0 {
                 object_commands.deferred_despawn(entity);
-                if object == &Object::Plankton {
-                    object_commands.spawn(ObjectSpec {
-                        object: Object::Food,
-                        position: *position,
-                        ..default()
-                    });
-                }
-                if object == &Object::GemStone {
-                    for _ in 0..6 {
-                        let offset_pos = Position(Vec2 {
-                            x: random::<f32>() * 100.0,
-                            y: random::<f32>() * 100.0,
-                        });
-                        object_commands.spawn(ObjectSpec {
-                            object: Object::Gem,
-                            position: *position + offset_pos,
-                            ..default()
-                        });
+                death_events.send(DeathEvent {
+                    entity,
+                    object: *object,
+                    team: *team,
+                    position: *position,
+                });
+                let Some(config) = configs.get(object) else {
+                    continue;
+                };
+                // A single drop takes the object's place, several scatter around it.
+                let scatter = config.drops.len() > 1;
+                for &dropped in &config.drops {
+                    let mut drop_position = *position;
+                    if scatter {
+                        drop_position.0 += Vec2 {
+                            x: rng.gen::<f32>() * DROP_SCATTER,
+                            y: rng.gen::<f32>() * DROP_SCATTER,
+                        };
                         firework_events.send(FireworkSpec {
                             size: VfxSize::Large,
-                            position: position.0.extend(0.0) + offset_pos.0.extend(0.0),
+                            position: drop_position.extend(0.0),
                             color: FireworkColor::White,
                         });
                     }
+                    object_commands.spawn(ObjectSpec {
+                        object: dropped,
+                        position: drop_position,
+                        ..default()
+                    });
                 }
-                if object != &Object::Food && vis != Visibility::Hidden {
+                // Eaten objects disappear quietly.
+                if !config.edible && vis != Visibility::Hidden {
                     firework_events.send(FireworkSpec {
                         size: VfxSize::Medium,
                         position: position.extend(0.0),
@@ -94,6 +144,15 @@ impl Health {
     }
 }
 
+/// Sent when an object's health drops to zero.
+#[derive(Event, Debug, Clone)]
+pub struct DeathEvent {
+    pub entity: Entity,
+    pub object: Object,
+    pub team: Team,
+    pub position: Position,
+}
+
 #[derive(Event, Debug)]
 pub struct DamageEvent {
     pub damager: Entity,
@@ -106,6 +165,7 @@ impl DamageEvent {
     pub fn update(
         mut query: Query<(Entity, &mut Health, &Team, &Object, &Position, &Visibility)>,
         mut forces: Query<&mut Force>,
+        mut stealths: Query<&mut Stealth>,
         mut events: EventReader<DamageEvent>,
         mut firework_events: EventWriter<FireworkSpec>,
         mut audio_events: EventWriter<AudioEvent>,
@@ -121,6 +181,16 @@ impl DamageEvent {
             if let Ok(mut force) = forces.get_mut(event.damaged) {
                 *force += Force(*event.velocity * 0.5 * knockback_amount);
             }
+            // Attacking an enemy reveals a stealthy damager for a while.
+            if let (Ok(mut stealth), Ok(damager), Ok(damaged)) = (
+                stealths.get_mut(event.damager),
+                query.get(event.damager),
+                query.get(event.damaged),
+            ) {
+                if damager.2 != damaged.2 {
+                    stealth.reveal(ATTACK_REVEAL_SECONDS);
+                }
+            }
             // Reduce health and set off firework for the damaged.
             if let Ok((entity, mut health, &team, object, &position, &vis)) =
                 query.get_mut(event.damaged)
@@ -129,7 +199,7 @@ impl DamageEvent {
                     health.damage(event.amount);
                 };
 
-                if object != &Object::Food && vis != Visibility::Hidden {
+                if object != &Object::FOOD && vis != Visibility::Hidden {
                     let size = VfxSize::Small;
                     firework_events.send(FireworkSpec {
                         size,
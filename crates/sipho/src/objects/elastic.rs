@@ -9,11 +9,15 @@ pub struct ElasticPlugin;
 impl Plugin for ElasticPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<SpawnElasticEvent>()
+            .add_event::<ElasticSnapEvent>()
             .register_type::<Elastic>()
             .add_systems(
                 FixedUpdate,
                 (
-                    (Elastic::tie_selection, SpawnElasticEvent::update)
+                    (
+                        Elastic::tie_selection.run_if(TeamConfig::is_playing),
+                        SpawnElasticEvent::update,
+                    )
                         .chain()
                         .in_set(FixedUpdateStage::PostSpawn),
                     (Elastic::update).in_set(FixedUpdateStage::AccumulateForces),
@@ -37,6 +41,13 @@ impl SpawnElasticEvent {
     }
 }
 
+/// Sent when an elastic is stretched until it snaps.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ElasticSnapEvent {
+    pub entities: (Entity, Entity),
+    pub position: Vec2,
+}
+
 #[derive(Component, Debug, Default, DerefMut, Deref)]
 pub struct AttachedTo(pub SmallVec<[Entity; 10]>);
 
@@ -189,6 +200,7 @@ impl Elastic {
             }
         }
     }
+    #[allow(clippy::too_many_arguments)]
     pub fn update(
         mut elastic_query: Query<(Entity, &Elastic, &mut Transform, &mut Visibility)>,
         object_query: Query<(Entity, &Position, &Objectives, &Visibility), Without<Elastic>>,
@@ -196,6 +208,7 @@ impl Elastic {
         mut mass_query: Query<&mut Mass>,
         mut attachments: Query<&mut AttachedTo>,
         mut firework_events: EventWriter<FireworkSpec>,
+        mut snap_events: EventWriter<ElasticSnapEvent>,
         mut commands: Commands,
     ) {
         for (entity, elastic, mut transform, mut visibility) in elastic_query.iter_mut() {
@@ -222,11 +235,16 @@ impl Elastic {
                 let magnitude = delta.length();
                 if magnitude > Elastic::MAX_LENGTH {
                     snap(&mut commands, entity, elastic, &mut attachments);
+                    let position = (position1.0 + position2.0) / 2.0;
                     firework_events.send(FireworkSpec {
-                        position: ((position1.0 + position2.0) / 2.0).extend(0.0),
+                        position: position.extend(0.0),
                         color: FireworkColor::White,
                         size: VfxSize::Small,
                     });
+                    snap_events.send(ElasticSnapEvent {
+                        entities: elastic.0,
+                        position,
+                    });
                 }
                 let mag_shift = (magnitude - 16.0).max(0.0);
                 let force = mag_shift.powi(3) * 0.0001;
@@ -1,6 +1,5 @@
 use crate::prelude::*;
 
-pub mod ai;
 mod assets;
 mod background;
 mod builder;
@@ -20,14 +19,19 @@ pub mod zooid_worker;
 
 pub use {
     assets::ObjectAssets,
+    builder::ObjectBuilder,
     carry::{CarriedBy, CarryEvent},
     commands::{ObjectBundle, ObjectCommands, ObjectSpec},
-    config::{InteractionConfig, InteractionConfigs, ObjectConfig, ObjectConfigs},
+    config::{
+        AttackerKind, InteractionConfig, InteractionConfigs, ObjectComponent, ObjectConfig,
+        ObjectConfigs, ObjectMaterial,
+    },
     consumer::Consumer,
-    damage::{DamageEvent, Health},
-    elastic::{AttachedTo, Elastic, ElasticCommands, ElasticPlugin, SpawnElasticEvent},
+    damage::{DamageEvent, DeathEvent, Health},
+    elastic::{
+        AttachedTo, Elastic, ElasticCommands, ElasticPlugin, ElasticSnapEvent, SpawnElasticEvent,
+    },
     neighbors::{AlliedCollisions, AlliedNeighbors, EnemyCollisions, EnemyNeighbors},
-    object::Object,
     path_to_head::{PathToHead, PathToHeadFollower},
     zooid_head::ZooidHead,
 };
@@ -49,7 +53,6 @@ impl Plugin for ObjectsPlugin {
             path_to_head::PathToHeadPlugin,
             damage::DamagePlugin,
             builder::ObjectBuilderPlugin,
-            ai::EnemyAIPlugin,
         ))
         .init_resource::<ObjectAssets>();
     }
@@ -2,6 +2,7 @@ use crate::prelude::*;
 use bevy::math::FloatOrd;
 use bevy::utils::HashSet;
 use enum_iterator::all;
+use sipho_core::grid::fog::TeamVisibility;
 use smallvec::SmallVec;
 
 pub struct NeighborsPlugin;
@@ -103,9 +104,32 @@ pub fn update(
         &Position,
     )>,
     others: Query<(&Object, &Team, &Position)>,
+    stealths: Query<&Stealth>,
     grid: Res<Grid2<TeamEntitySets>>,
+    visibility: Res<Grid2<TeamVisibility>>,
+    detection: Res<Grid2<TeamDetection>>,
     configs: Res<ObjectConfigs>,
 ) {
+    // Enemies a team can't see, through fog or stealth, don't become neighbors.
+    // Neutral objects have no vision and keep seeing everything.
+    let is_visible_to = |team: Team, entity: Entity| {
+        if team == Team::None {
+            return true;
+        }
+        let Some(rowcol) = others
+            .get(entity)
+            .ok()
+            .and_then(|(_, _, position)| visibility.to_rowcol(position.0))
+        else {
+            return false;
+        };
+        if let Ok(stealth) = stealths.get(entity) {
+            if !stealth.is_revealed_to(rowcol, team, &detection) {
+                return false;
+            }
+        }
+        visibility.is_visible(team, rowcol)
+    };
     query.par_iter_mut().for_each(
         |(
             entity,
@@ -140,11 +164,13 @@ pub fn update(
             let enemy_teams: Vec<Team> = all::<Team>()
                 .filter(|other_team| team != other_team)
                 .collect();
-            let enemy_entities = grid.get_n_entities_in_radius(
+            // Filter before limiting, so hidden enemies don't crowd out visible ones.
+            let enemy_entities = grid.get_n_filtered_entities_in_radius(
                 position.0,
                 config.neighbor_radius,
                 &enemy_teams,
                 MAX_NEIGHBORS,
+                |other| is_visible_to(*team, other),
             );
             for neighbor in
                 get_neighbors(entity, position.0, &enemy_entities, &others, config).into_iter()
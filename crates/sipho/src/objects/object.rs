@@ -1,5 +1,4 @@
 use std::f32::consts::PI;
-use strum_macros::IntoStaticStr;
 
 use super::{
     carry::CarriedBy,
@@ -9,79 +8,29 @@ use super::{
 };
 use crate::prelude::*;
 use bevy::{ecs::query::QueryData, prelude::*};
-use enum_iterator::Sequence;
 use rand::Rng;
 
 /// Plugin for running zooids simulation.
 pub struct ObjectPlugin;
 impl Plugin for ObjectPlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<Object>().add_systems(
+        app.add_systems(
             FixedUpdate,
-            ((Object::update_force, Object::update_collisions)
-                .in_set(FixedUpdateStage::AccumulateForces),)
+            (
+                (ObjectForces::update_force, ObjectForces::update_collisions)
+                    .in_set(FixedUpdateStage::AccumulateForces),
+            )
                 .in_set(GameStateSet::Running),
         );
     }
 }
 
-/// Entities that can interact with each other.
-#[derive(
-    Component,
-    Reflect,
-    Default,
-    Copy,
-    Clone,
-    PartialEq,
-    PartialOrd,
-    Ord,
-    Eq,
-    Hash,
-    Debug,
-    clap::ValueEnum,
-    IntoStaticStr,
-    Sequence,
-)]
-#[reflect(Component)]
-pub enum Object {
-    #[default]
-    Worker,
-    Head,
-    Plankton,
-    Food,
-    Gem,
-    GemStone,
-    Shocker,
-    Armor,
-    BuilderPreview,
-}
-impl Object {
-    /// Returns true if an object can attack.
-    pub fn can_attack(self) -> bool {
-        matches!(self, Self::Worker | Self::Shocker)
-    }
-
-    /// Returns true if an object can be attacked.
-    pub fn can_be_attacked(self) -> bool {
-        true
-    }
-
-    /// Returns true if an object can carry.
-    pub fn can_carry(self) -> bool {
-        matches!(self, Self::Worker)
-    }
-
-    /// Returns true if an object can carry.
-    pub fn can_be_carried(self) -> bool {
-        matches!(self, Self::Food)
-    }
-}
-
 #[derive(QueryData)]
 #[query_data(mutable)]
 pub struct UpdateForceQueryData {
     entity: Entity,
     object: &'static Object,
+    position: &'static Position,
     velocity: &'static Velocity,
     force: &'static mut Force,
     parent: Option<&'static Parent>,
@@ -93,25 +42,17 @@ pub struct UpdateForceQueryData {
     path_follower: Option<&'static PathToHeadFollower>,
 }
 
-impl Object {
-    pub fn zindex(self) -> f32 {
-        match self {
-            Self::Worker => zindex::ZOOIDS_MIN,
-            Self::Head => zindex::ZOOID_HEAD,
-            Self::Food => zindex::FOOD,
-            Self::Gem => zindex::GEM,
-            Self::GemStone => zindex::GEMSTONE,
-            Self::Plankton => zindex::PLANKTON,
-            Self::Shocker => zindex::ZOOIDS_MIN,
-            Self::Armor => zindex::ZOOIDS_MIN,
-            Self::BuilderPreview => zindex::ZOOIDS_MIN,
-        }
-    }
+/// Systems for forces between objects.
+pub struct ObjectForces;
+impl ObjectForces {
     pub fn update_force(
         mut query: Query<UpdateForceQueryData>,
-        others: Query<(&Self, &Velocity)>,
+        others: Query<(&Object, &Velocity)>,
         configs: Res<ObjectConfigs>,
+        seabed: Res<Grid2<Seabed>>,
+        mut rng: ResMut<GameRng>,
     ) {
+        let seed = rng.gen();
         query.par_iter_mut().for_each(|mut object| {
             let mut separation_force = Force::ZERO;
             let mut alignment_force = Force::ZERO;
@@ -160,6 +101,9 @@ impl Object {
             }
             *object.force += separation_force;
 
+            // Kelp drags.
+            *object.force += seabed.force(object.position.0, *object.velocity);
+
             // When idle, slow down.
             if *object.objectives.last() == Objective::Idle
                 && object.carried_by.is_empty()
@@ -175,7 +119,7 @@ impl Object {
             }
 
             // When moving slow, spin around to create some extra movement.
-            let random_factor = rand::thread_rng().gen_range(0.8..1.0);
+            let random_factor = GameRng::for_entity(seed, object.entity).gen_range(0.8..1.0);
             let spin_amount = (config.idle_speed * 2. - object.velocity.length_squared()).max(0.0)
                 * (random_factor)
                 * 2.;
@@ -195,6 +139,7 @@ impl Object {
         )>,
         // mut carry_events: EventWriter<CarryEvent>,
         path_to_head: Query<&PathToHead>,
+        configs: Res<ObjectConfigs>,
     ) {
         for (_entity, object, collisions, mut path_follower, parent) in objects.iter_mut() {
             for neighbor in collisions.iter() {
@@ -208,7 +153,9 @@ impl Object {
                     if let Ok(path) = path_to_head.get(neighbor.entity) {
                         path_follower.target = path.next;
                     }
-                } else if object.can_carry() && neighbor.object.can_be_carried() && parent.is_none()
+                } else if configs[object].carrier
+                    && configs[&neighbor.object].carriable
+                    && parent.is_none()
                 {
                     // carry_events.send(CarryEvent {
                     //     carrier: entity,
@@ -1,50 +1,14 @@
 use crate::{prelude::*, ui::selector::HighlightBundle};
-use bevy::prelude::*;
+use bevy::{ecs::system::EntityCommands, prelude::*};
 use bevy_bundletree::*;
-use zooid_head::{HeadBundle, NearestZooidHead};
-use zooid_worker::WorkerBundle;
+use zooid_head::NearestZooidHead;
+use zooid_worker::ZooidWorker;
 
-use super::{background::BackgroundBundle, plankton::PlanktonBundle};
-
-#[derive(Bundle, Default)]
-pub struct ShockerBundle {
-    pub nearest_head: NearestZooidHead,
-    pub object: ObjectBundle,
-}
-
-#[derive(Bundle, Default)]
-pub struct ArmorBundle {
-    pub nearest_head: NearestZooidHead,
-    pub object: ObjectBundle,
-}
-
-#[derive(Bundle, Default)]
-pub struct FoodBundle {
-    pub follower: PathToHeadFollower,
-    pub object: ObjectBundle,
-}
-
-#[derive(Bundle, Default)]
-pub struct GemBundle {
-    pub follower: PathToHeadFollower,
-    pub object: ObjectBundle,
-}
-
-#[derive(Bundle, Default)]
-pub struct GemStoneBundle {
-    pub object: ObjectBundle,
-}
+use super::{background::BackgroundBundle, plankton::Plankton};
 
 #[derive(BundleEnum, IntoBundleTree)]
 pub enum ObjectTree {
-    Worker(WorkerBundle),
-    Shocker(ShockerBundle),
-    Armor(ArmorBundle),
-    Head(HeadBundle),
-    Plankton(PlanktonBundle),
-    Food(FoodBundle),
-    Gem(GemBundle),
-    GemStone(GemStoneBundle),
+    Object(ObjectBundle),
     Background(BackgroundBundle),
     Highlight(HighlightBundle),
 }
@@ -57,59 +21,47 @@ impl ObjectTree {
         config: &ObjectConfig,
         time: &Time,
     ) -> BundleTree<ObjectTree> {
-        let background = BackgroundBundle {
-            mesh: mesh.clone(),
-            material: background_material.clone(),
-            ..default()
-        };
-        let object_type = spec.object;
         let object = ObjectBundle {
             mesh: mesh.clone(),
-            material: primary_material.clone(),
+            material: primary_material,
             ..ObjectBundle::new(config, spec, time)
         };
-        match object_type {
-            Object::Worker => WorkerBundle {
-                object,
-                ..default()
-            }
-            .with_children([background.into_tree()]),
-            Object::Shocker => ShockerBundle {
-                object,
-                ..default()
-            }
-            .with_children([background.into_tree()]),
-            Object::Armor => ArmorBundle {
-                object,
-                ..default()
+        if !config.components.contains(&ObjectComponent::Background) {
+            return object.into_tree();
+        }
+        object.with_children([BackgroundBundle {
+            mesh,
+            material: background_material,
+            ..default()
+        }
+        .into_tree()])
+    }
+}
+
+impl ObjectComponent {
+    /// Add this component to a newly spawned object.
+    pub fn insert(self, entity_commands: &mut EntityCommands) {
+        match self {
+            // Spawned as a child by `ObjectTree`.
+            Self::Background => {}
+            Self::NearestHead => {
+                entity_commands.insert(NearestZooidHead::default());
             }
-            .with_children([background.into_tree()]),
-            Object::Head => HeadBundle {
-                object,
-                ..default()
+            Self::PathToHeadFollower => {
+                entity_commands.insert(PathToHeadFollower::default());
             }
-            .with_children([background.into_tree()]),
-            Object::Plankton => PlanktonBundle {
-                object,
-                ..default()
+            Self::Worker => {
+                entity_commands.insert(ZooidWorker::default());
             }
-            .with_children([background.into_tree()]),
-            Object::Food => FoodBundle {
-                object,
-                ..default()
+            Self::Head => {
+                entity_commands.insert(ZooidHead::default());
             }
-            .into_tree(),
-            Object::Gem => GemBundle {
-                object,
-                ..default()
+            Self::Consumer => {
+                entity_commands.insert(Consumer::default());
             }
-            .into_tree(),
-            Object::GemStone => GemStoneBundle {
-                object,
-                ..default()
+            Self::Plankton => {
+                entity_commands.insert(Plankton);
             }
-            .into_tree(),
-            Object::BuilderPreview => unreachable!(),
         }
     }
 }
@@ -6,18 +6,13 @@ impl Plugin for PlanktonPlugin {
         app.add_systems(
             FixedUpdate,
             (Plankton::spawn
+                .run_if(TeamConfig::is_playing)
                 .in_set(FixedUpdateStage::Spawn)
                 .in_set(GameStateSet::Running),),
         );
     }
 }
 
-#[derive(Bundle, Default)]
-pub struct PlanktonBundle {
-    pub plankton: Plankton,
-    pub object: ObjectBundle,
-}
-
 #[derive(Component, Default)]
 pub struct Plankton;
 impl Plankton {
@@ -25,7 +20,7 @@ impl Plankton {
         for control_event in control_events.read() {
             if control_event.is_pressed(ControlAction::Plankton) {
                 commands.spawn(ObjectSpec {
-                    object: Object::Plankton,
+                    object: Object::PLANKTON,
                     team: Team::None,
                     position: Position(control_event.position),
                     ..default()
@@ -5,8 +5,13 @@ use bevy::utils::{Entry, HashMap, HashSet};
 
 use super::elastic::SpawnElasticEvent;
 use super::zooid_worker::ZooidWorker;
-use super::Object;
 use super::{ObjectCommands, ObjectSpec, Team};
+
+/// Food a head consumes to see one cell further.
+const FOOD_PER_VISION: usize = 10;
+/// Most extra cells of vision a head gains from growing.
+const MAX_VISION_BONUS: u16 = 4;
+
 pub struct ZooidHeadPlugin;
 impl Plugin for ZooidHeadPlugin {
     fn build(&self, app: &mut App) {
@@ -14,9 +19,9 @@ impl Plugin for ZooidHeadPlugin {
             FixedUpdate,
             (
                 (
-                    ZooidHead::spawn,
+                    ZooidHead::spawn.run_if(TeamConfig::is_playing),
                     ZooidHead::update,
-                    ZooidHead::spawn_linked_zooids,
+                    ZooidHead::spawn_linked_zooids.run_if(TeamConfig::is_playing),
                 )
                     .chain()
                     .in_set(FixedUpdateStage::Spawn),
@@ -28,14 +33,6 @@ impl Plugin for ZooidHeadPlugin {
     }
 }
 
-#[derive(Bundle, Default)]
-pub struct HeadBundle {
-    pub worker: ZooidHead,
-    pub nearest_head: NearestZooidHead,
-    pub object: ObjectBundle,
-    pub consumer: Consumer,
-}
-
 enum SpawnedType {
     Worker,
     Shocker,
@@ -48,22 +45,28 @@ pub struct ZooidHead {
     pub spawn_index: usize,
 }
 impl ZooidHead {
-    // Increase head size based on consumer.
+    // Increase head size and vision based on consumer.
     pub fn update(
-        mut query: Query<(&mut Transform, &Consumer), With<ZooidHead>>,
+        mut query: Query<(&mut Transform, &mut Vision, &Consumer), With<ZooidHead>>,
         configs: Res<ObjectConfigs>,
     ) {
-        let config = configs.get(&Object::Head).unwrap();
-        for (mut transform, consumer) in query.iter_mut() {
+        let config = configs.get(&Object::HEAD).unwrap();
+        for (mut transform, mut vision, consumer) in query.iter_mut() {
             let count = 1. + consumer.food_consumed() as f32 / 20.;
 
-            transform.scale = Vec3::splat(config.radius * 1.5 * count / (count + 1.))
+            transform.scale = Vec3::splat(config.radius * 1.5 * count / (count + 1.));
+
+            let bonus = ((consumer.food_consumed() / FOOD_PER_VISION) as u16).min(MAX_VISION_BONUS);
+            let radius = config.vision_radius + bonus;
+            if vision.radius != radius {
+                vision.radius = radius;
+            }
         }
     }
 
     pub fn spawn_initial(mut commands: ObjectCommands, config: Res<TeamConfig>) {
         commands.spawn(ObjectSpec {
-            object: Object::Head,
+            object: Object::HEAD,
             position: Position::ZERO,
             selected: true,
             team: config.player_team,
@@ -71,7 +74,7 @@ impl ZooidHead {
         });
         for _ in 0..20 {
             commands.spawn(ObjectSpec {
-                object: Object::Food,
+                object: Object::FOOD,
                 position: Position::ZERO,
                 ..default()
             });
@@ -88,14 +91,14 @@ impl ZooidHead {
         for control_event in control_events.read() {
             if control_event.is_pressed(ControlAction::Head) {
                 commands.spawn(ObjectSpec {
-                    object: Object::Head,
+                    object: Object::HEAD,
                     position: Position(control_event.position),
                     team: config.player_team,
                     ..default()
                 });
                 for _ in 0..20 {
                     commands.spawn(ObjectSpec {
-                        object: Object::Food,
+                        object: Object::FOOD,
                         position: Position(control_event.position),
                         ..default()
                     });
@@ -107,10 +110,10 @@ impl ZooidHead {
                 for (_, entity) in query.iter() {
                     killable.push(entity);
 
-                    if killable.len() >= obj_config.get(&Object::Head).unwrap().spawn_cost as usize
+                    if killable.len() >= obj_config.get(&Object::HEAD).unwrap().spawn_cost as usize
                     {
                         commands.spawn(ObjectSpec {
-                            object: Object::Head,
+                            object: Object::HEAD,
                             position: Position(control_event.position),
                             team: config.player_team,
                             ..default()
@@ -197,6 +200,69 @@ impl ZooidHead {
 
     pub fn get_shortest_leg_length() {}
 
+    /// Direction and speed a head launches new objects at.
+    fn spawn_velocity(velocity: &Velocity, config: &ObjectConfig) -> Velocity {
+        let direction = velocity.try_normalize().unwrap_or(Vec2::Y);
+        Velocity(direction * config.spawn_velocity)
+    }
+
+    /// Grow an object on the head's shortest limb, paying its food cost.
+    /// Shared by the player's controls and computer players.
+    #[allow(clippy::too_many_arguments)]
+    pub fn grow(
+        &mut self,
+        head: Entity,
+        object: Object,
+        velocity: &Velocity,
+        team: &Team,
+        attachments: &Query<&AttachedTo>,
+        positions: &Query<&Position>,
+        configs: &ObjectConfigs,
+        commands: &mut ObjectCommands,
+        elastic_events: &mut EventWriter<SpawnElasticEvent>,
+        audio: &mut EventWriter<AudioEvent>,
+    ) -> Option<Entity> {
+        let config = configs.get(&object)?;
+        let (limb, _) = self.get_next_limb(head, attachments);
+        let position = *positions.get(limb).ok()?;
+        commands.try_consume(head, config.food_cost).ok()?;
+        self.make_linked(
+            &Self::spawn_velocity(velocity, config),
+            elastic_events,
+            audio,
+            &position,
+            team,
+            object,
+            commands,
+            limb,
+        )
+    }
+
+    /// Spawn a free object next to the head, paying its food cost.
+    pub fn bud(
+        head: Entity,
+        object: Object,
+        velocity: &Velocity,
+        team: &Team,
+        positions: &Query<&Position>,
+        configs: &ObjectConfigs,
+        commands: &mut ObjectCommands,
+    ) -> Option<Entity> {
+        let config = configs.get(&object)?;
+        let position = *positions.get(head).ok()?;
+        commands.try_consume(head, config.food_cost).ok()?;
+        let spawn_velocity = Self::spawn_velocity(velocity, config);
+        commands
+            .spawn(ObjectSpec {
+                object,
+                team: *team,
+                position: Position(position.0 + spawn_velocity.0),
+                velocity: Some(spawn_velocity),
+                ..default()
+            })
+            .map(|entity_commands| entity_commands.id())
+    }
+
     /// System to spawn zooids on Z key.
     #[allow(clippy::too_many_arguments)]
     pub fn spawn_linked_zooids(
@@ -209,7 +275,6 @@ impl ZooidHead {
         mut elastic_events: EventWriter<SpawnElasticEvent>,
         mut audio: EventWriter<AudioEvent>,
     ) {
-        let config = configs.get(&Object::Worker).unwrap();
         for control_event in control_events.read() {
             let spawn_type = if control_event.is_pressed(ControlAction::Grow) {
                 SpawnedType::Worker
@@ -218,38 +283,23 @@ impl ZooidHead {
             } else {
                 continue;
             };
+            let object = match spawn_type {
+                SpawnedType::Shocker => Object::SHOCKER,
+                SpawnedType::Worker => Object::WORKER,
+            };
             for (mut head, head_id, velocity, team) in query.iter_mut() {
-                // Find the shortest leg to spawn an entity onto.
-                // Spawn first entity.
-                let (entity, _) = head.get_next_limb(head_id, &attachments);
-
-                let position = positions.get(entity).unwrap();
-                let food_needed = match spawn_type {
-                    SpawnedType::Shocker => 3,
-                    SpawnedType::Worker => 1,
-                };
-                if commands.try_consume(head_id, food_needed).is_ok() {
-                    let direction = if let Some(normalized) = velocity.try_normalize() {
-                        normalized
-                    } else {
-                        Vec2::Y
-                    };
-                    let spawn_velocity: Vec2 = direction * config.spawn_velocity;
-                    Self::make_linked(
-                        &mut head,
-                        &Velocity(spawn_velocity),
-                        &mut elastic_events,
-                        &mut audio,
-                        position,
-                        team,
-                        match spawn_type {
-                            SpawnedType::Shocker => Object::Shocker,
-                            SpawnedType::Worker => Object::Worker,
-                        },
-                        &mut commands,
-                        entity,
-                    );
-                }
+                head.grow(
+                    head_id,
+                    object,
+                    velocity,
+                    team,
+                    &attachments,
+                    &positions,
+                    &configs,
+                    &mut commands,
+                    &mut elastic_events,
+                    &mut audio,
+                );
             }
             break;
         }
@@ -1,24 +1,22 @@
+use crate::behavior::{leaves::*, BehaviorNode, BehaviorTree};
 use crate::prelude::*;
-use crate::zooid_head::NearestZooidHead;
 
 pub struct ZooidWorkerPlugin;
 impl Plugin for ZooidWorkerPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             FixedUpdate,
-            (ZooidWorker::debug_spawn.in_set(FixedUpdateStage::Spawn),)
+            (
+                ZooidWorker::debug_spawn
+                    .run_if(TeamConfig::is_playing)
+                    .in_set(FixedUpdateStage::Spawn),
+                ZooidWorker::add_behavior.in_set(FixedUpdateStage::PostSpawn),
+            )
                 .in_set(GameStateSet::Running),
         );
     }
 }
 
-#[derive(Bundle, Default)]
-pub struct WorkerBundle {
-    pub worker: ZooidWorker,
-    pub nearest_head: NearestZooidHead,
-    pub object: ObjectBundle,
-}
-
 /// State for an individual zooid.
 #[derive(Component, Reflect, Clone)]
 #[reflect(Component)]
@@ -31,6 +29,23 @@ impl Default for ZooidWorker {
     }
 }
 impl ZooidWorker {
+    /// Free workers back off from their target while their dash is on cooldown.
+    pub fn behavior() -> BehaviorNode {
+        BehaviorNode::sequence([
+            BehaviorNode::leaf(Detached),
+            BehaviorNode::leaf(OnCooldown),
+            BehaviorNode::leaf(Kite { force: 0.5 }),
+        ])
+    }
+
+    pub fn add_behavior(query: Query<Entity, Added<ZooidWorker>>, mut commands: Commands) {
+        for entity in query.iter() {
+            commands
+                .entity(entity)
+                .insert(BehaviorTree::new(Self::behavior()));
+        }
+    }
+
     pub fn debug_spawn(
         mut commands: ObjectCommands,
         mut control_events: EventReader<ControlEvent>,
@@ -45,7 +60,7 @@ impl ZooidWorker {
             };
             if let Some(team) = team {
                 commands.spawn(ObjectSpec {
-                    object: Object::Worker,
+                    object: Object::WORKER,
                     team,
                     position: Position(control_event.position),
                     ..default()
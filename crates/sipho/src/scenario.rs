@@ -0,0 +1,293 @@
+use crate::{
+    ai::{AiDifficulty, AiSettings},
+    creatures::SpawnCreatureEvent,
+    prelude::*,
+    scene::ScenePaths,
+};
+use sipho_core::grid::{
+    currents::CurrentSource,
+    fog::{FogConfig, TeamVisibility},
+};
+
+/// Plugin for scripted scenarios.
+/// Triggers are loaded from a scenario file and fire actions once their condition is met.
+pub struct ScenarioPlugin;
+impl Plugin for ScenarioPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Scenario>()
+            .register_type::<Trigger>()
+            .register_type::<Vec<Trigger>>()
+            .register_type::<TriggerCondition>()
+            .register_type::<TriggerAction>()
+            .register_type::<Vec<TriggerAction>>()
+            .register_type::<MissionObjective>()
+            .register_type::<MissionStatus>()
+            .register_type::<ObjectSpec>()
+            .register_type::<SpawnCreatureEvent>()
+            .init_resource::<Scenario>()
+            .init_resource::<ScenarioState>()
+            .init_resource::<MissionObjectives>()
+            .add_event::<TriggerEvent>()
+            .add_systems(PreStartup, Scenario::load)
+            .add_systems(OnExit(GameState::Loading), Scenario::add_currents)
+            .add_systems(
+                FixedUpdate,
+                (Scenario::update, TriggerEvent::update)
+                    .chain()
+                    .in_set(FixedUpdateStage::Spawn)
+                    .in_set(GameStateSet::Running),
+            );
+    }
+}
+
+/// Triggers for the current mission.
+#[derive(Resource, Clone, Default, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct Scenario {
+    pub triggers: Vec<Trigger>,
+    /// Currents only present on this map, added to the ones from the config.
+    #[reflect(default)]
+    pub currents: Vec<CurrentSource>,
+}
+impl Scenario {
+    pub fn load(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        mut load_state: ResMut<AssetLoadState>,
+        paths: Res<ScenePaths>,
+    ) {
+        let scene = DynamicSceneBundle {
+            scene: asset_server.load(paths.scenario.clone()),
+            ..default()
+        };
+        load_state.track(&scene.scene);
+        commands.spawn((Name::new("Scenario"), scene));
+    }
+
+    /// Add the map's currents once both the config and scenario are loaded.
+    pub fn add_currents(scenario: Res<Self>, mut currents: ResMut<CurrentsSpec>) {
+        currents.extend(scenario.currents.iter().cloned());
+    }
+
+    /// Check trigger conditions and fire the triggers that are met.
+    pub fn update(
+        scenario: Res<Self>,
+        mut state: ResMut<ScenarioState>,
+        time: Res<Time>,
+        grid: Res<Grid2<TeamEntitySets>>,
+        objects: Query<(&Object, &Team)>,
+        consumers: Query<(&Consumer, &Team)>,
+        mut events: EventWriter<TriggerEvent>,
+    ) {
+        state.elapsed += time.delta_seconds();
+        state
+            .triggers
+            .resize(scenario.triggers.len(), TriggerState::default());
+
+        let elapsed = state.elapsed;
+        for (trigger, trigger_state) in scenario.triggers.iter().zip(state.triggers.iter_mut()) {
+            if trigger_state.fired {
+                continue;
+            }
+            let met = match trigger.condition {
+                TriggerCondition::EnterRegion { team, min, max } => grid
+                    .get_entities_in_aabb(&Aabb2 { min, max })
+                    .into_iter()
+                    .any(|entity| {
+                        objects
+                            .get(entity)
+                            .is_ok_and(|(_, &entity_team)| entity_team == team)
+                    }),
+                TriggerCondition::FoodAtLeast { team, amount } => {
+                    let food: usize = consumers
+                        .iter()
+                        .filter(|(_, &consumer_team)| consumer_team == team)
+                        .map(|(consumer, _)| consumer.food_consumed())
+                        .sum();
+                    food >= amount
+                }
+                TriggerCondition::TimerElapsed(seconds) => elapsed >= seconds,
+                TriggerCondition::ObjectsDestroyed { team, object } => {
+                    let count = objects
+                        .iter()
+                        .filter(|(&other_object, &other_team)| {
+                            other_object == object && other_team == team
+                        })
+                        .count();
+                    // Only count as destroyed once we have seen at least one object.
+                    trigger_state.armed |= count > 0;
+                    trigger_state.armed && count == 0
+                }
+            };
+            if met {
+                info!("Scenario trigger: {}", trigger.name);
+                trigger_state.fired = true;
+                events.send(TriggerEvent {
+                    actions: trigger.actions.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Runtime state of the scenario.
+#[derive(Resource, Default, Debug)]
+pub struct ScenarioState {
+    /// Seconds since the scenario started.
+    pub elapsed: f32,
+    pub triggers: Vec<TriggerState>,
+}
+
+/// Runtime state of a single trigger.
+#[derive(Default, Debug, Clone)]
+pub struct TriggerState {
+    pub fired: bool,
+    /// True once the watched objects have been seen at least once.
+    pub armed: bool,
+}
+
+/// Fires the actions once the condition is met.
+#[derive(Clone, Reflect, Debug)]
+pub struct Trigger {
+    pub name: String,
+    pub condition: TriggerCondition,
+    pub actions: Vec<TriggerAction>,
+}
+
+#[derive(Clone, Reflect, Debug)]
+pub enum TriggerCondition {
+    /// An entity of the team is within the region.
+    EnterRegion { team: Team, min: Vec2, max: Vec2 },
+    /// The team has consumed at least this much food.
+    FoodAtLeast { team: Team, amount: usize },
+    /// Seconds since the scenario started.
+    TimerElapsed(f32),
+    /// The team no longer has any of the given object.
+    ObjectsDestroyed { team: Team, object: Object },
+}
+
+#[derive(Clone, Reflect, Debug)]
+pub enum TriggerAction {
+    SpawnObject(ObjectSpec),
+    /// Hand control of a team to the computer, or back to nobody.
+    SetAi {
+        team: Team,
+        difficulty: Option<AiDifficulty>,
+    },
+    SpawnCreature(SpawnCreatureEvent),
+    ShowMessage(String),
+    /// Reveal the fog around a position for a team.
+    RevealFog {
+        team: Team,
+        position: Vec2,
+        radius: u16,
+    },
+    /// Add or update a mission objective by id.
+    SetObjective(MissionObjective),
+    EndGame {
+        winner: Team,
+    },
+}
+
+/// Event to run a trigger's actions.
+#[derive(Event, Debug)]
+pub struct TriggerEvent {
+    pub actions: Vec<TriggerAction>,
+}
+impl TriggerEvent {
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
+        mut events: EventReader<Self>,
+        mut commands: ObjectCommands,
+        mut creatures: EventWriter<SpawnCreatureEvent>,
+        mut mission: ResMut<MissionObjectives>,
+        mut ai_settings: ResMut<AiSettings>,
+        mut visibility: ResMut<Grid2<TeamVisibility>>,
+        mut visibility_events: EventWriter<VisibilityUpdateEvent>,
+        fog_config: Res<FogConfig>,
+        team_config: Res<TeamConfig>,
+        mut next_state: ResMut<NextState<GameState>>,
+        mut next_physics_state: ResMut<NextState<PhysicsSimulationState>>,
+    ) {
+        for event in events.read() {
+            for action in event.actions.iter() {
+                match action {
+                    TriggerAction::SpawnObject(spec) => {
+                        commands.spawn(spec.clone());
+                    }
+                    TriggerAction::SetAi { team, difficulty } => {
+                        if let Some(difficulty) = difficulty {
+                            ai_settings.teams.insert(*team, *difficulty);
+                        } else {
+                            ai_settings.teams.remove(team);
+                        }
+                    }
+                    TriggerAction::SpawnCreature(creature) => {
+                        creatures.send(creature.clone());
+                    }
+                    TriggerAction::ShowMessage(message) => {
+                        mission.message.clone_from(message);
+                    }
+                    TriggerAction::RevealFog {
+                        team,
+                        position,
+                        radius,
+                    } => {
+                        if let Some(rowcol) = visibility.to_rowcol(*position) {
+                            visibility_events.send(VisibilityUpdateEvent {
+                                additions: visibility.reveal(rowcol, *radius, *team, &fog_config),
+                                ..default()
+                            });
+                        }
+                    }
+                    TriggerAction::SetObjective(objective) => {
+                        mission.set(objective.clone());
+                    }
+                    TriggerAction::EndGame { winner } => {
+                        mission.message = if *winner == team_config.player_team {
+                            "Victory!".to_string()
+                        } else {
+                            "Defeat.".to_string()
+                        };
+                        next_state.set(GameState::GameOver);
+                        next_physics_state.set(PhysicsSimulationState::Paused);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Reflect, Debug)]
+pub enum MissionStatus {
+    #[default]
+    InProgress,
+    Completed,
+    Failed,
+}
+
+/// A mission goal shown in the HUD.
+#[derive(Default, Clone, Reflect, Debug)]
+#[reflect(Default)]
+pub struct MissionObjective {
+    pub id: String,
+    pub text: String,
+    pub status: MissionStatus,
+}
+
+/// Current mission goals and the latest scenario message.
+#[derive(Resource, Default, Debug)]
+pub struct MissionObjectives {
+    pub objectives: Vec<MissionObjective>,
+    pub message: String,
+}
+impl MissionObjectives {
+    /// Replace the objective with the same id, or add it if it is new.
+    pub fn set(&mut self, objective: MissionObjective) {
+        if let Some(existing) = self.objectives.iter_mut().find(|o| o.id == objective.id) {
+            *existing = objective;
+        } else {
+            self.objectives.push(objective);
+        }
+    }
+}
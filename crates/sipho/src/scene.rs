@@ -10,6 +10,7 @@ pub struct LoadableScenePlugin;
 impl Plugin for LoadableScenePlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<SaveEvent>()
+            .init_resource::<ScenePaths>()
             .register_type::<SaveEntity>()
             .register_type::<Name>()
             .register_type::<Health>()
@@ -29,15 +30,38 @@ pub struct SaveEntity;
 
 // The initial scene file will be loaded below and not change when the scene is saved
 const SCENE_FILE_PATH: &str = "scenes/config.scn.ron";
+const SCENARIO_FILE_PATH: &str = "scenes/scenario.scn.ron";
+/// Scenario that teaches the basics against a computer player.
+pub const TUTORIAL_FILE_PATH: &str = "scenes/tutorial.scn.ron";
+const LOCALIZATION_FILE_PATH: &str = "scenes/localization.scn.ron";
+
+/// Asset paths of the scenes loaded at startup.
+/// Insert before adding the plugin to play with different configs or scenarios.
+#[derive(Resource, Clone, Debug)]
+pub struct ScenePaths {
+    pub config: String,
+    pub scenario: String,
+    pub localization: String,
+}
+impl Default for ScenePaths {
+    fn default() -> Self {
+        Self {
+            config: SCENE_FILE_PATH.to_string(),
+            scenario: SCENARIO_FILE_PATH.to_string(),
+            localization: LOCALIZATION_FILE_PATH.to_string(),
+        }
+    }
+}
 
 pub fn load_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut load_state: ResMut<AssetLoadState>,
+    paths: Res<ScenePaths>,
 ) {
     let scene = DynamicSceneBundle {
         // Scenes are loaded just like any other asset.
-        scene: asset_server.load(SCENE_FILE_PATH),
+        scene: asset_server.load(paths.config.clone()),
         ..default()
     };
     load_state.track(&scene.scene);
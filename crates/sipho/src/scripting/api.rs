@@ -0,0 +1,237 @@
+//! Functions exposed to scripts.
+//!
+//! Entities are passed to scripts as integer ids, objects and teams as their names.
+//! - `entities(team)`, `entities_in_radius(x, y, radius)`: lists of entity ids.
+//! - `object(id)`, `team(id)`, `health(id)`, `position(id)`: read entity state.
+//! - `attack(id, target)`, `follow(id, target)`, `idle(id)`: change objectives.
+//! - `spawn(object, team, x, y)`: spawn an object.
+//! - `set_obstacle(x, y, blocked)`: block or clear the grid cell at a position.
+//!
+//! Scripts subscribe to events by defining `on_tick()`, `on_damage(damager, damaged, amount)`
+//! and `on_death(id, object, team)`. Reads see the simulation as of the last `on_tick()`,
+//! so event handlers should rely on their arguments for the latest state.
+use std::sync::{Arc, Mutex};
+
+use bevy::utils::HashMap;
+use enum_iterator::all;
+use rhai::{Array, Dynamic, Engine, ImmutableString, Map, FLOAT, INT};
+
+use crate::prelude::*;
+
+/// State of an entity visible to scripts.
+#[derive(Debug, Clone)]
+pub struct ScriptEntity {
+    pub object: Object,
+    pub team: Team,
+    pub health: i32,
+    pub position: Vec2,
+}
+
+/// Commands queued by scripts, applied after all scripts have run.
+#[derive(Debug)]
+pub enum ScriptCommand {
+    PushObjective(Entity, Objective),
+    ClearObjectives(Entity),
+    Spawn(ObjectSpec),
+    SetObstacle(Vec2, Obstacle),
+}
+
+/// Snapshot of the simulation that scripts can read.
+#[derive(Default)]
+pub struct ScriptContext {
+    pub grid: SparseGrid2<TeamEntitySets>,
+    pub entities: HashMap<Entity, ScriptEntity>,
+    pub commands: Vec<ScriptCommand>,
+}
+impl ScriptContext {
+    /// Copy the occupied grid cells and object state.
+    pub fn snapshot(
+        &mut self,
+        grid: &Grid2<TeamEntitySets>,
+        query: &Query<(Entity, &Object, &Team, &Position, Option<&Health>)>,
+    ) {
+        self.grid.resize_with(grid.spec.clone());
+        self.grid.cells.clear();
+        for row in 0..grid.rows {
+            for col in 0..grid.cols {
+                let sets = &grid[(row, col)];
+                if sets.iter().any(|set| !set.is_empty()) {
+                    self.grid.cells.insert((row, col), sets.clone());
+                }
+            }
+        }
+
+        self.entities.clear();
+        for (entity, &object, &team, position, health) in query.iter() {
+            self.entities.insert(
+                entity,
+                ScriptEntity {
+                    object,
+                    team,
+                    health: health.map(|health| health.health).unwrap_or_default(),
+                    position: position.0,
+                },
+            );
+        }
+    }
+
+    pub fn entities_in_radius(&self, position: Vec2, radius: f32) -> Vec<Entity> {
+        let mut result = Vec::new();
+        for rowcol in self.grid.get_in_radius(position, radius) {
+            let Some(sets) = self.grid.get(rowcol) else {
+                continue;
+            };
+            for set in sets.iter() {
+                result.extend(set.iter().copied().filter(|entity| {
+                    self.entities.get(entity).is_some_and(|other| {
+                        other.position.distance_squared(position) <= radius * radius
+                    })
+                }));
+            }
+        }
+        result
+    }
+}
+
+pub fn entity_id(entity: Entity) -> INT {
+    entity.to_bits() as INT
+}
+
+fn entity_from_id(id: INT) -> Option<Entity> {
+    Entity::try_from_bits(id as u64).ok()
+}
+
+fn parse_team(name: &str) -> Option<Team> {
+    all::<Team>().find(|team| format!("{:?}", team) == name)
+}
+
+/// Register the script API on the engine.
+pub fn register(engine: &mut Engine, context: Arc<Mutex<ScriptContext>>) {
+    let read = {
+        let context = context.clone();
+        move |id: INT| -> Option<ScriptEntity> {
+            let context = context.lock().unwrap();
+            context.entities.get(&entity_from_id(id)?).cloned()
+        }
+    };
+    let push = {
+        let context = context.clone();
+        move |command: ScriptCommand| context.lock().unwrap().commands.push(command)
+    };
+
+    {
+        let context = context.clone();
+        engine.register_fn("entities", move |team: ImmutableString| -> Array {
+            let team = parse_team(&team);
+            let context = context.lock().unwrap();
+            context
+                .entities
+                .iter()
+                .filter(|(_, entity)| Some(entity.team) == team)
+                .map(|(&entity, _)| Dynamic::from(entity_id(entity)))
+                .collect()
+        });
+    }
+    engine.register_fn(
+        "entities_in_radius",
+        move |x: FLOAT, y: FLOAT, radius: FLOAT| -> Array {
+            let context = context.lock().unwrap();
+            context
+                .entities_in_radius(Vec2::new(x as f32, y as f32), radius as f32)
+                .into_iter()
+                .map(|entity| Dynamic::from(entity_id(entity)))
+                .collect()
+        },
+    );
+    {
+        let read = read.clone();
+        engine.register_fn("object", move |id: INT| -> String {
+            read(id)
+                .map(|entity| format!("{:?}", entity.object))
+                .unwrap_or_default()
+        });
+    }
+    {
+        let read = read.clone();
+        engine.register_fn("team", move |id: INT| -> String {
+            read(id)
+                .map(|entity| format!("{:?}", entity.team))
+                .unwrap_or_default()
+        });
+    }
+    {
+        let read = read.clone();
+        engine.register_fn("health", move |id: INT| -> INT {
+            read(id).map(|entity| entity.health as INT).unwrap_or(0)
+        });
+    }
+    engine.register_fn("position", move |id: INT| -> Dynamic {
+        let Some(entity) = read(id) else {
+            return Dynamic::UNIT;
+        };
+        let mut map = Map::new();
+        map.insert("x".into(), Dynamic::from(entity.position.x as FLOAT));
+        map.insert("y".into(), Dynamic::from(entity.position.y as FLOAT));
+        Dynamic::from_map(map)
+    });
+
+    {
+        let push = push.clone();
+        engine.register_fn("attack", move |id: INT, target: INT| {
+            if let (Some(entity), Some(target)) = (entity_from_id(id), entity_from_id(target)) {
+                push(ScriptCommand::PushObjective(
+                    entity,
+                    Objective::AttackEntity(target),
+                ));
+            }
+        });
+    }
+    {
+        let push = push.clone();
+        engine.register_fn("follow", move |id: INT, target: INT| {
+            if let (Some(entity), Some(target)) = (entity_from_id(id), entity_from_id(target)) {
+                push(ScriptCommand::PushObjective(
+                    entity,
+                    Objective::FollowEntity(target),
+                ));
+            }
+        });
+    }
+    {
+        let push = push.clone();
+        engine.register_fn("idle", move |id: INT| {
+            if let Some(entity) = entity_from_id(id) {
+                push(ScriptCommand::ClearObjectives(entity));
+            }
+        });
+    }
+    {
+        let push = push.clone();
+        engine.register_fn("set_obstacle", move |x: FLOAT, y: FLOAT, blocked: bool| {
+            let obstacle = if blocked {
+                Obstacle::Full
+            } else {
+                Obstacle::Empty
+            };
+            push(ScriptCommand::SetObstacle(
+                Vec2::new(x as f32, y as f32),
+                obstacle,
+            ));
+        });
+    }
+    engine.register_fn(
+        "spawn",
+        move |object: ImmutableString, team: ImmutableString, x: FLOAT, y: FLOAT| -> bool {
+            let Some(team) = parse_team(&team) else {
+                return false;
+            };
+            push(ScriptCommand::Spawn(ObjectSpec {
+                object: Object::new(&object),
+                team,
+                position: Position(Vec2::new(x as f32, y as f32)),
+                ..default()
+            }));
+            true
+        },
+    );
+}
@@ -0,0 +1,254 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadedFolder},
+    utils::{HashMap, HashSet},
+};
+use rhai::{CallFnOptions, Dynamic, Engine, FuncArgs, Scope, AST, INT};
+
+use crate::prelude::*;
+
+use self::api::{ScriptCommand, ScriptContext};
+
+pub mod api;
+
+/// Plugin for running Rhai scripts from `assets/scripts`.
+/// Scripts only see a snapshot of the simulation taken every tick and queue commands that are applied afterwards.
+pub struct ScriptingPlugin;
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Script>()
+            .init_asset_loader::<ScriptLoader>()
+            .init_resource::<ScriptRuntime>()
+            .add_systems(Startup, ScriptRuntime::setup)
+            .add_systems(Update, ScriptRuntime::reload)
+            .add_systems(
+                FixedUpdate,
+                ScriptRuntime::update
+                    .in_set(FixedUpdateStage::AI)
+                    .in_set(GameStateSet::Running),
+            );
+    }
+}
+
+const SCRIPTS_FOLDER: &str = "scripts";
+
+/// Source code of a Rhai script.
+#[derive(Asset, TypePath, Debug)]
+pub struct Script {
+    pub source: String,
+}
+
+#[derive(Default)]
+pub struct ScriptLoader;
+impl AssetLoader for ScriptLoader {
+    type Asset = Script;
+    type Settings = ();
+    type Error = std::io::Error;
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Script, Self::Error> {
+        let mut source = String::new();
+        reader.read_to_string(&mut source).await?;
+        Ok(Script { source })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["rhai"]
+    }
+}
+
+/// A compiled script along with the scope its top level statements ran in.
+struct ScriptInstance {
+    ast: AST,
+    scope: Scope<'static>,
+    functions: HashSet<String>,
+}
+
+/// Runs loaded scripts against a snapshot of the simulation.
+#[derive(Resource)]
+pub struct ScriptRuntime {
+    engine: Engine,
+    context: Arc<Mutex<ScriptContext>>,
+    scripts: HashMap<AssetId<Script>, ScriptInstance>,
+    /// Keeps the script handles alive.
+    folder: Handle<LoadedFolder>,
+    tick_timer: Timer,
+}
+impl Default for ScriptRuntime {
+    fn default() -> Self {
+        let context = Arc::new(Mutex::new(ScriptContext::default()));
+        let mut engine = Engine::new();
+        // Keep misbehaving scripts from stalling the simulation.
+        engine
+            .set_max_operations(100_000)
+            .set_max_call_levels(32)
+            .set_max_string_size(1024)
+            .set_max_array_size(4096)
+            .set_max_map_size(256)
+            .on_print(|text| info!("[script] {}", text))
+            .on_debug(|text, source, position| {
+                debug!("[script {:?} {}] {}", source, position, text)
+            });
+        api::register(&mut engine, context.clone());
+        Self {
+            engine,
+            context,
+            scripts: HashMap::default(),
+            folder: Handle::default(),
+            tick_timer: Timer::from_seconds(0.25, TimerMode::Repeating),
+        }
+    }
+}
+impl ScriptRuntime {
+    pub fn setup(mut runtime: ResMut<Self>, asset_server: Res<AssetServer>) {
+        runtime.folder = asset_server.load_folder(SCRIPTS_FOLDER);
+    }
+
+    /// Compile scripts when they are loaded or modified on disk.
+    pub fn reload(
+        mut runtime: ResMut<Self>,
+        mut events: EventReader<AssetEvent<Script>>,
+        scripts: Res<Assets<Script>>,
+        asset_server: Res<AssetServer>,
+    ) {
+        for event in events.read() {
+            match *event {
+                AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                    let Some(script) = scripts.get(id) else {
+                        continue;
+                    };
+                    let path = asset_server.get_path(id);
+                    match runtime.compile(&script.source) {
+                        Ok(instance) => {
+                            info!("Loaded script {:?}", path);
+                            runtime.scripts.insert(id, instance);
+                        }
+                        Err(error) => error!("Failed to load script {:?}: {}", path, error),
+                    }
+                }
+                AssetEvent::Removed { id } | AssetEvent::Unused { id } => {
+                    runtime.scripts.remove(&id);
+                }
+                AssetEvent::LoadedWithDependencies { .. } => {}
+            }
+        }
+    }
+
+    /// Compile a script and run its top level statements.
+    fn compile(&self, source: &str) -> Result<ScriptInstance, String> {
+        let ast = self.engine.compile(source).map_err(|e| e.to_string())?;
+        let mut scope = Scope::new();
+        self.engine
+            .run_ast_with_scope(&mut scope, &ast)
+            .map_err(|e| e.to_string())?;
+        let functions = ast.iter_functions().map(|f| f.name.to_string()).collect();
+        Ok(ScriptInstance {
+            ast,
+            scope,
+            functions,
+        })
+    }
+
+    /// Call the function in every script that defines it.
+    fn call(&mut self, name: &str, args: impl FuncArgs + Clone) {
+        for instance in self.scripts.values_mut() {
+            if !instance.functions.contains(name) {
+                continue;
+            }
+            // Don't re-run the top level statements on every call.
+            let options = CallFnOptions::new().eval_ast(false).rewind_scope(true);
+            if let Err(error) = self.engine.call_fn_with_options::<Dynamic>(
+                options,
+                &mut instance.scope,
+                &instance.ast,
+                name,
+                args.clone(),
+            ) {
+                error!("Script error in {}: {}", name, error);
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
+        mut runtime: ResMut<Self>,
+        time: Res<Time>,
+        grid: Res<Grid2<TeamEntitySets>>,
+        query: Query<(Entity, &Object, &Team, &Position, Option<&Health>)>,
+        mut objectives: Query<&mut Objectives>,
+        mut damage_events: EventReader<DamageEvent>,
+        mut death_events: EventReader<DeathEvent>,
+        mut commands: ObjectCommands,
+        mut obstacle_events: EventWriter<ObstacleEditEvent>,
+    ) {
+        if runtime.scripts.is_empty() {
+            damage_events.clear();
+            death_events.clear();
+            return;
+        }
+        let tick = runtime.tick_timer.tick(time.delta()).just_finished();
+        if damage_events.is_empty() && death_events.is_empty() && !tick {
+            return;
+        }
+
+        // Copying the simulation is expensive, so event handlers read the snapshot from the last tick.
+        if tick {
+            runtime.context.lock().unwrap().snapshot(&grid, &query);
+        }
+        for event in damage_events.read() {
+            runtime.call(
+                "on_damage",
+                (
+                    api::entity_id(event.damager),
+                    api::entity_id(event.damaged),
+                    event.amount as INT,
+                ),
+            );
+        }
+        for event in death_events.read() {
+            runtime.call(
+                "on_death",
+                (
+                    api::entity_id(event.entity),
+                    format!("{:?}", event.object),
+                    format!("{:?}", event.team),
+                ),
+            );
+        }
+        if tick {
+            runtime.call("on_tick", ());
+        }
+
+        let script_commands = std::mem::take(&mut runtime.context.lock().unwrap().commands);
+        let mut obstacle_edits = ObstacleEditEvent::default();
+        for command in script_commands {
+            match command {
+                ScriptCommand::PushObjective(entity, objective) => {
+                    if let Ok(mut objectives) = objectives.get_mut(entity) {
+                        objectives.push(objective);
+                    }
+                }
+                ScriptCommand::ClearObjectives(entity) => {
+                    if let Ok(mut objectives) = objectives.get_mut(entity) {
+                        objectives.clear();
+                    }
+                }
+                ScriptCommand::Spawn(spec) => {
+                    commands.spawn(spec);
+                }
+                ScriptCommand::SetObstacle(position, obstacle) => {
+                    if let Some(rowcol) = grid.to_rowcol(position) {
+                        obstacle_edits.edits.push((rowcol, obstacle));
+                    }
+                }
+            }
+        }
+        if !obstacle_edits.edits.is_empty() {
+            obstacle_events.send(obstacle_edits);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::prelude::*;
+use bevy::input::ButtonState;
+use sipho_core::grid::fog::FogConfig;
+
+/// Plugin for watching a match without controlling a team.
+pub struct SpectatorPlugin;
+impl Plugin for SpectatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Spectator>().add_systems(
+            Update,
+            (Spectator::update_controls, Spectator::follow_battle)
+                .chain()
+                .before(CameraController::update_screen_control)
+                .in_set(GameStateSet::Running),
+        );
+    }
+}
+
+/// Size in cells of the blocks searched for battles.
+pub const BATTLE_BLOCK: u16 = 8;
+/// Seconds between searches for the largest battle.
+pub const BATTLE_SEARCH_SECONDS: f32 = 1.0;
+/// How quickly the camera catches up with the battle it follows.
+pub const FOLLOW_SPEED: f32 = 2.0;
+
+/// State of the observer's view.
+#[derive(Resource)]
+pub struct Spectator {
+    /// True if the camera follows the largest battle.
+    pub follow_battle: bool,
+    /// Where the largest battle was last found.
+    pub battle: Option<Vec2>,
+    search_timer: Timer,
+}
+impl Default for Spectator {
+    fn default() -> Self {
+        Self {
+            follow_battle: false,
+            battle: None,
+            search_timer: Timer::from_seconds(BATTLE_SEARCH_SECONDS, TimerMode::Repeating),
+        }
+    }
+}
+impl Spectator {
+    /// Switch the fog overlay between teams and toggle following battles.
+    pub fn update_controls(
+        mut spectator: ResMut<Self>,
+        mut controls: EventReader<ControlEvent>,
+        mut fog_config: ResMut<FogConfig>,
+        team_config: Res<TeamConfig>,
+    ) {
+        if !team_config.observer {
+            return;
+        }
+        for control in controls.read() {
+            if control.state != ButtonState::Pressed {
+                continue;
+            }
+            match control.action {
+                ControlAction::ViewBlue => Self::view(&mut fog_config, Team::Blue, false),
+                ControlAction::ViewRed => Self::view(&mut fog_config, Team::Red, false),
+                ControlAction::ViewAll => {
+                    let team = fog_config.player_team;
+                    Self::view(&mut fog_config, team, true);
+                }
+                ControlAction::FollowBattle => {
+                    spectator.follow_battle = !spectator.follow_battle;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Show the map as the team sees it, or all of it.
+    fn view(fog_config: &mut ResMut<FogConfig>, team: Team, reveal_all: bool) {
+        if fog_config.player_team != team || fog_config.reveal_all != reveal_all {
+            fog_config.player_team = team;
+            fog_config.reveal_all = reveal_all;
+        }
+    }
+
+    /// Periodically find the largest battle and move the camera towards it.
+    pub fn follow_battle(
+        mut spectator: ResMut<Self>,
+        mut camera: Query<(&CameraController, &mut Transform), With<MainCamera>>,
+        mut camera_moves: EventWriter<CameraMoveEvent>,
+        grid: Res<Grid2<TeamEntitySets>>,
+        time: Res<Time>,
+    ) {
+        if !spectator.follow_battle {
+            return;
+        }
+        if spectator.search_timer.tick(time.delta()).just_finished() {
+            spectator.battle = grid.largest_battle(BATTLE_BLOCK).or(spectator.battle);
+        }
+        let (Some(battle), Ok((controller, mut transform))) =
+            (spectator.battle, camera.get_single_mut())
+        else {
+            return;
+        };
+        // The camera is tilted, so it sits below what it looks at.
+        let target = battle - Vec2::Y * MainCamera::y_offset(transform.translation.z);
+        let position = transform
+            .translation
+            .xy()
+            .lerp(target, (FOLLOW_SPEED * time.delta_seconds()).min(1.));
+        controller.set_position(&mut transform, position);
+        camera_moves.send(CameraMoveEvent {
+            position: transform.translation,
+        });
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+
+use bevy::utils::HashMap;
+
+use crate::prelude::*;
+
+/// Plugin for tracking per-team statistics over a match.
+pub struct StatsPlugin;
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MatchStats>().add_systems(
+            FixedUpdate,
+            MatchStats::update
+                .in_set(FixedUpdateStage::Cleanup)
+                .in_set(GameStateSet::Running),
+        );
+    }
+}
+
+/// Seconds of history used for income rates.
+pub const INCOME_WINDOW: f32 = 60.;
+
+/// Statistics for a single team.
+#[derive(Default, Debug, Clone)]
+pub struct TeamStats {
+    /// Zooids spawned by the team, not counting heads.
+    pub units_produced: usize,
+    /// Food and gems consumed by the team's heads.
+    pub resources_gathered: usize,
+    /// Heads currently alive.
+    pub heads: usize,
+    /// Zooids currently alive, not counting heads.
+    pub units: usize,
+    /// Food stored by the team's heads.
+    pub food: usize,
+    /// Gems stored by the team's heads.
+    pub gems: usize,
+    /// Objects currently alive per type.
+    pub counts: HashMap<Object, usize>,
+    /// Game time at which each resource in the income window was gathered.
+    pub gathered_at: VecDeque<f32>,
+    /// True once the team has had a head.
+    pub playing: bool,
+}
+impl TeamStats {
+    /// Number of objects of the type currently alive.
+    pub fn count(&self, object: Object) -> usize {
+        self.counts.get(&object).copied().unwrap_or_default()
+    }
+
+    /// Resources gathered over the last `INCOME_WINDOW` seconds.
+    pub fn income(&self) -> usize {
+        self.gathered_at.len()
+    }
+}
+
+/// Statistics for the current match.
+/// Counts are kept up to date from spawns and despawns, so reading them is cheap.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct MatchStats {
+    /// Seconds of game time until the match was decided.
+    pub elapsed: f32,
+    /// Set once only one team has heads left.
+    pub winner: Option<Team>,
+    pub teams: [TeamStats; Team::COUNT],
+    /// Type and team of every counted object.
+    objects: HashMap<Entity, (Object, Team)>,
+}
+impl MatchStats {
+    pub fn team(&self, team: Team) -> &TeamStats {
+        &self.teams[team]
+    }
+
+    pub fn team_mut(&mut self, team: Team) -> &mut TeamStats {
+        &mut self.teams[team as usize]
+    }
+
+    /// Record a resource gathered by the team.
+    pub fn gather(&mut self, team: Team) {
+        let elapsed = self.elapsed;
+        let team_stats = self.team_mut(team);
+        team_stats.resources_gathered += 1;
+        team_stats.gathered_at.push_back(elapsed);
+    }
+
+    /// True once the match has been decided.
+    pub fn finished(&self) -> bool {
+        self.winner.is_some()
+    }
+
+    pub fn update(
+        mut stats: ResMut<Self>,
+        spawned: Query<(Entity, &Object, &Team), Added<Object>>,
+        mut despawns: EventReader<DespawnEvent>,
+        heads: Query<(&Team, &Consumer), With<ZooidHead>>,
+        time: Res<Time>,
+    ) {
+        if stats.finished() {
+            return;
+        }
+        stats.elapsed += time.delta_seconds();
+
+        for (entity, &object, &team) in spawned.iter() {
+            stats.objects.insert(entity, (object, team));
+            *stats.team_mut(team).counts.entry(object).or_default() += 1;
+            if team == Team::None || matches!(object, Object::HEAD | Object::BUILDER_PREVIEW) {
+                continue;
+            }
+            stats.team_mut(team).units_produced += 1;
+        }
+        for &DespawnEvent(entity) in despawns.read() {
+            if let Some((object, team)) = stats.objects.remove(&entity) {
+                if let Some(count) = stats.team_mut(team).counts.get_mut(&object) {
+                    *count = count.saturating_sub(1);
+                }
+            }
+        }
+
+        let window_start = stats.elapsed - INCOME_WINDOW;
+        for team_stats in stats.teams.iter_mut() {
+            team_stats.heads = 0;
+            team_stats.food = 0;
+            team_stats.gems = 0;
+            team_stats.units = team_stats
+                .counts
+                .iter()
+                .filter(|(object, _)| !matches!(object, Object::HEAD | Object::BUILDER_PREVIEW))
+                .map(|(_, &count)| count)
+                .sum();
+            while team_stats
+                .gathered_at
+                .front()
+                .is_some_and(|&gathered_at| gathered_at < window_start)
+            {
+                team_stats.gathered_at.pop_front();
+            }
+        }
+        for (&team, consumer) in heads.iter().filter(|(&team, _)| team != Team::None) {
+            let team_stats = stats.team_mut(team);
+            team_stats.heads += 1;
+            team_stats.food += consumer.food_consumed();
+            team_stats.gems += consumer.gems_consumed();
+            team_stats.playing = true;
+        }
+
+        // A match is decided once more than one team has played and only one has heads left.
+        let playing = stats.teams.iter().filter(|team| team.playing).count();
+        let mut alive = enum_iterator::all::<Team>()
+            .filter(|&team| team != Team::None && stats.team(team).heads > 0);
+        if let (Some(winner), None) = (alive.next(), alive.next()) {
+            if playing > 1 {
+                stats.winner = Some(winner);
+            }
+        }
+    }
+}
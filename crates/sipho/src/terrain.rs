@@ -8,13 +8,22 @@ impl Plugin for TerrainPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(HeightMapPlugin)
             .add_systems(Startup, Terrain::setup)
-            .add_systems(OnExit(GameState::Loading), Terrain::setup_obstacles);
+            .add_systems(OnExit(GameState::Loading), Terrain::setup_obstacles)
+            .add_systems(
+                FixedUpdate,
+                Terrain::vent_damage
+                    .in_set(FixedUpdateStage::AI)
+                    .in_set(GameStateSet::Running),
+            );
     }
 }
 
 pub const SCALE: f32 = 1024. * 16.;
 pub const HEIGHT: f32 = 256.;
 pub const SEALEVEL: f32 = 0.7;
+/// Seconds between damage ticks from vents.
+pub const VENT_PERIOD: f32 = 1.0;
+pub const VENT_DAMAGE: i32 = 1;
 
 #[derive(Component)]
 pub struct Terrain;
@@ -49,6 +58,9 @@ impl Terrain {
         ));
     }
 
+    /// Derive obstacles from the heightmap.
+    /// The height is sampled at each cell corner, and cells are classified
+    /// as full or diagonal obstacles depending on which corners are above sea level.
     pub fn setup_obstacles(
         terrain: Query<&Handle<Mesh>, With<Terrain>>,
         meshes: Res<Assets<Mesh>>,
@@ -61,13 +73,112 @@ impl Terrain {
             .unwrap()
             .as_float3()
             .unwrap();
-        for position in vertex_positions {
-            let position = Vec3::new(position[0] * SCALE, position[1] * SCALE, position[2]);
-            if position.z > SEALEVEL {
-                if let Some(rowcol) = obstacles.to_rowcol(position.xy()) {
-                    obstacles[rowcol] = Obstacle::Full;
+        let heights = HeightField::from_positions(vertex_positions);
+
+        // Corners are laid out in a (rows + 1) x (cols + 1) grid.
+        let corner_cols = obstacles.cols as usize + 1;
+        let mut corners = vec![false; (obstacles.rows as usize + 1) * corner_cols];
+        for row in 0..=obstacles.rows as usize {
+            for col in 0..corner_cols {
+                let corner =
+                    Vec2::new(col as f32, row as f32) * obstacles.width - obstacles.offset();
+                corners[row * corner_cols + col] = heights.sample(corner / SCALE) > SEALEVEL;
+            }
+        }
+
+        for row in 0..obstacles.rows {
+            for col in 0..obstacles.cols {
+                let i = row as usize * corner_cols + col as usize;
+                let obstacle = Obstacle::from_corners([
+                    corners[i],
+                    corners[i + 1],
+                    corners[i + corner_cols],
+                    corners[i + corner_cols + 1],
+                ]);
+                if obstacle != Obstacle::Empty {
+                    obstacles[(row, col)] = obstacle;
                 }
             }
         }
     }
+
+    /// Damage units resting on vents.
+    /// Health is reduced directly rather than through `DamageEvent`s, which are for attacks between objects.
+    pub fn vent_damage(
+        mut objects: Query<(&Team, &Position, &mut Health)>,
+        seabed: Res<Grid2<Seabed>>,
+        time: Res<Time>,
+        mut elapsed: Local<f32>,
+    ) {
+        *elapsed += time.delta_seconds();
+        if *elapsed < VENT_PERIOD {
+            return;
+        }
+        *elapsed -= VENT_PERIOD;
+        for (&team, position, mut health) in &mut objects {
+            // Neutral objects like food and plankton are left alone.
+            if team == Team::None || !health.damageable {
+                continue;
+            }
+            let Some(rowcol) = seabed.to_rowcol(position.0) else {
+                continue;
+            };
+            if seabed[rowcol] == Seabed::Vent {
+                health.damage(VENT_DAMAGE);
+            }
+        }
+    }
+}
+
+/// Heights of the terrain mesh vertices, indexed by their position on the mesh's regular grid.
+struct HeightField {
+    min: Vec2,
+    step: Vec2,
+    cols: usize,
+    rows: usize,
+    heights: Vec<f32>,
+}
+impl HeightField {
+    fn from_positions(positions: &[[f32; 3]]) -> Self {
+        let distinct = |axis: usize| {
+            let mut values: Vec<f32> = positions.iter().map(|p| p[axis]).collect();
+            values.sort_by(f32::total_cmp);
+            values.dedup_by(|a, b| (*a - *b).abs() < 1e-5);
+            values
+        };
+        let xs = distinct(0);
+        let ys = distinct(1);
+        let min = Vec2::new(xs[0], ys[0]);
+        let max = Vec2::new(xs[xs.len() - 1], ys[ys.len() - 1]);
+        let (cols, rows) = (xs.len(), ys.len());
+        let step = (max - min) / Vec2::new((cols - 1).max(1) as f32, (rows - 1).max(1) as f32);
+
+        let mut heights = vec![0.; cols * rows];
+        for position in positions {
+            let index = ((Vec2::new(position[0], position[1]) - min) / step).round();
+            heights[index.y as usize * cols + index.x as usize] = position[2];
+        }
+        Self {
+            min,
+            step,
+            cols,
+            rows,
+            heights,
+        }
+    }
+
+    /// Bilinearly interpolate the height at a point in mesh space.
+    fn sample(&self, position: Vec2) -> f32 {
+        let index = (position - self.min) / self.step;
+        let col = index.x.clamp(0., (self.cols - 1) as f32);
+        let row = index.y.clamp(0., (self.rows - 1) as f32);
+        let (col0, row0) = (col.floor() as usize, row.floor() as usize);
+        let (col1, row1) = ((col0 + 1).min(self.cols - 1), (row0 + 1).min(self.rows - 1));
+        let (tx, ty) = (col.fract(), row.fract());
+        let height = |row: usize, col: usize| self.heights[row * self.cols + col];
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        let bottom = lerp(height(row0, col0), height(row0, col1), tx);
+        let top = lerp(height(row1, col0), height(row1, col1), tx);
+        lerp(bottom, top, ty)
+    }
 }
@@ -0,0 +1,473 @@
+use bevy::{
+    math::FloatOrd,
+    pbr::NotShadowCaster,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+        render_resource::{AsBindGroup, ShaderRef, ShaderType},
+        view::NoFrustumCulling,
+    },
+    utils::{HashMap, HashSet},
+};
+
+use crate::prelude::*;
+
+/// Plugin for health bars, colony integrity and floating damage numbers.
+pub struct HealthBarsPlugin;
+impl Plugin for HealthBarsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<HealthBarsMaterial>::default())
+            .register_type::<HealthBarConfig>()
+            .init_resource::<HealthBarConfig>()
+            .add_systems(Startup, (HealthBars::setup, DamageNumber::setup))
+            .add_systems(
+                Update,
+                (
+                    HealthBars::update,
+                    (DamageNumber::spawn, DamageNumber::update).chain(),
+                )
+                    .in_set(GameStateSet::Running),
+            );
+    }
+}
+
+/// Settings for health bars and damage numbers.
+#[derive(Resource, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct HealthBarConfig {
+    /// Show health bars over damaged or selected objects, and colony integrity over heads.
+    pub enabled: bool,
+    /// Show floating numbers when visible objects take damage.
+    pub damage_numbers: bool,
+}
+impl Default for HealthBarConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            damage_numbers: true,
+        }
+    }
+}
+
+/// Bars of one object, as read by `shaders/health_bars.wgsl`.
+#[derive(ShaderType, Clone, Copy, PartialEq, Debug)]
+pub struct HealthBar {
+    /// Bottom left corner of the health bar.
+    pub position: Vec2,
+    pub width: f32,
+    /// Filled fraction of the health bar, or negative to hide it.
+    pub fill: f32,
+    /// Filled fraction of the integrity bar shown under heads, or negative to hide it.
+    pub integrity: f32,
+}
+impl HealthBar {
+    pub const HIDDEN: Self = Self {
+        position: Vec2::ZERO,
+        width: 0.,
+        fill: -1.,
+        integrity: -1.,
+    };
+}
+
+/// Draws a health bar and an integrity bar for each slot in `bars`.
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub struct HealthBarsMaterial {
+    #[storage(0, read_only)]
+    pub bars: Vec<HealthBar>,
+    #[uniform(1)]
+    background: LinearRgba,
+    #[uniform(2)]
+    integrity: LinearRgba,
+    #[uniform(3)]
+    height: f32,
+}
+impl HealthBarsMaterial {
+    fn new(capacity: usize) -> Self {
+        Self {
+            bars: vec![HealthBar::HIDDEN; capacity],
+            background: HealthBars::BACKGROUND,
+            integrity: HealthBars::INTEGRITY,
+            height: HealthBars::HEIGHT,
+        }
+    }
+}
+impl Material for HealthBarsMaterial {
+    fn vertex_shader() -> ShaderRef {
+        "shaders/health_bars.wgsl".into()
+    }
+    fn fragment_shader() -> ShaderRef {
+        "shaders/health_bars.wgsl".into()
+    }
+}
+
+/// Every health bar, drawn in one call by placing the quads of a single mesh in the shader.
+/// Each object with a bar owns a slot of the material, which is only written when the bar changes.
+#[derive(Component, Default)]
+pub struct HealthBars {
+    slots: HashMap<Entity, usize>,
+    free: Vec<usize>,
+    /// Health of each colony as a fraction of its max health, keyed by head.
+    integrity: HashMap<Entity, f32>,
+}
+impl HealthBars {
+    pub const HEIGHT: f32 = 6.;
+    pub const MIN_WIDTH: f32 = 24.;
+    pub const BACKGROUND: LinearRgba = LinearRgba::rgb(0.05, 0.05, 0.05);
+    pub const INTEGRITY: LinearRgba = LinearRgba::rgb(0.2, 0.5, 1.0);
+    /// Slots allocated up front. Doubled whenever they run out.
+    pub const INITIAL_CAPACITY: usize = 256;
+
+    fn setup(
+        mut commands: Commands,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut materials: ResMut<Assets<HealthBarsMaterial>>,
+    ) {
+        commands.spawn((
+            Name::new("HealthBars"),
+            Self::default(),
+            MaterialMeshBundle {
+                mesh: meshes.add(Self::mesh(Self::INITIAL_CAPACITY)),
+                material: materials.add(HealthBarsMaterial::new(Self::INITIAL_CAPACITY)),
+                transform: Transform::from_xyz(0., 0., zindex::HEALTH_BARS),
+                ..default()
+            },
+            NotShadowCaster,
+            // Quads are placed by the shader, so the mesh bounds can't be used for culling.
+            NoFrustumCulling,
+        ));
+    }
+
+    /// Unit quads for the health bar and the integrity bar of each slot.
+    /// The z coordinate is 1 for integrity bars.
+    fn mesh(capacity: usize) -> Mesh {
+        let mut positions = Vec::with_capacity(capacity * 8);
+        let mut indices = Vec::with_capacity(capacity * 12);
+        for _ in 0..capacity {
+            for z in [0., 1.] {
+                let start = positions.len() as u32;
+                positions.extend([[0., 0., z], [1., 0., z], [1., 1., z], [0., 1., z]]);
+                indices.extend([start, start + 1, start + 2, start, start + 2, start + 3]);
+            }
+        }
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::RENDER_WORLD,
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_indices(Indices::U32(indices))
+    }
+
+    /// Give an object a slot, reusing freed ones first.
+    fn allocate(&mut self, entity: Entity) -> usize {
+        let slot = self.free.pop().unwrap_or(self.slots.len());
+        self.slots.insert(entity, slot);
+        slot
+    }
+
+    /// Free an object's slot, returning it if it had one.
+    fn release(&mut self, entity: Entity) -> Option<usize> {
+        let slot = self.slots.remove(&entity)?;
+        self.free.push(slot);
+        Some(slot)
+    }
+
+    /// Health of each colony as a fraction of its max health.
+    fn colony_integrity<'a>(
+        objects: impl IntoIterator<Item = (&'a Health, &'a PathToHead)>,
+    ) -> HashMap<Entity, f32> {
+        let mut colonies: HashMap<Entity, (i32, i32)> = HashMap::new();
+        for (health, path) in objects {
+            if let Some(head) = path.head {
+                let colony = colonies.entry(head).or_default();
+                colony.0 += health.health.max(0);
+                colony.1 += health.max.max(health.health);
+            }
+        }
+        colonies
+            .into_iter()
+            .map(|(head, (health, max))| (head, health as f32 / max.max(1) as f32))
+            .collect()
+    }
+
+    /// Bars to show for damaged or selected objects and heads, respecting fog of war and stealth.
+    #[allow(clippy::too_many_arguments)]
+    fn bar(
+        &self,
+        object: &Object,
+        position: &Position,
+        health: &Health,
+        path: &PathToHead,
+        visibility: &Visibility,
+        selected: bool,
+        is_head: bool,
+        configs: &ObjectConfigs,
+    ) -> Option<HealthBar> {
+        if *visibility == Visibility::Hidden || object == &Object::FOOD {
+            return None;
+        }
+        if !(selected || is_head || health.is_damaged()) {
+            return None;
+        }
+        let radius = configs.get(object)?.radius;
+        let width = (2. * radius).max(Self::MIN_WIDTH);
+        let integrity = if is_head {
+            path.head
+                .and_then(|head| self.integrity.get(&head).copied())
+                .unwrap_or_default()
+        } else {
+            -1.
+        };
+        Some(HealthBar {
+            position: position.0 + Vec2::new(-width / 2., radius + Self::HEIGHT),
+            width,
+            fill: health.fraction(),
+            integrity,
+        })
+    }
+
+    /// Write the bars of objects that changed, were deselected or despawned.
+    #[allow(clippy::type_complexity, clippy::too_many_arguments)]
+    pub fn update(
+        mut bars: Query<(
+            &mut Self,
+            &Handle<Mesh>,
+            &Handle<HealthBarsMaterial>,
+            &mut Visibility,
+        )>,
+        objects: Query<
+            (
+                Entity,
+                &Object,
+                &Position,
+                &Health,
+                &PathToHead,
+                &Visibility,
+                Has<Selected>,
+                Has<ZooidHead>,
+            ),
+            Without<Self>,
+        >,
+        changed: Query<
+            Entity,
+            (
+                Or<(
+                    Changed<Health>,
+                    Changed<Position>,
+                    Changed<Visibility>,
+                    Changed<PathToHead>,
+                    Added<Selected>,
+                )>,
+                Without<Self>,
+            ),
+        >,
+        colonies_changed: Query<(), Or<(Changed<Health>, Changed<PathToHead>)>>,
+        mut deselected: RemovedComponents<Selected>,
+        mut despawned: RemovedComponents<Health>,
+        configs: Res<ObjectConfigs>,
+        config: Res<HealthBarConfig>,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut materials: ResMut<Assets<HealthBarsMaterial>>,
+    ) {
+        let Ok((mut health_bars, mesh, material, mut bars_visibility)) = bars.get_single_mut()
+        else {
+            return;
+        };
+        let shown = if config.enabled {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        if *bars_visibility != shown {
+            *bars_visibility = shown;
+        }
+
+        // Free the slots of despawned objects even while disabled, so they can be reused.
+        let mut updates: Vec<(usize, HealthBar)> = despawned
+            .read()
+            .filter_map(|entity| health_bars.release(entity))
+            .map(|slot| (slot, HealthBar::HIDDEN))
+            .collect();
+        if !config.enabled {
+            return;
+        }
+
+        // Bars missed while disabled are all refreshed, and slots freed meanwhile are hidden.
+        let refresh = config.is_changed();
+        let mut dirty: HashSet<Entity> = changed.iter().chain(deselected.read()).collect();
+        if refresh {
+            dirty.extend(objects.iter().map(|(entity, ..)| entity));
+            updates.extend(
+                health_bars
+                    .free
+                    .iter()
+                    .map(|&slot| (slot, HealthBar::HIDDEN)),
+            );
+        }
+        if refresh || !colonies_changed.is_empty() {
+            let integrity = Self::colony_integrity(
+                objects
+                    .iter()
+                    .map(|(_, _, _, health, path, ..)| (health, path)),
+            );
+            // Heads whose colony integrity changed.
+            dirty.extend(
+                integrity
+                    .iter()
+                    .filter(|&(head, value)| health_bars.integrity.get(head) != Some(value))
+                    .map(|(&head, _)| head),
+            );
+            health_bars.integrity = integrity;
+        }
+
+        for entity in dirty {
+            let bar = objects.get(entity).ok().and_then(
+                |(_, object, position, health, path, visibility, selected, is_head)| {
+                    health_bars.bar(
+                        object, position, health, path, visibility, selected, is_head, &configs,
+                    )
+                },
+            );
+            let slot = match bar {
+                Some(_) => match health_bars.slots.get(&entity) {
+                    Some(&slot) => slot,
+                    None => health_bars.allocate(entity),
+                },
+                None => match health_bars.release(entity) {
+                    Some(slot) => slot,
+                    None => continue,
+                },
+            };
+            updates.push((slot, bar.unwrap_or(HealthBar::HIDDEN)));
+        }
+
+        // Only touch the material if a bar actually changed, since that uploads it again.
+        let Some(current) = materials.get(material) else {
+            return;
+        };
+        updates.retain(|&(slot, bar)| current.bars.get(slot) != Some(&bar));
+        if updates.is_empty() {
+            return;
+        }
+        let Some(current) = materials.get_mut(material) else {
+            return;
+        };
+        let needed = health_bars.slots.len() + health_bars.free.len();
+        if needed > current.bars.len() {
+            let capacity = needed.next_power_of_two();
+            current.bars.resize(capacity, HealthBar::HIDDEN);
+            meshes.insert(mesh, Self::mesh(capacity));
+        }
+        for (slot, bar) in updates {
+            current.bars[slot] = bar;
+        }
+    }
+}
+
+/// Number of damage numbers that can be shown at once.
+pub const DAMAGE_NUMBERS: usize = 64;
+/// Seconds a damage number is shown.
+pub const DAMAGE_NUMBER_SECONDS: f32 = 0.8;
+/// Distance a damage number rises before disappearing.
+pub const DAMAGE_NUMBER_RISE: f32 = 40.;
+
+/// Floating number showing damage taken, from a fixed pool of text nodes.
+#[derive(Component)]
+pub struct DamageNumber {
+    pub position: Vec2,
+    /// Seconds since the number appeared.
+    pub age: f32,
+}
+impl Default for DamageNumber {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            age: DAMAGE_NUMBER_SECONDS,
+        }
+    }
+}
+impl DamageNumber {
+    fn setup(mut commands: Commands) {
+        for _ in 0..DAMAGE_NUMBERS {
+            commands.spawn((
+                Name::new("DamageNumber"),
+                Self::default(),
+                TextBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 18.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ),
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+            ));
+        }
+    }
+
+    /// Show a number for each damage event on a visible object, reusing the oldest numbers.
+    pub fn spawn(
+        mut events: EventReader<DamageEvent>,
+        mut numbers: Query<(&mut Self, &mut Text)>,
+        damaged: Query<(&Object, &Position, &Visibility)>,
+        config: Res<HealthBarConfig>,
+    ) {
+        for event in events.read() {
+            if !config.damage_numbers || event.amount <= 0 {
+                continue;
+            }
+            let Ok((object, position, visibility)) = damaged.get(event.damaged) else {
+                continue;
+            };
+            if *visibility == Visibility::Hidden || object == &Object::FOOD {
+                continue;
+            }
+            let Some((mut number, mut text)) = numbers
+                .iter_mut()
+                .max_by_key(|(number, _)| FloatOrd(number.age))
+            else {
+                return;
+            };
+            number.position = position.0;
+            number.age = 0.;
+            text.sections[0].value = event.amount.to_string();
+        }
+    }
+
+    /// Move numbers up and fade them out over their lifetime.
+    pub fn update(
+        mut numbers: Query<(&mut Self, &mut Style, &mut Visibility, &mut Text)>,
+        camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+        time: Res<Time>,
+    ) {
+        let Ok((camera, camera_transform)) = camera.get_single() else {
+            return;
+        };
+        for (mut number, mut style, mut visibility, mut text) in &mut numbers {
+            if number.age >= DAMAGE_NUMBER_SECONDS {
+                *visibility = Visibility::Hidden;
+                continue;
+            }
+            number.age += time.delta_seconds();
+            let t = (number.age / DAMAGE_NUMBER_SECONDS).min(1.);
+            let world_position =
+                (number.position + Vec2::Y * DAMAGE_NUMBER_RISE * t).extend(zindex::HEALTH_BARS);
+            let Some(viewport_position) =
+                camera.world_to_viewport(camera_transform, world_position)
+            else {
+                *visibility = Visibility::Hidden;
+                continue;
+            };
+            style.left = Val::Px(viewport_position.x);
+            style.top = Val::Px(viewport_position.y);
+            text.sections[0].style.color = Color::WHITE.with_alpha(1. - t);
+            *visibility = Visibility::Visible;
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use super::*;
+use crate::{
+    alerts::{Alerts, ALERT_SECONDS},
+    ui::localization::Localization,
+};
+use bevy::color::palettes::css::GOLD;
+
+/// Shows the latest alerts below the top bar.
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+pub struct HudAlertPane;
+impl MakeBundleTree<HudUiNode, &HudAssets> for HudAlertPane {
+    fn tree(self, _assets: &HudAssets) -> BundleTree<HudUiNode> {
+        HudAlertPaneBundle::default().with_children([TextBundle {
+            style: Style {
+                padding: UiRect::axes(Val::Px(15.0), Val::Px(5.0)),
+                ..default()
+            },
+            text: Text::default().with_justify(JustifyText::Center),
+            ..default()
+        }
+        .into_tree()])
+    }
+}
+
+#[derive(Bundle)]
+pub struct HudAlertPaneBundle {
+    pub data: HudAlertPane,
+    pub node: NodeBundle,
+}
+impl Default for HudAlertPaneBundle {
+    fn default() -> Self {
+        Self {
+            data: HudAlertPane,
+            node: NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(50.0),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        }
+    }
+}
+
+impl HudAlertPane {
+    /// Show the latest alerts, newest first, fading out as they age.
+    pub fn update(
+        alerts: Res<Alerts>,
+        localization: Res<Localization>,
+        ui: Query<&Children, With<Self>>,
+        mut text: Query<&mut Text>,
+    ) {
+        let mut text = text.get_mut(ui.single()[0]).unwrap();
+        text.sections = alerts
+            .notifications
+            .iter()
+            .rev()
+            .map(|alert| {
+                TextSection::new(
+                    format!("{}\n", localization.label(alert.kind.message())),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: GOLD.with_alpha(1. - alert.age / ALERT_SECONDS).into(),
+                        ..default()
+                    },
+                )
+            })
+            .collect();
+    }
+}
@@ -5,7 +5,8 @@ use bevy::{
 };
 use std::time::Duration;
 
-use super::*;
+use super::{tooltip::ActionCost, *};
+use crate::ui::localization::Localization;
 
 /// Tag component used to mark which setting is currently selected
 #[derive(Component)]
@@ -72,6 +73,7 @@ impl MakeBundleTree<HudUiNode, &HudAssets> for HudControlsPane {
                     text,
                     action,
                     control: None,
+                    disabled: false,
                 }
                 .tree(assets)
             }),
@@ -85,6 +87,8 @@ pub struct HudControlsButton {
     pub text: String,
     pub action: InputAction,
     pub control: Option<ControlAction>,
+    /// True if the selection can't pay for the control.
+    pub disabled: bool,
 }
 #[derive(Bundle)]
 pub struct HudControlsButtonBundle {
@@ -173,10 +177,14 @@ impl HudControlsButton {
         mut controls: EventWriter<ControlEvent>,
         mut raycasts: EventReader<RaycastEvent>,
         mut state: ResMut<ControlState>,
+        formation: Res<Formation>,
+        team_config: Res<TeamConfig>,
+        localization: Res<Localization>,
     ) {
         let objects: HashSet<Object> = selected.iter().copied().collect();
 
         let mut map: HashMap<InputAction, ControlAction> = HashMap::with_capacity(4);
+        map.insert(InputAction::Grid22, ControlAction::Formation);
         map.insert(InputAction::Grid24, ControlAction::Plankton);
         map.insert(InputAction::Grid32, ControlAction::Head);
         map.insert(InputAction::Grid33, ControlAction::TieAll);
@@ -192,18 +200,25 @@ impl HudControlsButton {
         let mut action_to_button = HashMap::new();
         for (entity, mut button, _interaction, children) in buttons.iter_mut() {
             let control = map.get(&button.action).copied();
-            if button.control != control {
+            let formation_changed =
+                formation.is_changed() && control == Some(ControlAction::Formation);
+            if button.control != control || formation_changed {
                 button.control = control;
                 let text = &mut button_text.get_mut(children[1]).unwrap().sections[0].value;
-                if let Some(control) = map.get(&button.action) {
-                    *text = format!("{:?}", control);
-                } else {
-                    text.clear();
+                match control {
+                    // Show the formation the next move order will use.
+                    Some(ControlAction::Formation) => *text = format!("{:?}", *formation),
+                    Some(control) => *text = localization.control(control).name,
+                    None => text.clear(),
                 }
             }
             action_to_button.insert(button.action, entity);
         }
 
+        // Observers can't control anything.
+        if team_config.observer {
+            return;
+        }
         if let Some(raycast) = raycasts.read().next() {
             for input in inputs.read() {
                 if let Some(&entity) = action_to_button.get(&input.action) {
@@ -250,7 +265,45 @@ impl HudControlsButton {
         }
     }
 
+    /// Grey out buttons whose controls the selection can't pay for.
+    pub fn update_disabled(
+        mut buttons: Query<(&mut Self, &Children)>,
+        mut text: Query<&mut Text>,
+        selected: Query<(&Object, Option<&Consumer>), With<Selected>>,
+        configs: Res<ObjectConfigs>,
+    ) {
+        let food = selected
+            .iter()
+            .filter_map(|(_, consumer)| consumer.map(Consumer::food_consumed))
+            .max()
+            .unwrap_or_default();
+        let workers = selected
+            .iter()
+            .filter(|(&object, _)| object == Object::WORKER)
+            .count();
+        for (mut button, children) in &mut buttons {
+            let disabled = button.control.is_some_and(|control| {
+                !ActionCost::new(control, &configs).is_affordable(food, workers)
+            });
+            if button.disabled == disabled {
+                continue;
+            }
+            button.disabled = disabled;
+            let color = if disabled {
+                DISABLED_TEXT
+            } else {
+                Color::WHITE
+            };
+            for child in children.iter() {
+                if let Ok(mut text) = text.get_mut(*child) {
+                    text.sections[0].style.color = color;
+                }
+            }
+        }
+    }
+
     // Handle changing all buttons color based on mouse interaction
+    #[allow(clippy::type_complexity)]
     pub fn button_system(
         mut interaction_query: Query<
             (
@@ -259,14 +312,15 @@ impl HudControlsButton {
                 &mut BackgroundColor,
                 Option<&SelectedOption>,
             ),
-            Changed<Interaction>,
+            Or<(Changed<Interaction>, Changed<HudControlsButton>)>,
         >,
     ) {
-        for (_button, interaction, mut color, selected) in &mut interaction_query {
+        for (button, interaction, mut color, selected) in &mut interaction_query {
             *color = match (*interaction, selected) {
                 (Interaction::Pressed, _) | (Interaction::None, Some(_)) => PRESSED_BUTTON.into(),
                 (Interaction::Hovered, Some(_)) => HOVERED_PRESSED_BUTTON.into(),
                 (Interaction::Hovered, None) => HOVERED_BUTTON.into(),
+                (Interaction::None, None) if button.disabled => DISABLED_BUTTON.into(),
                 (Interaction::None, None) => NORMAL_BUTTON.into(),
             }
         }
@@ -0,0 +1,192 @@
+use super::*;
+use crate::ui::localization::Localization;
+use bevy::{color::palettes::css::DARK_GRAY, utils::HashSet};
+use std::collections::VecDeque;
+
+/// Shows the details of a single selected object in place of the selected pane.
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+pub struct HudDetailPane;
+impl MakeBundleTree<HudUiNode, &HudAssets> for HudDetailPane {
+    fn tree(self, _assets: &HudAssets) -> BundleTree<HudUiNode> {
+        HudDetailPaneBundle::default().with_children([TextBundle::default().into_tree()])
+    }
+}
+
+#[derive(Bundle)]
+pub struct HudDetailPaneBundle {
+    pub data: HudDetailPane,
+    pub node: NodeBundle,
+}
+impl Default for HudDetailPaneBundle {
+    fn default() -> Self {
+        Self {
+            data: HudDetailPane,
+            node: NodeBundle {
+                style: Style {
+                    width: Val::Px(600.0),
+                    height: Val::Px(150.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    display: Display::None,
+                    ..default()
+                },
+                background_color: DARK_GRAY.with_alpha(0.2).into(),
+                ..default()
+            },
+        }
+    }
+}
+
+impl HudDetailPane {
+    /// Swap the selected pane for details when exactly one object is selected.
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    pub fn update(
+        selection: Query<
+            (
+                Entity,
+                &Object,
+                &Health,
+                &Objectives,
+                &PathToHead,
+                Option<&Consumer>,
+                Option<&Stunned>,
+                Option<&Cooldown>,
+            ),
+            With<Selected>,
+        >,
+        objects: Query<&Object>,
+        attachments: Query<&AttachedTo>,
+        paths: Query<&PathToHead>,
+        mut ui: Query<(&Children, &mut Style), With<Self>>,
+        mut selected_pane: Query<&mut Style, (With<HudSelectedPane>, Without<Self>)>,
+        mut text: Query<&mut Text>,
+        localization: Res<Localization>,
+    ) {
+        let (children, mut style) = ui.single_mut();
+        let mut selected_pane = selected_pane.single_mut();
+        let mut selected = selection.iter();
+        let single = match (selected.next(), selected.next()) {
+            (Some(item), None) => Some(item),
+            _ => None,
+        };
+        // Only touch styles when switching panes to avoid relayouts every frame.
+        let display = if single.is_some() {
+            (Display::Flex, Display::None)
+        } else {
+            (Display::None, Display::Grid)
+        };
+        if style.display != display.0 {
+            style.display = display.0;
+            selected_pane.display = display.1;
+        }
+        let Some(item) = single else {
+            return;
+        };
+
+        let (entity, object, health, objectives, path, consumer, stunned, cooldown) = item;
+        let label = |label: &'static str| localization.label(label);
+        let target = |entity: &Entity| {
+            objects
+                .get(*entity)
+                .map(|object| format!("{object:?}"))
+                .unwrap_or_default()
+        };
+        let mut details = format!("{}: {}/{}", label("Health"), health.health, health.max);
+        if let Some(stunned) = stunned {
+            details += &format!("  {} {:.1}s", label("Stunned"), stunned.remaining_secs());
+        }
+        if let Some(cooldown) = cooldown {
+            details += &format!("  {} {:.1}s", label("Cooldown"), cooldown.remaining_secs());
+        }
+        if let Some(consumer) = consumer {
+            let (colony, limbs) = Self::colony(entity, &attachments);
+            details += &format!(
+                "\n{}: {}  {}: {}  {}: {colony}  {}: {limbs}",
+                label("Food"),
+                consumer.food_consumed(),
+                label("Gems"),
+                consumer.gems_consumed(),
+                label("Colony"),
+                label("Limbs"),
+            );
+        } else if let Some(depth) = Self::depth(entity, path, &paths) {
+            details += &format!("\n{}: {depth}", label("Depth"));
+        } else {
+            details += &format!("\n{}", label("Detached"));
+        }
+        details += &format!("\n{}:", label("Objectives"));
+        // Show the current objective first.
+        for objective in objectives.iter().rev() {
+            details += &match objective {
+                Objective::Idle => format!("\n  {}", label("Idle")),
+                Objective::FollowEntity(entity) => {
+                    format!("\n  {} {}", label("Follow"), target(entity))
+                }
+                Objective::AttackFollowEntity(entity) | Objective::AttackEntity(entity) => {
+                    format!("\n  {} {}", label("Attack"), target(entity))
+                }
+            };
+        }
+
+        let title = format!("{object:?}\n");
+        let mut text = text.get_mut(children[0]).unwrap();
+        // Only write the text when it changes, since writing it lays it out again.
+        if let [current_title, current_details] = &text.sections[..] {
+            if current_title.value == title && current_details.value == details {
+                return;
+            }
+        }
+        text.sections = vec![
+            TextSection::new(
+                title,
+                TextStyle {
+                    font_size: 18.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            TextSection::new(
+                details,
+                TextStyle {
+                    font_size: 14.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ),
+        ];
+    }
+
+    /// Number of zooids connected to the head, and number of limbs attached directly to it.
+    fn colony(head: Entity, attachments: &Query<&AttachedTo>) -> (usize, usize) {
+        let limbs = attachments.get(head).map_or(0, |attached| attached.len());
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([head]);
+        while let Some(entity) = queue.pop_front() {
+            if !visited.insert(entity) {
+                continue;
+            }
+            if let Ok(attached) = attachments.get(entity) {
+                queue.extend(attached.iter().filter(|entity| !visited.contains(*entity)));
+            }
+        }
+        (visited.len(), limbs)
+    }
+
+    /// Number of elastics between the object and its head.
+    fn depth(entity: Entity, path: &PathToHead, paths: &Query<&PathToHead>) -> Option<usize> {
+        let head = path.head?;
+        let mut visited = HashSet::new();
+        visited.insert(entity);
+        let mut next = path.next;
+        while let Some(entity) = next {
+            if !visited.insert(entity) {
+                return None;
+            }
+            if entity == head {
+                return Some(visited.len() - 1);
+            }
+            next = paths.get(entity).ok().and_then(|path| path.next);
+        }
+        None
+    }
+}
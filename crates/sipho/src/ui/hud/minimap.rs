@@ -2,8 +2,10 @@ use bevy::reflect::TypePath;
 use bevy::render::render_resource::*;
 use bevy::ui::RelativeCursorPosition;
 use bevy_bundletree::*;
+use sipho_core::grid::fog::{FogConfig, TeamVisibility};
 
 use super::*;
+use crate::alerts::Alerts;
 
 pub struct MinimapPlugin;
 impl Plugin for MinimapPlugin {
@@ -22,12 +24,15 @@ impl Plugin for MinimapPlugin {
 pub struct MinimapGridEntry {
     visibility: f32,
     team_presence: [f32; 3],
+    /// One more than the team of a ghost last seen in the cell, or zero.
+    ghost: f32,
 }
 impl Default for MinimapGridEntry {
     fn default() -> Self {
         Self {
             visibility: 0.,
             team_presence: [0.; 3],
+            ghost: 0.,
         }
     }
 }
@@ -50,6 +55,12 @@ struct MinimapUiMaterialInput {
     camera_position: Vec2,
     #[uniform(3)]
     viewport_size: Vec2,
+    /// Grid position of the latest alert.
+    #[uniform(4)]
+    ping_position: Vec2,
+    /// Strength of the alert ping, fading from one to zero.
+    #[uniform(5)]
+    ping_strength: f32,
 }
 impl Default for MinimapUiMaterialInput {
     fn default() -> Self {
@@ -63,6 +74,8 @@ impl Default for MinimapUiMaterialInput {
             size: GridSize::default(),
             camera_position: Vec2::ZERO,
             viewport_size: DEFAULT_VIEWPORT_SIZE,
+            ping_position: Vec2::ZERO,
+            ping_strength: 0.,
         }
     }
 }
@@ -111,6 +124,8 @@ pub struct MinimapUiMaterial {
     input: MinimapUiMaterialInput,
     #[storage(1, read_only)]
     grid: Vec<MinimapGridEntry>,
+    /// Indices of cells currently showing ghosts.
+    ghost_cells: Vec<usize>,
 }
 impl UiMaterial for MinimapUiMaterial {
     fn fragment_shader() -> ShaderRef {
@@ -128,12 +143,17 @@ impl MinimapUiMaterial {
         );
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update(
         spec: Res<GridSpec>,
         mut shader_assets: ResMut<Assets<Self>>,
         mut grid_events: EventReader<EntityGridEvent>,
         mut visibility_updates: EventReader<VisibilityUpdateEvent>,
         mut camera_moves: EventReader<CameraMoveEvent>,
+        ghosts: Res<Ghosts>,
+        visibility: Res<Grid2<TeamVisibility>>,
+        fog_config: Res<FogConfig>,
+        alerts: Res<Alerts>,
     ) {
         for (_, material) in shader_assets.iter_mut() {
             if spec.is_changed() {
@@ -154,13 +174,28 @@ impl MinimapUiMaterial {
                 }
             }
 
+            // Redraw everything when switching to another team's view.
+            if fog_config.is_changed() && visibility.cells.len() == material.grid.len() {
+                for (i, entry) in material.grid.iter_mut().enumerate() {
+                    let rowcol = (
+                        (i / spec.cols as usize) as u16,
+                        (i % spec.cols as usize) as u16,
+                    );
+                    entry.visibility = visibility.fog_amount(rowcol, &fog_config);
+                }
+            }
+            let is_shown = |update: &&VisibilityUpdate| fog_config.is_shown(update);
             for event in visibility_updates.read() {
-                for &VisibilityUpdate { rowcol, amount, .. } in &event.removals {
+                for &VisibilityUpdate { rowcol, amount, .. } in
+                    event.removals.iter().filter(is_shown)
+                {
                     if spec.in_bounds(rowcol) {
                         material.grid[spec.flat_index(rowcol)].visibility = amount;
                     }
                 }
-                for &VisibilityUpdate { rowcol, amount, .. } in &event.additions {
+                for &VisibilityUpdate { rowcol, amount, .. } in
+                    event.additions.iter().filter(is_shown)
+                {
                     if spec.in_bounds(rowcol) {
                         material.grid[spec.flat_index(rowcol)].visibility = material.grid
                             [spec.flat_index(rowcol)]
@@ -170,6 +205,25 @@ impl MinimapUiMaterial {
                 }
             }
 
+            // Show what the player remembers, not where enemies really are.
+            if ghosts.is_changed() || fog_config.is_changed() {
+                for i in material.ghost_cells.drain(..) {
+                    material.grid[i].ghost = 0.;
+                }
+                for (_, ghost) in ghosts.team(fog_config.player_team) {
+                    if !ghost.visible && !fog_config.reveal_all && spec.in_bounds(ghost.rowcol) {
+                        let i = spec.flat_index(ghost.rowcol);
+                        material.grid[i].ghost = ghost.team as usize as f32 + 1.;
+                        material.ghost_cells.push(i);
+                    }
+                }
+            }
+
+            if let Some(alert) = alerts.latest {
+                material.input.ping_position = spec.to_uv(alert.position);
+                material.input.ping_strength = alert.ping_strength();
+            }
+
             for event in camera_moves.read() {
                 let position = event.position.xy() + MainCamera::y_offset(event.position.z);
                 material.input.camera_position = spec.to_uv(position);
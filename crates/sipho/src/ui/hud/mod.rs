@@ -1,17 +1,29 @@
 use crate::prelude::*;
 
 use self::{
+    alert_pane::{HudAlertPane, HudAlertPaneBundle},
     assets::HudAssets,
     controls_pane::{HudControlsButton, HudControlsButtonBundle, HudControlsPane},
+    detail_pane::{HudDetailPane, HudDetailPaneBundle},
     minimap::{MinimapUi, MinimapUiBundle},
+    objectives_pane::{HudObjectivesPane, HudObjectivesPaneBundle},
+    observer_pane::{HudObserverPane, HudObserverPaneBundle},
     selected_pane::{HudSelectedPane, HudSelectedPaneBundle, HudUnitButton, HudUnitButtonBundle},
+    tooltip::{HudTooltip, HudTooltipBundle},
+    top_bar::{HudTopBar, HudTopBarBundle},
 };
 use bevy_bundletree::*;
 
+pub mod alert_pane;
 pub mod assets;
 pub mod controls_pane;
+pub mod detail_pane;
 pub mod minimap;
+pub mod objectives_pane;
+pub mod observer_pane;
 pub mod selected_pane;
+pub mod tooltip;
+pub mod top_bar;
 
 pub struct HudPlugin;
 impl Plugin for HudPlugin {
@@ -22,9 +34,19 @@ impl Plugin for HudPlugin {
             .add_systems(
                 Update,
                 (
-                    HudControlsButton::button_system,
+                    (
+                        HudControlsButton::update_disabled,
+                        HudControlsButton::button_system,
+                        HudTooltip::update,
+                    )
+                        .chain(),
                     HudSelectedPane::update,
+                    HudDetailPane::update,
                     HudUnitButton::update,
+                    HudObjectivesPane::update,
+                    HudObserverPane::update,
+                    HudTopBar::update,
+                    HudAlertPane::update,
                 ),
             )
             .add_systems(
@@ -66,7 +88,13 @@ pub enum HudUiNode {
     ControlsButton(HudControlsButtonBundle),
     UnitButton(HudUnitButtonBundle),
     SelectedPane(HudSelectedPaneBundle),
+    DetailPane(HudDetailPaneBundle),
     Minimap(MinimapUiBundle),
+    ObjectivesPane(HudObjectivesPaneBundle),
+    ObserverPane(HudObserverPaneBundle),
+    TopBar(HudTopBarBundle),
+    Tooltip(HudTooltipBundle),
+    AlertPane(HudAlertPaneBundle),
 }
 
 pub const TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
@@ -74,11 +102,17 @@ pub const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 pub const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
 pub const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.35, 0.35);
 pub const HOVERED_PRESSED_BUTTON: Color = Color::srgb(0.45, 0.45, 0.45);
+pub const DISABLED_BUTTON: Color = Color::srgb(0.08, 0.08, 0.08);
+pub const DISABLED_TEXT: Color = Color::srgb(0.45, 0.45, 0.45);
 
 fn setup(mut commands: Commands, assets: Res<HudAssets>) {
     commands.spawn_tree(
         // Root
         HudRootBundle::default().with_children([
+            HudObjectivesPane.tree(&assets),
+            HudObserverPane.tree(&assets),
+            HudTopBar::default().tree(&assets),
+            HudAlertPane.tree(&assets),
             // Flex Row
             NodeBundle {
                 style: Style {
@@ -93,8 +127,10 @@ fn setup(mut commands: Commands, assets: Res<HudAssets>) {
             .with_children([
                 HudControlsPane.tree(&assets),
                 HudSelectedPane.tree(&assets),
+                HudDetailPane.tree(&assets),
                 MinimapUi.tree(&assets),
             ]),
+            HudTooltip.tree(&assets),
         ]),
     );
 }
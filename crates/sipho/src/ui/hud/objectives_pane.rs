@@ -0,0 +1,85 @@
+use super::*;
+use crate::scenario::{MissionObjectives, MissionStatus};
+use bevy::color::palettes::css::{DARK_GRAY, LIGHT_GREEN, TOMATO};
+
+/// Shows mission objectives and the latest scenario message.
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+pub struct HudObjectivesPane;
+impl MakeBundleTree<HudUiNode, &HudAssets> for HudObjectivesPane {
+    fn tree(self, _assets: &HudAssets) -> BundleTree<HudUiNode> {
+        HudObjectivesPaneBundle::default().with_children([TextBundle::default().into_tree()])
+    }
+}
+
+#[derive(Bundle)]
+pub struct HudObjectivesPaneBundle {
+    pub data: HudObjectivesPane,
+    pub node: NodeBundle,
+}
+impl Default for HudObjectivesPaneBundle {
+    fn default() -> Self {
+        Self {
+            data: HudObjectivesPane,
+            node: NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    left: Val::Px(10.0),
+                    max_width: Val::Px(400.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                background_color: DARK_GRAY.with_alpha(0.2).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        }
+    }
+}
+
+impl HudObjectivesPane {
+    pub fn update(
+        mission: Res<MissionObjectives>,
+        mut ui: Query<(&Children, &mut Visibility), With<Self>>,
+        mut text: Query<&mut Text>,
+    ) {
+        if !mission.is_changed() {
+            return;
+        }
+        let (children, mut visibility) = ui.single_mut();
+        *visibility = if mission.message.is_empty() && mission.objectives.is_empty() {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+
+        let mut text = text.get_mut(children[0]).unwrap();
+        text.sections.clear();
+        if !mission.message.is_empty() {
+            text.sections.push(TextSection::new(
+                format!("{}\n", mission.message),
+                TextStyle {
+                    font_size: 16.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+        }
+        for objective in mission.objectives.iter() {
+            let (marker, color) = match objective.status {
+                MissionStatus::InProgress => ("[ ]", TEXT_COLOR),
+                MissionStatus::Completed => ("[x]", LIGHT_GREEN.into()),
+                MissionStatus::Failed => ("[-]", TOMATO.into()),
+            };
+            text.sections.push(TextSection::new(
+                format!("{} {}\n", marker, objective.text),
+                TextStyle {
+                    font_size: 14.0,
+                    color,
+                    ..default()
+                },
+            ));
+        }
+    }
+}
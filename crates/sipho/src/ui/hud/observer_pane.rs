@@ -0,0 +1,77 @@
+use super::*;
+use crate::stats::MatchStats;
+use bevy::color::palettes::css::DARK_GRAY;
+
+/// Shows every team's resources and unit counts to observers.
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+pub struct HudObserverPane;
+impl MakeBundleTree<HudUiNode, &HudAssets> for HudObserverPane {
+    fn tree(self, _assets: &HudAssets) -> BundleTree<HudUiNode> {
+        HudObserverPaneBundle::default().with_children([TextBundle::default().into_tree()])
+    }
+}
+
+#[derive(Bundle)]
+pub struct HudObserverPaneBundle {
+    pub data: HudObserverPane,
+    pub node: NodeBundle,
+}
+impl Default for HudObserverPaneBundle {
+    fn default() -> Self {
+        Self {
+            data: HudObserverPane,
+            node: NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                background_color: DARK_GRAY.with_alpha(0.2).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        }
+    }
+}
+
+impl HudObserverPane {
+    pub fn update(
+        stats: Res<MatchStats>,
+        team_config: Res<TeamConfig>,
+        mut ui: Query<(&Children, &mut Visibility), With<Self>>,
+        mut text: Query<&mut Text>,
+    ) {
+        if !stats.is_changed() && !team_config.is_changed() {
+            return;
+        }
+        let (children, mut visibility) = ui.single_mut();
+        *visibility = if team_config.observer {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        let mut text = text.get_mut(children[0]).unwrap();
+        text.sections.clear();
+        for team in enum_iterator::all::<Team>().filter(|&team| team != Team::None) {
+            let team_stats = stats.team(team);
+            if !team_stats.playing {
+                continue;
+            }
+            text.sections.push(TextSection::new(
+                format!(
+                    "{:?}  Heads {}  Units {}  Resources {}\n",
+                    team, team_stats.heads, team_stats.units, team_stats.resources_gathered
+                ),
+                TextStyle {
+                    font_size: 14.0,
+                    color: Team::COLORS[team as usize],
+                    ..default()
+                },
+            ));
+        }
+    }
+}
@@ -65,23 +65,37 @@ impl Default for HudSelectedPaneBundle {
 
 impl HudSelectedPane {
     pub fn update(
-        selection: Query<&Object, With<Selected>>,
+        selection: Query<(&Object, Option<&Stealth>), With<Selected>>,
         ui: Query<(&Self, &Children)>,
         mut buttons: Query<&mut HudUnitButton>,
     ) {
-        let mut objects: HashMap<Object, usize> = HashMap::new();
-        for object in selection.iter() {
-            *objects.entry(*object).or_insert(0) += 1;
+        // Count of selected objects, stealthy ones and revealed ones per object type.
+        let mut objects: HashMap<Object, (usize, usize, usize)> = HashMap::new();
+        for (object, stealth) in selection.iter() {
+            let (count, stealthy, revealed) = objects.entry(*object).or_default();
+            *count += 1;
+            if let Some(stealth) = stealth {
+                *stealthy += 1;
+                if stealth.revealed {
+                    *revealed += 1;
+                }
+            }
         }
-        let mut sorted: Vec<(Object, usize)> = objects.iter().map(|(&k, &v)| (k, v)).collect();
+        let mut sorted: Vec<(Object, (usize, usize, usize))> =
+            objects.iter().map(|(&k, &v)| (k, v)).collect();
         sorted.sort_by_key(|&(object, _)| object);
 
         let (_ui, button_ids) = ui.single();
         for (i, button_id) in button_ids.iter().enumerate() {
             if let Ok(mut button) = buttons.get_mut(*button_id) {
                 if i < sorted.len() {
-                    let (object, count) = sorted[i];
+                    let (object, (count, stealthy, revealed)) = sorted[i];
                     button.text = format!("{object:?}\n{count}");
+                    if revealed > 0 {
+                        button.text += &format!("\nRevealed {revealed}");
+                    } else if stealthy > 0 {
+                        button.text += "\nHidden";
+                    }
                 } else {
                     button.text = "".to_string();
                 }
@@ -0,0 +1,222 @@
+use super::*;
+use crate::ui::localization::Localization;
+use bevy::{
+    color::palettes::css::{DARK_GRAY, TOMATO},
+    window::PrimaryWindow,
+};
+use sipho_core::inputs::InputConfig;
+use std::time::Duration;
+
+/// Resources spent by a control action.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ActionCost {
+    /// Food spent from a head.
+    pub food: usize,
+    /// Selected workers used up.
+    pub workers: usize,
+}
+impl ActionCost {
+    pub fn new(action: ControlAction, configs: &ObjectConfigs) -> Self {
+        let food_cost = |object: Object| configs.get(&object).map_or(0, |config| config.food_cost);
+        match action {
+            ControlAction::Build(object) => Self {
+                food: food_cost(object),
+                ..default()
+            },
+            ControlAction::Grow => Self {
+                food: food_cost(Object::WORKER),
+                ..default()
+            },
+            ControlAction::SpawnShocker => Self {
+                food: food_cost(Object::SHOCKER),
+                ..default()
+            },
+            ControlAction::Fuse => Self {
+                workers: configs
+                    .get(&Object::HEAD)
+                    .map_or(0, |config| config.spawn_cost.max(0) as usize),
+                ..default()
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// True if the selection holds enough resources to pay the cost.
+    pub fn is_affordable(self, food: usize, workers: usize) -> bool {
+        food >= self.food && workers >= self.workers
+    }
+}
+
+/// Shows what the hovered command button does.
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+pub struct HudTooltip;
+impl MakeBundleTree<HudUiNode, &HudAssets> for HudTooltip {
+    fn tree(self, _assets: &HudAssets) -> BundleTree<HudUiNode> {
+        HudTooltipBundle::default().with_children([TextBundle::default().into_tree()])
+    }
+}
+
+#[derive(Bundle)]
+pub struct HudTooltipBundle {
+    pub data: HudTooltip,
+    pub node: NodeBundle,
+}
+impl Default for HudTooltipBundle {
+    fn default() -> Self {
+        Self {
+            data: HudTooltip,
+            node: NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    max_width: Val::Px(300.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: DARK_GRAY.with_alpha(0.9).into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+        }
+    }
+}
+
+impl HudTooltip {
+    /// Distance in pixels between the cursor and the tooltip.
+    pub const CURSOR_OFFSET: f32 = 16.;
+
+    /// Show the name, description, cost, cooldown and hotkey of the hovered button.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
+        buttons: Query<(&HudControlsButton, &Interaction)>,
+        mut ui: Query<(&Children, &mut Style, &mut Visibility), With<Self>>,
+        mut text: Query<&mut Text>,
+        windows: Query<&Window, With<PrimaryWindow>>,
+        selected: Query<&Object, With<Selected>>,
+        configs: Res<ObjectConfigs>,
+        input_config: Res<InputConfig>,
+        localization: Res<Localization>,
+    ) {
+        let (children, mut style, mut visibility) = ui.single_mut();
+        let hovered = buttons
+            .iter()
+            .filter(|(_, &interaction)| interaction == Interaction::Hovered)
+            .find_map(|(button, _)| button.control.map(|control| (button, control)));
+        let (Some((button, control)), Ok(window)) = (hovered, windows.get_single()) else {
+            *visibility = Visibility::Hidden;
+            return;
+        };
+        let Some(cursor) = window.cursor_position() else {
+            *visibility = Visibility::Hidden;
+            return;
+        };
+        *visibility = Visibility::Inherited;
+        // Buttons are at the bottom of the screen, so show the tooltip above the cursor.
+        style.left = Val::Px(cursor.x + Self::CURSOR_OFFSET);
+        style.bottom = Val::Px(window.height() - cursor.y + Self::CURSOR_OFFSET);
+
+        let control_text = localization.control(control);
+        let mut lines = vec![];
+        let cost = ActionCost::new(control, &configs);
+        if cost.food > 0 {
+            lines.push(format!("{} {}", cost.food, localization.label("Food")));
+        }
+        if cost.workers > 0 {
+            lines.push(format!(
+                "{} {}",
+                cost.workers,
+                localization.label("Workers")
+            ));
+        }
+        let cost = if lines.is_empty() {
+            String::new()
+        } else {
+            format!("\n{}: {}", localization.label("Cost"), lines.join(", "))
+        };
+        let mut details = String::new();
+        if let Some(cooldown) = Self::cooldown(control, &selected, &configs) {
+            details += &format!(
+                "\n{}: {:.1}s",
+                localization.label("Cooldown"),
+                cooldown.as_secs_f32()
+            );
+        }
+        if let Some(hotkey) = Self::hotkey(&input_config, button.action) {
+            details += &format!("\n{}: {hotkey}", localization.label("Hotkey"));
+        }
+
+        let mut text = text.get_mut(children[0]).unwrap();
+        text.sections = vec![
+            TextSection::new(
+                control_text.name,
+                TextStyle {
+                    font_size: 16.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            TextSection::new(
+                format!("\n{}", control_text.description),
+                TextStyle {
+                    font_size: 13.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ),
+            TextSection::new(
+                cost,
+                TextStyle {
+                    font_size: 13.0,
+                    color: if button.disabled {
+                        TOMATO.into()
+                    } else {
+                        TEXT_COLOR
+                    },
+                    ..default()
+                },
+            ),
+            TextSection::new(
+                details,
+                TextStyle {
+                    font_size: 13.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ),
+        ];
+    }
+
+    /// Longest attack cooldown among the selected objects.
+    fn cooldown(
+        control: ControlAction,
+        selected: &Query<&Object, With<Selected>>,
+        configs: &ObjectConfigs,
+    ) -> Option<Duration> {
+        if !matches!(control, ControlAction::Attack | ControlAction::AttackMove) {
+            return None;
+        }
+        selected
+            .iter()
+            .filter_map(|object| configs[object].attacker)
+            .map(AttackerKind::cooldown)
+            .max()
+    }
+
+    /// Name of the key bound to the input, like "Q" for `KeyQ`.
+    /// If several keys are bound, the first in `KeyCode` order is shown.
+    fn hotkey(config: &InputConfig, action: InputAction) -> Option<String> {
+        let key = config
+            .keyboard
+            .iter()
+            .filter(|(_, &bound)| bound == action)
+            .map(|(key, _)| key)
+            .min()
+            .map(|key| format!("{key:?}"))?;
+        let name = key
+            .strip_prefix("Key")
+            .or_else(|| key.strip_prefix("Digit"))
+            .unwrap_or(&key);
+        Some(name.to_string())
+    }
+}
@@ -0,0 +1,111 @@
+use super::*;
+use crate::stats::{MatchStats, TeamStats};
+use bevy::color::palettes::css::{DARK_GRAY, LIGHT_GREEN, TOMATO};
+
+/// Seconds a changed value stays highlighted.
+pub const PULSE_SECONDS: f32 = 0.6;
+
+/// Values shown in the top bar, in order.
+const FIELDS: [&str; 7] = [
+    "Food", "Gems", "Income", "Heads", "Workers", "Shockers", "Armor",
+];
+
+/// Shows the player's stored resources, income and unit counts.
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+pub struct HudTopBar {
+    /// Last shown value per field.
+    values: [usize; FIELDS.len()],
+    /// Seconds left highlighting each field, and whether it increased.
+    pulses: [(f32, bool); FIELDS.len()],
+}
+impl MakeBundleTree<HudUiNode, &HudAssets> for HudTopBar {
+    fn tree(self, _assets: &HudAssets) -> BundleTree<HudUiNode> {
+        HudTopBarBundle::default().with_children([TextBundle {
+            text: Text::from_sections(FIELDS.map(|_| TextSection::default())),
+            style: Style {
+                padding: UiRect::axes(Val::Px(15.0), Val::Px(5.0)),
+                ..default()
+            },
+            background_color: DARK_GRAY.with_alpha(0.2).into(),
+            ..default()
+        }
+        .into_tree()])
+    }
+}
+
+#[derive(Bundle)]
+pub struct HudTopBarBundle {
+    pub data: HudTopBar,
+    pub node: NodeBundle,
+}
+impl Default for HudTopBarBundle {
+    fn default() -> Self {
+        Self {
+            data: HudTopBar::default(),
+            node: NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        }
+    }
+}
+
+impl HudTopBar {
+    fn values(team_stats: &TeamStats) -> [usize; FIELDS.len()] {
+        [
+            team_stats.food,
+            team_stats.gems,
+            team_stats.income(),
+            team_stats.count(Object::HEAD),
+            team_stats.count(Object::WORKER),
+            team_stats.count(Object::SHOCKER),
+            team_stats.count(Object::ARMOR),
+        ]
+    }
+
+    /// Show the player's stats, highlighting values that changed.
+    pub fn update(
+        stats: Res<MatchStats>,
+        team_config: Res<TeamConfig>,
+        mut ui: Query<(&mut Self, &Children, &mut Visibility)>,
+        mut text: Query<&mut Text>,
+        time: Res<Time>,
+    ) {
+        let (mut bar, children, mut visibility) = ui.single_mut();
+        // Observers see every team's stats in the observer pane instead.
+        *visibility = if team_config.observer {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+
+        let values = Self::values(stats.team(team_config.player_team));
+        let mut text = text.get_mut(children[0]).unwrap();
+        for (i, field) in FIELDS.iter().enumerate() {
+            let (mut pulse, mut increased) = bar.pulses[i];
+            if values[i] != bar.values[i] {
+                pulse = PULSE_SECONDS;
+                increased = values[i] > bar.values[i];
+                bar.values[i] = values[i];
+            }
+            pulse = (pulse - time.delta_seconds()).max(0.);
+            bar.pulses[i] = (pulse, increased);
+
+            let highlight: Color = if increased { LIGHT_GREEN } else { TOMATO }.into();
+            let section = &mut text.sections[i];
+            section.value = match *field {
+                "Income" => format!("+{}/min    ", values[i]),
+                _ => format!("{field} {}  ", values[i]),
+            };
+            section.style.font_size = 16.0 * (1. + 0.2 * pulse / PULSE_SECONDS);
+            section.style.color = TEXT_COLOR.mix(&highlight, pulse / PULSE_SECONDS);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::utils::HashMap;
+
+use crate::{prelude::*, scene::ScenePaths};
+
+/// Plugin for player facing text loaded from a localization table.
+pub struct LocalizationPlugin;
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Localization>()
+            .register_type::<ControlText>()
+            .register_type::<HashMap<ControlAction, ControlText>>()
+            .register_type::<HashMap<String, String>>()
+            .init_resource::<Localization>()
+            .add_systems(PreStartup, Localization::load);
+    }
+}
+
+/// Name and description of a control action.
+#[derive(Clone, Default, Reflect, Debug)]
+pub struct ControlText {
+    pub name: String,
+    pub description: String,
+}
+
+/// Player facing text for the current language.
+#[derive(Resource, Clone, Default, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct Localization {
+    /// Text for each control action.
+    pub controls: HashMap<ControlAction, ControlText>,
+    /// Short labels keyed by their English text.
+    pub labels: HashMap<String, String>,
+}
+impl Localization {
+    pub fn load(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        mut load_state: ResMut<AssetLoadState>,
+        paths: Res<ScenePaths>,
+    ) {
+        let scene = DynamicSceneBundle {
+            scene: asset_server.load(paths.localization.clone()),
+            ..default()
+        };
+        load_state.track(&scene.scene);
+        commands.spawn((Name::new("Localization"), scene));
+    }
+
+    /// Text for the action, falling back to its debug name.
+    pub fn control(&self, action: ControlAction) -> ControlText {
+        self.controls
+            .get(&action)
+            .cloned()
+            .unwrap_or_else(|| ControlText {
+                name: format!("{action:?}"),
+                description: String::new(),
+            })
+    }
+
+    /// Translation of the label, falling back to the label itself.
+    pub fn label<'a>(&'a self, label: &'a str) -> &'a str {
+        self.labels.get(label).map_or(label, String::as_str)
+    }
+}
@@ -1,6 +1,8 @@
 use crate::prelude::*;
 
+pub mod health_bars;
 pub mod hud;
+pub mod localization;
 pub mod pause_menu;
 pub mod selector;
 pub mod waypoint;
@@ -14,7 +16,9 @@ pub struct UiPlugin;
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
+            health_bars::HealthBarsPlugin,
             hud::HudPlugin,
+            localization::LocalizationPlugin,
             pause_menu::PauseMenuPlugin,
             selector::SelectorPlugin,
             waypoint::WaypointPlugin,
@@ -9,7 +9,9 @@ impl Plugin for WaypointPlugin {
             FixedUpdate,
             (
                 Waypoint::cleanup.in_set(FixedUpdateStage::Cleanup),
-                Waypoint::update.in_set(FixedUpdateStage::Spawn),
+                Waypoint::update
+                    .run_if(TeamConfig::is_playing)
+                    .in_set(FixedUpdateStage::Spawn),
             )
                 .in_set(GameStateSet::Running),
         );
@@ -62,23 +64,23 @@ impl Waypoint {
     #[allow(clippy::too_many_arguments)]
     pub fn update(
         mut control_events: EventReader<ControlEvent>,
-        selection: Query<(Entity, &Object, &AttachedTo), With<Selected>>,
+        selection: Query<(Entity, &Object, &AttachedTo, &Position), With<Selected>>,
         teams: Query<&Team>,
         mut commands: Commands,
         mut objectives: Query<&mut Objectives>,
         assets: Res<WaypointAssets>,
         obstacles: Res<Grid2<Obstacle>>,
         team_config: Res<TeamConfig>,
+        formation: Res<Formation>,
     ) {
         for control in control_events.read() {
             if control.state != ButtonState::Pressed {
                 continue;
             }
-            let objective = match control.action {
+            let (objective, waypoint) = match control.action {
                 ControlAction::Move | ControlAction::AttackMove => {
                     // Don't spawn waypoints in obstacles.
-                    let rowcol = obstacles.to_rowcol(control.position).unwrap();
-                    if !obstacles.is_clear(rowcol) {
+                    if !Self::can_place(&obstacles, control.position) {
                         continue;
                     }
 
@@ -87,40 +89,100 @@ impl Waypoint {
                         Waypoint::default().bundle(&assets, control.position, control.action);
                     let waypoint_entity = commands.spawn(waypoint_bundle).id();
 
-                    Some(match control.action {
+                    let objective = match control.action {
                         ControlAction::Move => Objective::FollowEntity(waypoint_entity),
                         ControlAction::AttackMove => Objective::AttackFollowEntity(waypoint_entity),
                         _ => unreachable!(),
-                    })
+                    };
+                    (objective, Some(waypoint_entity))
                 }
                 ControlAction::Interact => {
-                    if let Ok(team) = teams.get(control.entity) {
-                        Some(if *team == team_config.player_team {
-                            Objective::FollowEntity(control.entity)
-                        } else {
-                            Objective::AttackFollowEntity(control.entity)
-                        })
+                    let Ok(team) = teams.get(control.entity) else {
+                        continue;
+                    };
+                    let objective = if *team == team_config.player_team {
+                        Objective::FollowEntity(control.entity)
                     } else {
-                        None
-                    }
+                        Objective::AttackFollowEntity(control.entity)
+                    };
+                    (objective, None)
                 }
-                _ => None,
+                _ => continue,
             };
 
-            if let Some(objective) = objective {
-                for (entity, object, attached_to) in selection.iter() {
-                    let mut objectives = objectives.get_mut(entity).unwrap();
-                    // Don't change objectives for workers that are in the middle of the parent.
-                    if *object == Object::Worker && attached_to.len() > 1 {
-                        if objectives.last() != &Objective::Idle {
-                            objectives.clear();
-                        }
-                        continue;
-                    }
+            Self::order(
+                selection
+                    .iter()
+                    .map(|(entity, &object, attached_to, position)| {
+                        (entity, object, attached_to.len(), position.0)
+                    }),
+                &objective,
+                waypoint,
+                control.position,
+                *formation,
+                &mut objectives,
+                &obstacles,
+                &mut commands,
+            );
+        }
+    }
+
+    /// True if a waypoint can be placed at the position.
+    pub fn can_place(obstacles: &Grid2<Obstacle>, position: Vec2) -> bool {
+        obstacles
+            .to_rowcol(position)
+            .is_some_and(|rowcol| obstacles.is_clear(rowcol))
+    }
+
+    /// Give units a new objective. Used for both player and computer orders.
+    /// Units are given as (entity, object, attachments, position).
+    /// Workers in the middle of an arm hold their place.
+    /// Units ordered to a waypoint spread out around it in formation.
+    #[allow(clippy::too_many_arguments)]
+    pub fn order(
+        units: impl IntoIterator<Item = (Entity, Object, usize, Vec2)>,
+        objective: &Objective,
+        waypoint: Option<Entity>,
+        position: Vec2,
+        formation: Formation,
+        objectives: &mut Query<&mut Objectives>,
+        obstacles: &Grid2<Obstacle>,
+        commands: &mut Commands,
+    ) {
+        let mut ordered = Vec::new();
+        for (entity, object, attachments, position) in units {
+            let Ok(mut objectives) = objectives.get_mut(entity) else {
+                continue;
+            };
+            // Don't change objectives for workers that are in the middle of the parent.
+            if object == Object::WORKER && attachments > 1 {
+                if objectives.last() != &Objective::Idle {
                     objectives.clear();
-                    objectives.push(objective.clone());
                 }
+                continue;
+            }
+            objectives.clear();
+            if *objective != Objective::Idle {
+                objectives.push(objective.clone());
+            }
+            ordered.push((entity, position));
+        }
+
+        // Spread units moving to a waypoint into formation around it.
+        let Some(anchor) = waypoint else {
+            for (entity, _) in ordered {
+                commands.entity(entity).remove::<FormationSlot>();
+            }
+            return;
+        };
+        for (entity, mut offset) in formation.assign(&ordered, position, obstacles.spec.width) {
+            // Units whose slot is blocked go to the waypoint itself.
+            if !Self::can_place(obstacles, position + offset) {
+                offset = Vec2::ZERO;
             }
+            commands
+                .entity(entity)
+                .insert(FormationSlot { anchor, offset });
         }
     }
 
@@ -0,0 +1,87 @@
+//! Measures line of sight fog of war on a large grid with thousands of units.
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use sipho_core::{
+    grid::fog::{FogConfig, TeamVisibility},
+    prelude::*,
+};
+
+const SIZE: u16 = 256;
+const UNITS: u16 = 4096;
+const RADIUS: u16 = 8;
+
+fn spec() -> GridSpec {
+    GridSpec {
+        rows: SIZE,
+        cols: SIZE,
+        width: 64.0,
+        visualize: false,
+        visualize_navigation: false,
+    }
+}
+
+/// Open water with small square islands, like most maps.
+fn islands() -> Grid2<Obstacle> {
+    let mut obstacles = Grid2::<Obstacle> {
+        spec: spec(),
+        ..Default::default()
+    };
+    obstacles.resize();
+    for row in (16..SIZE - 16).step_by(32) {
+        for col in (16..SIZE - 16).step_by(32) {
+            for rowcol in obstacles.get_in_radius_discrete((row, col), 4) {
+                obstacles[rowcol] = Obstacle::Full;
+            }
+        }
+    }
+    obstacles
+}
+
+/// Cells for units spread over the map, away from the boundary.
+fn unit_cells() -> Vec<RowCol> {
+    (0..UNITS)
+        .map(|i| (16 + (i * 37) % (SIZE - 32), 16 + (i * 101) % (SIZE - 32)))
+        .collect()
+}
+
+fn bench_fog(c: &mut Criterion) {
+    let obstacles = islands();
+    let config = FogConfig::default();
+    let mut visibility = Grid2::<TeamVisibility> {
+        spec: spec(),
+        ..Default::default()
+    };
+    visibility.resize();
+    let mut sightlines = Sightlines::default();
+    let cells = unit_cells();
+    for (i, &rowcol) in cells.iter().enumerate() {
+        let team = if i % 2 == 0 { Team::Blue } else { Team::Red };
+        visibility.add_visibility((rowcol, RADIUS), team, &mut sightlines, &obstacles, &config);
+    }
+
+    let mut group = c.benchmark_group("fog");
+    group.bench_function("line_of_sight", |b| {
+        b.iter(|| obstacles.line_of_sight(black_box((SIZE / 2 + 8, SIZE / 2)), RADIUS))
+    });
+    // Every unit steps one cell over and back, as if the whole map were moving.
+    group.bench_function("move_units", |b| {
+        b.iter(|| {
+            for (i, &(row, col)) in cells.iter().enumerate() {
+                let team = if i % 2 == 0 { Team::Blue } else { Team::Red };
+                for (from, to) in [((row, col), (row, col + 1)), ((row, col + 1), (row, col))] {
+                    black_box(visibility.remove_visibility((from, RADIUS), team, &mut sightlines));
+                    black_box(visibility.add_visibility(
+                        (to, RADIUS),
+                        team,
+                        &mut sightlines,
+                        &obstacles,
+                        &config,
+                    ));
+                }
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_fog);
+criterion_main!(benches);
@@ -0,0 +1,101 @@
+//! Compares cell level A* with hierarchical path finding on a large maze-like grid.
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use sipho_core::{
+    nav::{hpa::CLUSTER_SIZE, AStarRunner},
+    prelude::*,
+};
+
+const SIZE: u16 = 256;
+
+fn empty_grid() -> Grid2<Obstacle> {
+    let mut obstacles = Grid2::<Obstacle> {
+        spec: GridSpec {
+            rows: SIZE,
+            cols: SIZE,
+            width: 64.0,
+            visualize: false,
+            visualize_navigation: false,
+        },
+        ..Default::default()
+    };
+    obstacles.resize();
+    obstacles
+}
+
+/// Open water with small square islands, like most maps.
+fn islands() -> Grid2<Obstacle> {
+    let mut obstacles = empty_grid();
+    for row in (16..SIZE - 16).step_by(32) {
+        for col in (16..SIZE - 16).step_by(32) {
+            for rowcol in obstacles.get_in_radius_discrete((row, col), 4) {
+                obstacles[rowcol] = Obstacle::Full;
+            }
+        }
+    }
+    obstacles
+}
+
+/// Grid with long walls that each have a single gap, alternating sides.
+fn maze() -> Grid2<Obstacle> {
+    let mut obstacles = empty_grid();
+    for (i, col) in (20..SIZE - 20).step_by(24).enumerate() {
+        let gap = if i % 2 == 0 { SIZE - 12 } else { 12 };
+        for row in 0..SIZE {
+            if row.abs_diff(gap) > 2 {
+                obstacles[(row, col)] = Obstacle::Full;
+            }
+        }
+    }
+    obstacles
+}
+
+fn flow_grid(obstacles: &Grid2<Obstacle>) -> SparseFlowGrid2 {
+    let mut grid = SparseFlowGrid2::default();
+    grid.spec = obstacles.spec.clone();
+    grid
+}
+
+fn bench_map(c: &mut Criterion, name: &str, obstacles: Grid2<Obstacle>) {
+    let grid = flow_grid(&obstacles);
+    let graph = NavigationGraph::build(&obstacles, CLUSTER_SIZE);
+    let destination = (SIZE / 2, SIZE - 8);
+    let sources = [(8, 8), (SIZE / 2, 8), (SIZE - 8, 8)];
+
+    let mut group = c.benchmark_group(name);
+    group.bench_function("build_graph", |b| {
+        b.iter(|| NavigationGraph::build(black_box(&obstacles), CLUSTER_SIZE))
+    });
+    group.bench_function("a_star", |b| {
+        b.iter(|| {
+            AStarRunner::new(destination).a_star(
+                black_box(&sources),
+                destination,
+                &grid,
+                &obstacles,
+            )
+        })
+    });
+    group.bench_function("a_star_hierarchical", |b| {
+        b.iter(|| {
+            AStarRunner::new(destination).a_star_hierarchical(
+                black_box(&sources),
+                destination,
+                &grid,
+                &obstacles,
+                &graph,
+            )
+        })
+    });
+    group.bench_function("find_path", |b| {
+        b.iter(|| graph.find_path(black_box(sources[0]), destination, &obstacles))
+    });
+    group.finish();
+}
+
+fn bench_navigation(c: &mut Criterion) {
+    bench_map(c, "islands", islands());
+    bench_map(c, "maze", maze());
+}
+
+criterion_group!(benches, bench_navigation);
+criterion_main!(benches);
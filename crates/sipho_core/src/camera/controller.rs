@@ -75,8 +75,11 @@ impl CameraController {
         if !grid_spec.is_changed() {
             return;
         }
+        let Ok(window) = window.get_single() else {
+            return;
+        };
         if let Ok((mut controller, camera_transform)) = controller_query.get_single_mut() {
-            if let Some(world2d_size) = Self::get_world2d_size(camera_transform, window.single()) {
+            if let Some(world2d_size) = Self::get_world2d_size(camera_transform, window) {
                 controller.world2d_bounds = grid_spec.world2d_bounds();
                 controller.world2d_bounds.min += world2d_size;
                 controller.world2d_bounds.max -= world2d_size;
@@ -160,7 +163,9 @@ impl CameraController {
         mut event_writer: EventWriter<CameraMoveEvent>,
     ) {
         let dt = time.delta_seconds();
-        let window = window_query.single();
+        let Ok(window) = window_query.get_single() else {
+            return;
+        };
         let (mut controller, mut camera_transform) = controller_query.single_mut();
 
         let mut force = Vec2::ZERO;
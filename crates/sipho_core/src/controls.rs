@@ -216,6 +216,7 @@ impl ControlEvent {
         grid_spec: Option<Res<GridSpec>>,
         time: Res<Time>,
         mut state: ResMut<ControlState>,
+        team_config: Res<TeamConfig>,
     ) {
         let Some(grid_spec) = grid_spec else {
             return;
@@ -261,6 +262,10 @@ impl ControlEvent {
         for event in input_events.read() {
             if let Some(raycast_event) = &raycast_event {
                 let action = ControlAction::from((raycast_event.target, state.mode, event.action));
+                // Observers can look around but not control anything.
+                if team_config.observer && !action.is_observer_action() {
+                    continue;
+                }
                 if let ControlAction::AttackMove = action {
                     state.mode = ControlMode::Normal;
                 }
@@ -332,11 +337,15 @@ pub enum ControlAction {
     TieAll,
     Tie,
     Fuse,
+    Formation,
     PauseMenu,
-    // Build
-    Worker,
-    Armor,
-    Shocker,
+    JumpToAlert,
+    ViewBlue,
+    ViewRed,
+    ViewAll,
+    FollowBattle,
+    /// Place an object of this type.
+    Build(Object),
 }
 impl ControlAction {
     pub fn get_repeat_duration(self) -> Duration {
@@ -345,10 +354,24 @@ impl ControlAction {
             Self::Select => Duration::from_millis(5),
             Self::DragCamera => Duration::from_millis(5),
             Self::PanCamera => Duration::from_millis(5),
-            Self::Worker | Self::Armor | Self::Shocker | Self::Tie => Duration::from_millis(1),
+            Self::Build(_) | Self::Tie => Duration::from_millis(1),
             _ => Duration::from_millis(0),
         }
     }
+
+    /// Returns true if the action is available to observers.
+    pub fn is_observer_action(self) -> bool {
+        matches!(
+            self,
+            Self::PanCamera
+                | Self::DragCamera
+                | Self::PauseMenu
+                | Self::ViewBlue
+                | Self::ViewRed
+                | Self::ViewAll
+                | Self::FollowBattle
+        )
+    }
 }
 impl From<(RaycastTarget, ControlMode, InputAction)> for ControlAction {
     fn from(value: (RaycastTarget, ControlMode, InputAction)) -> Self {
@@ -373,6 +396,11 @@ impl From<(RaycastTarget, ControlMode, InputAction)> for ControlAction {
             (RaycastTarget::WorldGrid, _, InputAction::Fuse) => Self::Fuse,
             (RaycastTarget::WorldGrid, _, InputAction::DragCamera) => Self::DragCamera,
             (_, _, InputAction::PauseMenu) => Self::PauseMenu,
+            (_, _, InputAction::JumpToAlert) => Self::JumpToAlert,
+            (_, _, InputAction::ViewBlue) => Self::ViewBlue,
+            (_, _, InputAction::ViewRed) => Self::ViewRed,
+            (_, _, InputAction::ViewAll) => Self::ViewAll,
+            (_, _, InputAction::FollowBattle) => Self::FollowBattle,
             (RaycastTarget::None, _, _) => Self::None,
             _ => Self::None,
         }
@@ -24,7 +24,7 @@ impl CursorParam<'_, '_> {
     /// Returns the world position of the cursor.
     pub fn ray3d(&self) -> Option<Ray3d> {
         let (camera, camera_transform) = self.camera.single();
-        let window = self.window.single();
+        let window = self.window.get_single().ok()?;
         let cursor_position = window.cursor_position()?;
         camera.viewport_to_world(camera_transform, cursor_position)
     }
@@ -60,7 +60,9 @@ impl Cursor {
         control_state: Res<ControlState>,
         assets: Res<CursorAssets>,
     ) {
-        let window = window.single_mut();
+        let Ok(window) = window.get_single_mut() else {
+            return;
+        };
         if let Some(cursor_pixel_position) = window.cursor_position() {
             let (mut style, mut image) = cursor.single_mut();
             style.left = Val::Px(cursor_pixel_position.x - 2.0);
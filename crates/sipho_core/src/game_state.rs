@@ -51,6 +51,8 @@ pub enum GameState {
     Loading,
     Running,
     Paused,
+    /// The game has been won or lost.
+    GameOver,
 }
 
 #[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
@@ -87,7 +89,10 @@ fn toggle_pause_game(
 fn prepare_window(mut next_state: ResMut<NextState<GameState>>, mut window: Query<&mut Window>) {
     // TODO: Make this wait for more frames.
     // https://github.com/bevyengine/bevy/issues/14398
-    window.single_mut().visible = true;
+    // Headless apps have no window.
+    if let Ok(mut window) = window.get_single_mut() {
+        window.visible = true;
+    }
     next_state.set(GameState::Loading);
 }
 
@@ -0,0 +1,148 @@
+use crate::prelude::*;
+
+/// Fraction of the current applied as force each tick.
+const CURRENT_FORCE: f32 = 0.05;
+/// Typical swimming speed, used to weigh how much currents help or hinder navigation.
+const SWIM_SPEED: f32 = 5.0;
+/// Speed of a full strength `Seabed::Current` channel.
+const CHANNEL_SPEED: f32 = 6.0;
+
+/// Plugin for ocean currents.
+pub struct CurrentsPlugin;
+impl Plugin for CurrentsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CurrentsSpec>()
+            .init_resource::<CurrentField>()
+            .register_type::<CurrentsSpec>()
+            .register_type::<CurrentSource>()
+            .register_type::<Vec<CurrentSource>>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    CurrentField::update.in_set(FixedUpdateStage::PostSpawn),
+                    CurrentField::apply
+                        .in_set(FixedUpdateStage::AccumulateForces)
+                        .in_set(GameStateSet::Running),
+                ),
+            );
+    }
+}
+
+/// Analytic source of current, in world units per tick.
+#[derive(Reflect, Debug, Clone)]
+pub enum CurrentSource {
+    /// Uniform flow across the whole map. Maps opt into it through their scenario.
+    Stream { velocity: Vec2 },
+    /// Flow circling a center, counterclockwise for positive strength.
+    /// Speed peaks at the radius and falls off quickly outside of it.
+    Vortex {
+        center: Vec2,
+        radius: f32,
+        strength: f32,
+    },
+}
+impl CurrentSource {
+    /// Velocity of this source's flow at a world position.
+    pub fn velocity(&self, position: Vec2) -> Vec2 {
+        match *self {
+            Self::Stream { velocity } => velocity,
+            Self::Vortex {
+                center,
+                radius,
+                strength,
+            } => {
+                let delta = position - center;
+                let distance = delta.length();
+                if distance == 0. || radius <= 0. {
+                    return Vec2::ZERO;
+                }
+                let speed = if distance < radius {
+                    strength * distance / radius
+                } else {
+                    strength * (radius / distance).powi(2)
+                };
+                delta.perp() / distance * speed
+            }
+        }
+    }
+}
+
+/// Currents for the map, loaded from the config scene.
+#[derive(Resource, Default, Deref, DerefMut, Reflect)]
+#[reflect(Resource)]
+pub struct CurrentsSpec(pub Vec<CurrentSource>);
+
+/// Current velocity per cell, baked from `CurrentsSpec`.
+#[derive(Resource, Default, Clone, Debug, Deref, DerefMut)]
+pub struct CurrentField(pub Grid2<Vec2>);
+impl CurrentField {
+    /// Rebake the field when the currents, seabed or grid change.
+    pub fn update(
+        mut field: ResMut<Self>,
+        currents: Res<CurrentsSpec>,
+        seabed: Res<Grid2<Seabed>>,
+        spec: Res<GridSpec>,
+    ) {
+        if currents.is_changed() || seabed.is_changed() || spec.is_changed() {
+            *field = Self::build(&currents, &seabed, &spec);
+        }
+    }
+
+    /// Sample every source at each cell center, plus the seabed's current channels.
+    pub fn build(currents: &CurrentsSpec, seabed: &Grid2<Seabed>, spec: &GridSpec) -> Self {
+        let mut field = Grid2::<Vec2> {
+            spec: spec.clone(),
+            ..default()
+        };
+        field.resize();
+        for row in 0..spec.rows {
+            for col in 0..spec.cols {
+                let position = spec.to_world_position((row, col));
+                let channel = seabed
+                    .get((row, col))
+                    .map(|seabed| seabed.drift() * CHANNEL_SPEED)
+                    .unwrap_or_default();
+                field[(row, col)] = currents
+                    .iter()
+                    .map(|source| source.velocity(position))
+                    .sum::<Vec2>()
+                    + channel;
+            }
+        }
+        Self(field)
+    }
+
+    /// Current at a world position.
+    pub fn at(&self, position: Vec2) -> Vec2 {
+        self.to_rowcol(position)
+            .and_then(|rowcol| self.get(rowcol))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Factor for the cost of moving from a cell to a neighboring cell.
+    /// Swimming with the current is cheaper than swimming against it.
+    pub fn weight(&self, from: RowCol, to: RowCol) -> f32 {
+        let Some(&current) = self.get(from) else {
+            return 1.;
+        };
+        let step = Vec2::new(to.1 as f32 - from.1 as f32, to.0 as f32 - from.0 as f32);
+        (1. - step.normalize_or_zero().dot(current) / SWIM_SPEED).clamp(0.5, 2.)
+    }
+
+    /// Push physics objects along the current.
+    /// Force is weighted by mass so light and heavy objects drift together.
+    pub fn apply(
+        field: Res<Self>,
+        mut query: Query<(&Position, &Mass, &mut Force), Without<Parent>>,
+    ) {
+        if field.cells.is_empty() {
+            return;
+        }
+        query
+            .par_iter_mut()
+            .for_each(|(position, mass, mut force)| {
+                *force += Force(field.at(position.0) * CURRENT_FORCE * mass.0);
+            });
+    }
+}
@@ -0,0 +1,127 @@
+use bevy::utils::HashMap;
+
+use crate::{grid::fog::TeamVisibility, prelude::*};
+
+/// Seconds a stealthy entity stays revealed after attacking.
+pub const ATTACK_REVEAL_SECONDS: f32 = 3.0;
+
+/// Stores how many detectors of each team cover a cell.
+#[derive(Clone, Default)]
+pub struct TeamDetection {
+    teams: [u32; Team::COUNT],
+}
+impl TeamDetection {
+    pub fn get(&self, team: Team) -> u32 {
+        self.teams[team as usize]
+    }
+
+    pub fn get_mut(&mut self, team: Team) -> &mut u32 {
+        &mut self.teams[team as usize]
+    }
+}
+
+/// Detection area each entity currently covers.
+#[derive(Resource, Default, Debug, Deref, DerefMut)]
+pub struct Detectors(pub HashMap<Entity, SightKey>);
+
+impl Grid2<TeamDetection> {
+    /// Move detection areas with their entities, like `Grid2::<TeamVisibility>::update` does for vision.
+    pub fn update(
+        mut grid: ResMut<Self>,
+        mut detectors: ResMut<Detectors>,
+        visions: Query<&Vision>,
+        changed_visions: Query<(Entity, &Vision, &GridEntity, &Team), Changed<Vision>>,
+        mut grid_events: EventReader<EntityGridEvent>,
+    ) {
+        for (entity, vision, grid_entity, &team) in &changed_visions {
+            let area = vision.detection(grid_entity.rowcol, team);
+            if detectors.get(&entity).copied() != area {
+                grid.update_detector(entity, team, area, &mut detectors);
+            }
+        }
+        for event in grid_events.read() {
+            let area = visions
+                .get(event.entity)
+                .ok()
+                .and_then(|vision| vision.detection(event.rowcol, event.team));
+            grid.update_detector(event.entity, event.team, area, &mut detectors);
+        }
+    }
+
+    fn update_detector(
+        &mut self,
+        entity: Entity,
+        team: Team,
+        area: Option<SightKey>,
+        detectors: &mut Detectors,
+    ) {
+        if let Some((rowcol, radius)) = detectors.remove(&entity) {
+            for other_rowcol in self.get_in_radius_discrete(rowcol, radius) {
+                let detection = self[other_rowcol].get_mut(team);
+                *detection = detection.saturating_sub(1);
+            }
+        }
+        if let Some((rowcol, radius)) = area {
+            for other_rowcol in self.get_in_radius_discrete(rowcol, radius) {
+                *self[other_rowcol].get_mut(team) += 1;
+            }
+            detectors.insert(entity, (rowcol, radius));
+        }
+    }
+
+    /// Returns true if the team detects stealthy entities in the cell.
+    pub fn is_detected(&self, rowcol: RowCol, team: Team) -> bool {
+        self.get(rowcol)
+            .is_some_and(|detection| detection.get(team) > 0)
+    }
+}
+
+/// Hides an entity from enemies, unless they detect it or it has attacked recently.
+#[derive(Component, Reflect, Default, Debug, Clone)]
+#[reflect(Component)]
+pub struct Stealth {
+    /// Seconds left revealed to everyone.
+    pub reveal_timer: f32,
+    /// True if any enemy team can currently see the entity.
+    pub revealed: bool,
+}
+impl Stealth {
+    /// Reveal the entity to everyone for a while.
+    pub fn reveal(&mut self, seconds: f32) {
+        self.reveal_timer = self.reveal_timer.max(seconds);
+    }
+
+    /// Returns true if the observing team can see through the stealth in the given cell.
+    pub fn is_revealed_to(
+        &self,
+        rowcol: RowCol,
+        observer: Team,
+        detection: &Grid2<TeamDetection>,
+    ) -> bool {
+        self.reveal_timer > 0. || detection.is_detected(rowcol, observer)
+    }
+
+    /// Count down reveal timers and update whether enemies can see each entity.
+    pub fn update(
+        mut query: Query<(&mut Self, &Team, &GridEntity)>,
+        visibility: Res<Grid2<TeamVisibility>>,
+        detection: Res<Grid2<TeamDetection>>,
+        time: Res<Time>,
+    ) {
+        for (mut stealth, &team, grid_entity) in &mut query {
+            stealth.reveal_timer = (stealth.reveal_timer - time.delta_seconds()).max(0.);
+            let Some(rowcol) = grid_entity.rowcol else {
+                continue;
+            };
+            let revealed = enum_iterator::all::<Team>()
+                .filter(|&observer| observer != team && observer != Team::None)
+                .any(|observer| {
+                    visibility.is_visible(observer, rowcol)
+                        && stealth.is_revealed_to(rowcol, observer, &detection)
+                });
+            if stealth.revealed != revealed {
+                stealth.revealed = revealed;
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@ use std::ops::{Index, IndexMut};
 
 use crate::prelude::*;
 use bevy::{prelude::*, utils::HashSet};
+use enum_iterator::all;
 
 pub struct EntityGridPlugin;
 impl Plugin for EntityGridPlugin {
@@ -176,12 +177,26 @@ impl Grid2<TeamEntitySets> {
         teams: &[Team],
         n: usize,
     ) -> HashSet<Entity> {
-        let prefetch = self.get_entities_in_radius(position, radius / 2., teams);
+        self.get_n_filtered_entities_in_radius(position, radius, teams, n, |_| true)
+    }
+
+    /// Like `get_n_entities_in_radius`, but only entities passing the filter count towards `n`.
+    pub fn get_n_filtered_entities_in_radius(
+        &self,
+        position: Vec2,
+        radius: f32,
+        teams: &[Team],
+        n: usize,
+        filter: impl Fn(Entity) -> bool,
+    ) -> HashSet<Entity> {
+        let mut prefetch = self.get_entities_in_radius(position, radius / 2., teams);
+        prefetch.retain(|&entity| filter(entity));
         if prefetch.len() >= n {
-            prefetch
-        } else {
-            self.get_entities_in_radius(position, radius, teams)
+            return prefetch;
         }
+        let mut entities = self.get_entities_in_radius(position, radius, teams);
+        entities.retain(|&entity| filter(entity));
+        entities
     }
 
     /// Remove an entity from the grid entirely.
@@ -207,6 +222,33 @@ impl Grid2<TeamEntitySets> {
         None
     }
 
+    /// Center of the block of cells where the most units of different teams meet, if any.
+    /// Blocks score the units that aren't on their largest team, so one-sided crowds don't count.
+    pub fn largest_battle(&self, block: u16) -> Option<Vec2> {
+        let mut battle = None;
+        let mut best_score = 0;
+        for row in (0..self.rows).step_by(block as usize) {
+            for col in (0..self.cols).step_by(block as usize) {
+                let mut counts = [0; Team::COUNT];
+                for block_row in row..(row + block).min(self.rows) {
+                    for block_col in col..(col + block).min(self.cols) {
+                        let entities = &self[(block_row, block_col)];
+                        for team in all::<Team>().filter(|&team| team != Team::None) {
+                            counts[team as usize] += entities[team].len();
+                        }
+                    }
+                }
+                let total: usize = counts.iter().sum();
+                let score = total - counts.iter().max().unwrap();
+                if score > best_score {
+                    best_score = score;
+                    battle = Some((row + block / 2, col + block / 2));
+                }
+            }
+        }
+        battle.map(|rowcol| self.to_world_position(rowcol))
+    }
+
     /// Get all entities in a given bounding box.
     pub fn get_entities_in_aabb(&self, aabb: &Aabb2) -> Vec<Entity> {
         let mut result = HashSet::default();
@@ -246,4 +288,37 @@ mod tests {
         assert!(grid.get_mut((5, 5)).is_some());
         assert!(grid.get((5, 5)).is_some());
     }
+
+    #[test]
+    fn test_largest_battle() {
+        let mut grid = Grid2::<TeamEntitySets> {
+            spec: GridSpec {
+                rows: 32,
+                cols: 32,
+                width: 10.0,
+                visualize: false,
+                visualize_navigation: false,
+            },
+            ..Default::default()
+        };
+        grid.resize();
+        assert_eq!(grid.largest_battle(8), None);
+
+        // A large crowd of one team isn't a battle.
+        for i in 0..10 {
+            grid.update(Entity::from_raw(i), Team::Blue, None, (2, 2));
+        }
+        assert_eq!(grid.largest_battle(8), None);
+
+        for (i, team) in [Team::Blue, Team::Blue, Team::Red, Team::Red]
+            .into_iter()
+            .enumerate()
+        {
+            grid.update(Entity::from_raw(10 + i as u32), team, None, (17, 25));
+        }
+        assert_eq!(
+            grid.largest_battle(8),
+            Some(grid.to_world_position((20, 28)))
+        );
+    }
 }
@@ -14,15 +14,23 @@ pub struct FogPlugin;
 impl Plugin for FogPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<FogConfig>()
+            .register_type::<Vision>()
+            .register_type::<Stealth>()
             .insert_resource(FogConfig::default())
             .init_resource::<FogAssets>()
+            .init_resource::<Sightlines>()
+            .init_resource::<Viewers>()
+            .init_resource::<Detectors>()
             .add_plugins(ShaderPlanePlugin::<FogShaderMaterial>::default())
             .add_plugins(Grid2Plugin::<TeamVisibility>::default())
+            .add_plugins(Grid2Plugin::<TeamDetection>::default())
             .add_event::<VisibilityUpdateEvent>()
             .add_systems(
                 FixedUpdate,
                 (
                     Grid2::<TeamVisibility>::update,
+                    Grid2::<TeamDetection>::update,
+                    Stealth::update,
                     Grid2::<TeamVisibility>::update_visibility,
                     FogShaderMaterial::update,
                 )
@@ -42,19 +50,29 @@ impl Plugin for FogPlugin {
 #[derive(Resource, Reflect)]
 #[reflect(Resource)]
 pub struct FogConfig {
+    /// Team whose view of the map is shown.
     pub player_team: Team,
-    pub visibility_radius: u16,
-    pub fog_radius: u16,
+    /// Width in cells of the half fogged ring at the edge of each entity's vision.
+    pub fog_margin: u16,
+    /// Show the whole map without fog, e.g. for observers.
+    #[reflect(default)]
+    pub reveal_all: bool,
 }
 impl Default for FogConfig {
     fn default() -> Self {
         Self {
             player_team: Team::Blue,
-            visibility_radius: 7,
-            fog_radius: 6,
+            fog_margin: 1,
+            reveal_all: false,
         }
     }
 }
+impl FogConfig {
+    /// Returns true if the update changes what is shown.
+    pub fn is_shown(&self, update: &VisibilityUpdate) -> bool {
+        !self.reveal_all && update.team == self.player_team
+    }
+}
 
 /// Represents an update to visibility.
 #[derive(Default)]
@@ -75,10 +93,40 @@ pub struct VisibilityUpdateEvent {
 #[derive(Component, Default)]
 pub struct FogEntity;
 
+/// How far an entity sees through the fog of war, in cells.
+/// Entities without vision, or on `Team::None`, reveal nothing.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct Vision {
+    /// Radius revealed around the entity.
+    pub radius: u16,
+    /// Radius in which the entity detects hidden enemies.
+    pub detection_radius: u16,
+}
+impl Vision {
+    /// Sightline the entity looks along from the given cell, if any.
+    pub fn sight(&self, rowcol: Option<RowCol>, team: Team) -> Option<SightKey> {
+        if self.radius == 0 || team == Team::None {
+            return None;
+        }
+        rowcol.map(|rowcol| (rowcol, self.radius))
+    }
+
+    /// Area in which the entity detects stealthy enemies from the given cell, if any.
+    pub fn detection(&self, rowcol: Option<RowCol>, team: Team) -> Option<SightKey> {
+        if self.detection_radius == 0 || team == Team::None {
+            return None;
+        }
+        rowcol.map(|rowcol| (rowcol, self.detection_radius))
+    }
+}
+
 /// Stores visibility per team.
 #[derive(Clone, Default)]
 pub struct TeamVisibility {
     teams: [u32; Team::COUNT],
+    /// True once the team has seen the cell.
+    explored: [bool; Team::COUNT],
 }
 impl TeamVisibility {
     pub fn get(&self, team: Team) -> u32 {
@@ -88,62 +136,148 @@ impl TeamVisibility {
     pub fn get_mut(&mut self, team: Team) -> &mut u32 {
         &mut self.teams[team as usize]
     }
+
+    pub fn is_explored(&self, team: Team) -> bool {
+        self.explored[team as usize]
+    }
 }
 
 impl Grid2<TeamVisibility> {
+    /// Hide entities the player can't see, including enemies hidden by stealth.
     pub fn update_visibility(
-        mut query: Query<(&GridEntity, &mut Visibility), With<FogEntity>>,
+        mut query: Query<(&GridEntity, &Team, Option<&Stealth>, &mut Visibility), With<FogEntity>>,
         grid: ResMut<Self>,
+        detection: Res<Grid2<TeamDetection>>,
         configs: Res<FogConfig>,
     ) {
-        for (grid_entity, mut visibility) in &mut query {
+        let player_team = configs.player_team;
+        for (grid_entity, &team, stealth, mut visibility) in &mut query {
+            if configs.reveal_all {
+                *visibility = Visibility::Visible;
+                continue;
+            }
             if let Some(rowcol) = grid_entity.rowcol {
-                *visibility = grid.get_visibility(rowcol, configs.player_team)
+                let hidden = team != player_team
+                    && stealth.is_some_and(|stealth| {
+                        !stealth.is_revealed_to(rowcol, player_team, &detection)
+                    });
+                *visibility = if hidden {
+                    Visibility::Hidden
+                } else {
+                    grid.get_visibility(rowcol, player_team)
+                };
             }
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update(
         mut grid: ResMut<Self>,
+        mut sightlines: ResMut<Sightlines>,
+        mut viewers: ResMut<Viewers>,
+        obstacles: Res<Grid2<Obstacle>>,
         config: Res<FogConfig>,
+        visions: Query<&Vision>,
+        changed_visions: Query<(Entity, &Vision, &GridEntity, &Team), Changed<Vision>>,
+        mut obstacle_events: EventReader<ObstacleChangeEvent>,
         mut grid_events: EventReader<EntityGridEvent>,
         mut visibility_events: EventWriter<VisibilityUpdateEvent>,
     ) {
         let mut updates = VisibilityUpdateEvent::default();
 
-        for event in grid_events.read() {
-            if let Some(prev_rowcol) = event.prev_rowcol {
-                updates
-                    .removals
-                    .extend(grid.remove_visibility(prev_rowcol, event.team, &config));
+        for event in obstacle_events.read() {
+            for key in sightlines.affected_by(&event.changes) {
+                grid.refresh_sightline(key, &mut sightlines, &obstacles, &config, &mut updates);
             }
-            if let Some(rowcol) = event.rowcol {
-                updates
-                    .additions
-                    .extend(grid.add_visibility(rowcol, event.team, &config));
+        }
+
+        // Entities whose vision grew or shrank in place.
+        for (entity, vision, grid_entity, &team) in &changed_visions {
+            let sight = vision.sight(grid_entity.rowcol, team);
+            if viewers.get(&entity).copied() != sight {
+                grid.update_viewer(
+                    (entity, team, sight),
+                    &mut viewers,
+                    &mut sightlines,
+                    &obstacles,
+                    &config,
+                    &mut updates,
+                );
             }
         }
 
+        for event in grid_events.read() {
+            let sight = visions
+                .get(event.entity)
+                .ok()
+                .and_then(|vision| vision.sight(event.rowcol, event.team));
+            grid.update_viewer(
+                (event.entity, event.team, sight),
+                &mut viewers,
+                &mut sightlines,
+                &obstacles,
+                &config,
+                &mut updates,
+            );
+        }
+
         visibility_events.send(updates);
     }
 
-    fn remove_visibility(
+    /// Move an entity's visibility from the sightline it had to its new one.
+    fn update_viewer(
         &mut self,
-        rowcol: RowCol,
-        team: Team,
+        (entity, team, sight): (Entity, Team, Option<SightKey>),
+        viewers: &mut Viewers,
+        sightlines: &mut Sightlines,
+        obstacles: &Grid2<Obstacle>,
         config: &FogConfig,
+        updates: &mut VisibilityUpdateEvent,
+    ) {
+        if let Some(key) = viewers.remove(&entity) {
+            updates
+                .removals
+                .extend(self.remove_visibility(key, team, sightlines));
+        }
+        if let Some(key) = sight {
+            updates
+                .additions
+                .extend(self.add_visibility(key, team, sightlines, obstacles, config));
+            viewers.insert(entity, key);
+        }
+    }
+
+    /// Stop a unit of the team from looking along the given sightline.
+    pub fn remove_visibility(
+        &mut self,
+        key: SightKey,
+        team: Team,
+        sightlines: &mut Sightlines,
+    ) -> Vec<VisibilityUpdate> {
+        let Some(sightline) = sightlines.get_mut(&key) else {
+            return Vec::default();
+        };
+        let viewers = &mut sightline.viewers[team as usize];
+        *viewers = viewers.saturating_sub(1);
+        let updates = self.remove_visibility_in_cells(&sightline.cells, team, 1);
+        if sightline.is_empty() {
+            sightlines.remove(&key);
+        }
+        updates
+    }
+
+    fn remove_visibility_in_cells(
+        &mut self,
+        cells: &[RowCol],
+        team: Team,
+        count: u32,
     ) -> Vec<VisibilityUpdate> {
         let mut updates = Vec::default();
-        for other_rowcol in self.get_in_radius_discrete(rowcol, config.visibility_radius) {
-            // Don't add visibility on the boundary.
-            if self.is_near_boundary(other_rowcol) {
-                continue;
-            }
+        for &other_rowcol in cells {
             if let Some(grid_visibility) = self.get_mut(other_rowcol) {
-                if grid_visibility.get(team) > 0 {
-                    *grid_visibility.get_mut(team) -= 1;
-                }
-                if team == config.player_team && grid_visibility.get(team) == 0 {
+                let visibility = grid_visibility.get_mut(team);
+                *visibility = visibility.saturating_sub(count);
+                if *visibility == 0 {
                     updates.push(VisibilityUpdate {
                         team,
                         rowcol: other_rowcol,
@@ -155,42 +289,137 @@ impl Grid2<TeamVisibility> {
         updates
     }
 
+    /// Recompute a sightline after obstacles near it changed, moving its viewers' visibility over.
+    fn refresh_sightline(
+        &mut self,
+        key: SightKey,
+        sightlines: &mut Sightlines,
+        obstacles: &Grid2<Obstacle>,
+        config: &FogConfig,
+        updates: &mut VisibilityUpdateEvent,
+    ) {
+        let Some(sightline) = sightlines.get_mut(&key) else {
+            return;
+        };
+        let (source, radius) = key;
+        let cells = obstacles.line_of_sight(source, radius);
+        for team in enum_iterator::all::<Team>() {
+            let count = sightline.viewers[team as usize];
+            if count == 0 {
+                continue;
+            }
+            updates
+                .additions
+                .extend(self.add_visibility_in_cells(key, &cells, team, count, config));
+            updates
+                .removals
+                .extend(self.remove_visibility_in_cells(&sightline.cells, team, count));
+        }
+        sightline.cells = cells;
+    }
+
+    /// Returns true if the team currently sees the cell.
+    pub fn is_visible(&self, team: Team, rowcol: RowCol) -> bool {
+        self.get(rowcol)
+            .is_some_and(|visibility| visibility.get(team) > 0)
+    }
+
     /// Return the visibility status at the cell corresponding to position for the given team.
     pub fn get_visibility(&self, rowcol: RowCol, team: Team) -> Visibility {
-        if let Some(visibility) = self.get(rowcol) {
-            if visibility.get(team) > 0 {
-                return Visibility::Visible;
-            }
+        if self.is_visible(team, rowcol) {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        }
+    }
+
+    /// Returns true if the team has ever seen the cell.
+    pub fn is_explored(&self, rowcol: RowCol, team: Team) -> bool {
+        self.get(rowcol)
+            .is_some_and(|visibility| visibility.is_explored(team))
+    }
+
+    /// How clear the cell is shown with the given config, from 0 (unexplored) to 1 (visible).
+    /// Used to redraw the fog when switching views, without the half fogged margins.
+    pub fn fog_amount(&self, rowcol: RowCol, config: &FogConfig) -> f32 {
+        if config.reveal_all || self.is_visible(config.player_team, rowcol) {
+            1.0
+        } else if self.is_explored(rowcol, config.player_team) {
+            0.5
+        } else {
+            0.0
         }
-        Visibility::Hidden
     }
 
-    fn add_visibility(
+    /// Permanently reveal cells in the radius around the given cell for the team.
+    pub fn reveal(
         &mut self,
         cell: RowCol,
+        radius: u16,
         team: Team,
         config: &FogConfig,
     ) -> Vec<VisibilityUpdate> {
-        let mut updates = Vec::default();
-        for other_rowcol in self.get_in_radius_discrete(cell, config.visibility_radius) {
+        self.add_visibility_in_radius(cell, radius, team, config)
+    }
+
+    /// Start a unit of the team looking along the given sightline.
+    pub fn add_visibility(
+        &mut self,
+        key: SightKey,
+        team: Team,
+        sightlines: &mut Sightlines,
+        obstacles: &Grid2<Obstacle>,
+        config: &FogConfig,
+    ) -> Vec<VisibilityUpdate> {
+        let (rowcol, radius) = key;
+        let sightline = sightlines.entry(key).or_insert_with(|| Sightline {
+            cells: obstacles.line_of_sight(rowcol, radius),
+            ..default()
+        });
+        sightline.viewers[team as usize] += 1;
+        self.add_visibility_in_cells(key, &sightline.cells, team, 1, config)
+    }
+
+    fn add_visibility_in_radius(
+        &mut self,
+        cell: RowCol,
+        radius: u16,
+        team: Team,
+        config: &FogConfig,
+    ) -> Vec<VisibilityUpdate> {
+        let cells: Vec<RowCol> = self
+            .get_in_radius_discrete(cell, radius)
+            .into_iter()
             // Don't add visibility on the boundary.
-            if self.is_boundary(other_rowcol) {
-                continue;
-            }
+            .filter(|&rowcol| !self.is_boundary(rowcol))
+            .collect();
+        self.add_visibility_in_cells((cell, radius), &cells, team, 1, config)
+    }
+
+    fn add_visibility_in_cells(
+        &mut self,
+        (cell, radius): SightKey,
+        cells: &[RowCol],
+        team: Team,
+        count: u32,
+        config: &FogConfig,
+    ) -> Vec<VisibilityUpdate> {
+        let mut updates = Vec::default();
+        for &other_rowcol in cells {
             if let Some(grid_visibility) = self.get_mut(other_rowcol) {
-                *grid_visibility.get_mut(team) += 1;
-                if team == config.player_team {
-                    let amount = if GridSpec::in_radius(cell, other_rowcol, config.fog_radius) {
-                        1.0
-                    } else {
-                        0.5
-                    };
-                    updates.push(VisibilityUpdate {
-                        team,
-                        rowcol: other_rowcol,
-                        amount,
-                    });
-                }
+                *grid_visibility.get_mut(team) += count;
+                grid_visibility.explored[team as usize] = true;
+                let clear_radius = radius.saturating_sub(config.fog_margin);
+                let amount = if GridSpec::in_radius(cell, other_rowcol, clear_radius) {
+                    1.0
+                } else {
+                    0.5
+                };
+                updates.push(VisibilityUpdate {
+                    team,
+                    rowcol: other_rowcol,
+                    amount,
+                });
             }
         }
         updates
@@ -255,21 +484,35 @@ impl FogShaderMaterial {
         mut shader_assets: ResMut<Assets<Self>>,
         mut updates: EventReader<VisibilityUpdateEvent>,
         fog_assets: Res<FogAssets>,
+        grid: Res<Grid2<TeamVisibility>>,
+        config: Res<FogConfig>,
         mut images: ResMut<Assets<Image>>,
     ) {
+        let is_shown = |update: &&VisibilityUpdate| config.is_shown(update);
         // Mark shader assets as changed.
         shader_assets.get_mut(&assets.shader_material);
 
         let image = images.get_mut(&fog_assets.texture).unwrap();
         if let Ok(DynamicImage::ImageRgba8(mut rgba)) = image.clone().try_into_dynamic() {
+            // Redraw everything when switching to another team's view.
+            if config.is_changed() {
+                for (x, y, pixel) in rgba.enumerate_pixels_mut() {
+                    let amount = grid.fog_amount((y as u16, x as u16), &config);
+                    pixel.0[3] = ((amount * 0.99) * (u8::MAX as f32)) as u8;
+                }
+            }
             for event in updates.read() {
-                for &VisibilityUpdate { rowcol, amount, .. } in &event.removals {
+                for &VisibilityUpdate { rowcol, amount, .. } in
+                    event.removals.iter().filter(is_shown)
+                {
                     let amount_u8 = ((amount * 0.99) * (u8::MAX as f32)) as u8;
                     let (y, x) = rowcol;
                     let pixel = rgba.get_pixel_mut(x as u32, y as u32);
                     pixel.0[3] = amount_u8;
                 }
-                for &VisibilityUpdate { rowcol, amount, .. } in &event.additions {
+                for &VisibilityUpdate { rowcol, amount, .. } in
+                    event.additions.iter().filter(is_shown)
+                {
                     let amount_u8 = ((amount * 0.99) * (u8::MAX as f32)) as u8;
                     let (y, x) = rowcol;
                     let pixel = rgba.get_pixel_mut(x as u32, y as u32);
@@ -1,20 +1,28 @@
 use crate::prelude::*;
 
+pub mod currents;
+pub mod detection;
 pub mod entity;
 pub mod fog;
 pub mod grid2;
 pub mod obstacles;
 pub mod rowcol;
+pub mod seabed;
+pub mod sight;
 pub mod sparse_grid2;
 pub mod spec;
 pub mod visualizer;
 
 pub use {
+    currents::{CurrentField, CurrentSource, CurrentsSpec},
+    detection::{Detectors, Stealth, TeamDetection},
     entity::{EntityGridEvent, EntitySet, GridEntity, TeamEntitySets},
-    fog::{FogEntity, VisibilityUpdate, VisibilityUpdateEvent},
+    fog::{FogEntity, VisibilityUpdate, VisibilityUpdateEvent, Vision},
     grid2::{Grid2, Grid2Plugin},
-    obstacles::Obstacle,
+    obstacles::{Obstacle, ObstacleChangeEvent, ObstacleEditEvent},
     rowcol::{RowCol, RowColDistance},
+    seabed::{Seabed, SeabedPatch, SeabedSpec},
+    sight::{SightKey, Sightline, Sightlines, Viewers},
     sparse_grid2::SparseGrid2,
     spec::{GridSize, GridSpec},
 };
@@ -30,6 +38,8 @@ impl Plugin for GridPlugin {
                 visualizer::GridVisualizerPlugin,
                 entity::EntityGridPlugin,
                 obstacles::ObstaclesPlugin,
+                seabed::SeabedPlugin,
+                currents::CurrentsPlugin,
                 fog::FogPlugin,
             ));
     }
@@ -4,6 +4,7 @@ use bevy::{
     prelude::*,
     render::render_resource::{AsBindGroup, ShaderRef},
 };
+use std::f32::consts::FRAC_1_SQRT_2;
 /// Plugin for obstacles.
 /// Obstacles are implemented as a hacky force field in the center of each cell they are present in.
 pub struct ObstaclesPlugin;
@@ -18,6 +19,8 @@ impl Plugin for ObstaclesPlugin {
                 );
         }
         app.add_plugins(Grid2Plugin::<Obstacle>::default())
+            .add_event::<ObstacleEditEvent>()
+            .add_event::<ObstacleChangeEvent>()
             .register_type::<ObstaclesSpec>()
             .register_type::<Obstacle>()
             .register_type::<Vec<(RowCol, Obstacle)>>()
@@ -25,7 +28,10 @@ impl Plugin for ObstaclesPlugin {
             .register_type::<RowCol>()
             .add_systems(
                 FixedUpdate,
-                (Grid2::<Obstacle>::bounce_off_obstacles.in_set(FixedUpdateStage::PostPhysics),)
+                (
+                    Grid2::<Obstacle>::apply_edits.in_set(FixedUpdateStage::PostSpawn),
+                    Grid2::<Obstacle>::bounce_off_obstacles.in_set(FixedUpdateStage::PostPhysics),
+                )
                     .in_set(GameStateSet::Running),
             );
     }
@@ -43,6 +49,96 @@ pub enum Obstacle {
     DownLeft = 4,
     Full = 5,
 }
+impl Obstacle {
+    /// Directions to the sides and corners of a cell.
+    pub const DIRECTIONS: [(i16, i16); 8] = [
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+        (0, -1),
+    ];
+
+    /// Returns true if any side or corner that was closed in `previous` is open now.
+    pub fn opens(self, previous: Self) -> bool {
+        Self::DIRECTIONS
+            .iter()
+            .any(|&direction| self.is_open(direction) && !previous.is_open(direction))
+    }
+
+    /// Classify a cell from which of its corners are blocked: [down left, down right, up left, up right].
+    pub fn from_corners(corners: [bool; 4]) -> Self {
+        let [down_left, down_right, up_left, up_right] = corners;
+        match corners.iter().filter(|&&blocked| blocked).count() {
+            0 => Self::Empty,
+            // A single blocked corner cuts off the half of the cell containing it.
+            1 if up_right => Self::UpRight,
+            1 if up_left => Self::UpLeft,
+            1 if down_right => Self::DownRight,
+            1 => Self::DownLeft,
+            // Three blocked corners leave only the half containing the open corner.
+            3 if !down_left => Self::UpRight,
+            3 if !down_right => Self::UpLeft,
+            3 if !up_left => Self::DownRight,
+            3 => Self::DownLeft,
+            _ => Self::Full,
+        }
+    }
+
+    /// Returns the (row, col) direction of the corner that a diagonal obstacle blocks.
+    pub fn blocked_corner(self) -> Option<(i16, i16)> {
+        match self {
+            Self::UpRight => Some((1, 1)),
+            Self::UpLeft => Some((1, -1)),
+            Self::DownRight => Some((-1, 1)),
+            Self::DownLeft => Some((-1, -1)),
+            Self::Empty | Self::Full => None,
+        }
+    }
+
+    /// Normal of the diagonal face, pointing into the free half of the cell.
+    pub fn normal(self) -> Option<Vec2> {
+        let (row, col) = self.blocked_corner()?;
+        Some(-Vec2::new(col as f32, row as f32).normalize())
+    }
+
+    /// Returns true if the position within the cell (in [0, 1]) is blocked.
+    /// Matches the triangles drawn by `shaders/obstacles.wgsl`.
+    pub fn contains(self, local: Vec2) -> bool {
+        match self {
+            Self::Empty => false,
+            Self::Full => true,
+            _ => self.normal().unwrap().dot(local - 0.5) < 0.,
+        }
+    }
+
+    /// Returns true if the side or corner of the cell in the given (row, col) direction is passable.
+    pub fn is_open(self, direction: (i16, i16)) -> bool {
+        match self {
+            Self::Empty => true,
+            Self::Full => false,
+            _ => {
+                let (row, col) = self.blocked_corner().unwrap();
+                direction.0 != row && direction.1 != col
+            }
+        }
+    }
+}
+
+/// Request to change obstacles at runtime, e.g. from scripts.
+#[derive(Event, Debug, Clone, Default)]
+pub struct ObstacleEditEvent {
+    pub edits: Vec<(RowCol, Obstacle)>,
+}
+
+/// Sent when obstacles change at runtime, with the previous obstacle of each changed cell.
+#[derive(Event, Debug, Clone, Default)]
+pub struct ObstacleChangeEvent {
+    pub changes: Vec<(RowCol, Obstacle)>,
+}
 
 /// Grid of obstacle data.
 #[derive(Resource, Default, Deref, DerefMut, Reflect)]
@@ -50,10 +146,64 @@ pub enum Obstacle {
 pub struct ObstaclesSpec(pub Vec<(RowCol, Obstacle)>);
 
 impl Grid2<Obstacle> {
+    /// Change the obstacle in a cell, returning the previous obstacle if it changed.
+    pub fn replace(&mut self, rowcol: RowCol, obstacle: Obstacle) -> Option<Obstacle> {
+        let previous = std::mem::replace(self.get_mut(rowcol)?, obstacle);
+        (previous != obstacle).then_some(previous)
+    }
+
+    /// Apply requested edits and report the cells that changed so navigation and fog can update.
+    pub fn apply_edits(
+        mut grid: ResMut<Self>,
+        mut edit_events: EventReader<ObstacleEditEvent>,
+        mut change_events: EventWriter<ObstacleChangeEvent>,
+    ) {
+        let mut changes = Vec::new();
+        for event in edit_events.read() {
+            for &(rowcol, obstacle) in &event.edits {
+                // The boundary is always blocked, so edits there would be ignored by navigation.
+                if grid.is_boundary(rowcol) {
+                    continue;
+                }
+                if let Some(previous) = grid.replace(rowcol, obstacle) {
+                    changes.push((rowcol, previous));
+                }
+            }
+        }
+        if !changes.is_empty() {
+            change_events.send(ObstacleChangeEvent { changes });
+        }
+    }
+
     pub fn is_clear(&self, rowcol: RowCol) -> bool {
         self[rowcol] == Obstacle::Empty && !self.is_boundary(rowcol)
     }
 
+    /// Returns true if the world position lies within the blocked part of its cell.
+    pub fn is_blocked(&self, position: Vec2) -> bool {
+        let Some(rowcol) = self.to_rowcol(position) else {
+            return false;
+        };
+        self.is_boundary(rowcol) || self[rowcol].contains(self.to_uv(position).fract())
+    }
+
+    /// Returns true if an object can move between two neighboring cells.
+    /// Diagonal moves also require the corner shared with both orthogonal cells to be open.
+    pub fn can_traverse(&self, from: RowCol, to: RowCol) -> bool {
+        if !self.in_bounds(from) || !self.in_bounds(to) {
+            return false;
+        }
+        let (dr, dc) = (to.0 as i16 - from.0 as i16, to.1 as i16 - from.1 as i16);
+        if !self[from].is_open((dr, dc)) || !self[to].is_open((-dr, -dc)) {
+            return false;
+        }
+        if dr != 0 && dc != 0 {
+            return self[(to.0, from.1)].is_open((-dr, dc))
+                && self[(from.0, to.1)].is_open((dr, -dc));
+        }
+        true
+    }
+
     fn obstacle_force(
         &self,
         position: Vec2,
@@ -64,7 +214,8 @@ impl Grid2<Obstacle> {
         let mut force = Force::ZERO;
         let obstacle_position = self.to_world_position(rowcol);
 
-        if !self.is_clear(rowcol) {
+        // Only push away along the axis if the side facing us is blocked.
+        if self.is_boundary(rowcol) || !self[rowcol].is_open((-direction.0, -direction.1)) {
             //   W
             // ┏---┓
             // ┏━━━┳━━━┓
@@ -87,9 +238,28 @@ impl Grid2<Obstacle> {
         force
     }
 
+    /// Compute force away from the diagonal face of a half-blocked cell.
+    fn diagonal_force(&self, position: Vec2, velocity: Velocity, rowcol: RowCol) -> Force {
+        let Some(normal) = self[rowcol].normal() else {
+            return Force::ZERO;
+        };
+        // The diagonal face passes through the cell center.
+        let delta = position - self.to_world_position(rowcol);
+        // Skip positions beyond either end of the face.
+        if delta.dot(normal.perp()).abs() > FRAC_1_SQRT_2 * self.spec.width {
+            return Force::ZERO;
+        }
+        let max_d = self.spec.width;
+        let d = delta.dot(normal); // Signed distance from the face, positive on the free side.
+        let magnitude = ((max_d - d) / max_d).clamp(0., 1.);
+        let directional_adjustment = 1.0 - velocity.0.dot(normal).clamp(-0.5, 0.);
+        Force(magnitude * directional_adjustment * normal)
+    }
+
     /// Compute force due to neighboring obstacles.
     /// For each neighboring obstacle, if the object is moving towards the obstacle
     /// we apply a force away from the obstacle.
+    /// Diagonal obstacles push along the normal of their diagonal face.
     pub fn force(&self, position: Vec2, velocity: Velocity) -> Force {
         let mut force = Force::ZERO;
         if let Some((row, col)) = self.to_rowcol(position) {
@@ -100,6 +270,10 @@ impl Grid2<Obstacle> {
                 let obstacle_rowcol = ((row as i16 + dr) as u16, (col as i16 + dc) as u16);
                 force += self.obstacle_force(position, velocity, obstacle_rowcol, (dr, dc));
             }
+            force += self.diagonal_force(position, velocity, (row, col));
+            for (obstacle_rowcol, _) in self.neighbors8((row, col)) {
+                force += self.diagonal_force(position, velocity, obstacle_rowcol);
+            }
         }
         force
     }
@@ -112,12 +286,21 @@ impl Grid2<Obstacle> {
         for (mut position, mut velocity, mut force) in query.iter_mut() {
             let obstacle_force = obstacles.force(position.0, *velocity) * 10.;
             *force += obstacle_force;
-            if let Some(rowcol) = obstacles.to_rowcol(position.0) {
-                if !obstacles.is_clear(rowcol) {
-                    velocity.0 *= -1.0;
-                    position.0 += velocity.0;
+            if !obstacles.is_blocked(position.0) {
+                continue;
+            }
+            let Some(rowcol) = obstacles.to_rowcol(position.0) else {
+                continue;
+            };
+            match obstacles[rowcol].normal() {
+                // Reflect off the diagonal face.
+                Some(normal) if !obstacles.is_boundary(rowcol) => {
+                    let approach = velocity.0.dot(normal).min(0.);
+                    velocity.0 -= 2. * approach * normal;
                 }
+                _ => velocity.0 *= -1.0,
             }
+            position.0 += velocity.0;
         }
     }
 }
@@ -183,3 +366,36 @@ impl Material for ObstaclesShaderMaterial {
         AlphaMode::Blend
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_diagonal_obstacles() {
+        // Only the up right corner is open.
+        let obstacle = Obstacle::from_corners([true, true, true, false]);
+        assert_eq!(obstacle, Obstacle::DownLeft);
+        assert!(obstacle.contains(Vec2::new(0.1, 0.1)));
+        assert!(!obstacle.contains(Vec2::new(0.9, 0.9)));
+        assert!(obstacle.is_open((1, 1)));
+        assert!(!obstacle.is_open((0, -1)));
+
+        let mut grid = Grid2::<Obstacle> {
+            spec: GridSpec {
+                rows: 10,
+                cols: 10,
+                width: 10.0,
+                visualize: false,
+                visualize_navigation: false,
+            },
+            ..Default::default()
+        };
+        grid.resize();
+        grid[(5, 5)] = Obstacle::UpRight;
+        assert!(grid.can_traverse((4, 4), (5, 5)));
+        assert!(!grid.can_traverse((6, 6), (5, 5)));
+        assert!(!grid.can_traverse((5, 4), (6, 5)));
+        assert!(grid.can_traverse((4, 5), (5, 4)));
+    }
+}
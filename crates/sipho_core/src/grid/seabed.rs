@@ -0,0 +1,156 @@
+use crate::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+
+/// Drag applied against velocity while in kelp.
+const KELP_DRAG: f32 = 0.2;
+
+/// Plugin for terrain types on the seabed.
+pub struct SeabedPlugin;
+impl Plugin for SeabedPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            Grid2Plugin::<Seabed>::default(),
+            ShaderPlanePlugin::<SeabedShaderMaterial>::default(),
+        ))
+        .init_resource::<SeabedSpec>()
+        .register_type::<SeabedSpec>()
+        .register_type::<SeabedPatch>()
+        .register_type::<Vec<SeabedPatch>>()
+        .register_type::<Seabed>()
+        .add_systems(OnExit(GameState::Loading), SeabedSpec::apply)
+        .add_systems(Update, SeabedShaderMaterial::update);
+    }
+}
+
+/// Terrain type of a cell, affecting navigation costs and movement.
+#[derive(Default, Reflect, Debug, Clone, Copy, PartialEq)]
+pub enum Seabed {
+    /// Open seabed with no effect.
+    #[default]
+    Sand,
+    /// Slows objects moving through it.
+    Kelp,
+    /// Damages objects resting on it.
+    Vent,
+    /// Current channel flowing in the given direction. Strength is the length of the vector, up to 1.
+    /// Channels are baked into `CurrentField`, which applies their force and navigation cost.
+    Current(Vec2),
+}
+impl Seabed {
+    /// Id used by `shaders/seabed.wgsl`.
+    pub fn shader_id(self) -> u32 {
+        match self {
+            Self::Sand => 0,
+            Self::Kelp => 1,
+            Self::Vent => 2,
+            Self::Current(_) => 3,
+        }
+    }
+
+    /// Direction and strength of drift.
+    pub fn drift(self) -> Vec2 {
+        match self {
+            Self::Current(drift) => drift.clamp_length_max(1.),
+            _ => Vec2::ZERO,
+        }
+    }
+}
+
+/// Rectangle of cells with the same terrain.
+#[derive(Default, Reflect, Debug, Clone)]
+pub struct SeabedPatch {
+    /// Inclusive (row, col) corners.
+    pub min: RowCol,
+    pub max: RowCol,
+    pub seabed: Seabed,
+}
+
+/// Seabed patches loaded from the config scene.
+#[derive(Resource, Default, Deref, DerefMut, Reflect)]
+#[reflect(Resource)]
+pub struct SeabedSpec(pub Vec<SeabedPatch>);
+impl SeabedSpec {
+    /// Fill the seabed grid once loading is done.
+    pub fn apply(spec: Res<Self>, mut seabed: ResMut<Grid2<Seabed>>) {
+        for patch in spec.iter() {
+            for row in patch.min.0..=patch.max.0.min(seabed.rows.saturating_sub(1)) {
+                for col in patch.min.1..=patch.max.1.min(seabed.cols.saturating_sub(1)) {
+                    seabed[(row, col)] = patch.seabed;
+                }
+            }
+        }
+    }
+}
+
+impl Grid2<Seabed> {
+    /// Factor for the cost of moving from a cell to a neighboring cell.
+    /// Slow and damaging terrain cost more. Currents are weighed by `CurrentField`.
+    pub fn weight(&self, from: RowCol, _to: RowCol) -> f32 {
+        match self.get(from) {
+            Some(Seabed::Kelp) => 2.,
+            Some(Seabed::Vent) => 3.,
+            Some(Seabed::Sand | Seabed::Current(_)) | None => 1.,
+        }
+    }
+
+    /// Force from the terrain under an object.
+    pub fn force(&self, position: Vec2, velocity: Velocity) -> Force {
+        let Some(rowcol) = self.to_rowcol(position) else {
+            return Force::ZERO;
+        };
+        match self[rowcol] {
+            Seabed::Kelp => Force(-velocity.0 * KELP_DRAG),
+            Seabed::Sand | Seabed::Vent | Seabed::Current(_) => Force::ZERO,
+        }
+    }
+}
+
+/// Parameters passed to the seabed shader.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Default)]
+pub struct SeabedShaderMaterial {
+    #[uniform(0)]
+    size: GridSize,
+    #[storage(1, read_only)]
+    pub grid: Vec<u32>,
+    #[storage(2, read_only)]
+    pub drift: Vec<Vec2>,
+}
+impl ShaderPlaneMaterial for SeabedShaderMaterial {
+    fn translation(_spec: &GridSpec) -> Vec3 {
+        Vec2::ZERO.extend(zindex::SEABED)
+    }
+
+    fn resize(&mut self, spec: &GridSpec) {
+        self.size.width = spec.width;
+        self.size.rows = spec.rows.into();
+        self.size.cols = spec.cols.into();
+        let num_cells = spec.rows as usize * spec.cols as usize;
+        self.grid.resize(num_cells, Seabed::Sand.shader_id());
+        self.drift.resize(num_cells, Vec2::ZERO);
+    }
+}
+impl SeabedShaderMaterial {
+    /// Update the shader when the seabed changes.
+    pub fn update(
+        grid: Res<Grid2<Seabed>>,
+        assets: Res<ShaderPlaneAssets<Self>>,
+        mut shader_assets: ResMut<Assets<Self>>,
+    ) {
+        if !grid.is_changed() {
+            return;
+        }
+        let material = shader_assets.get_mut(&assets.shader_material).unwrap();
+        for (i, seabed) in grid.cells.iter().enumerate().take(material.grid.len()) {
+            material.grid[i] = seabed.shader_id();
+            material.drift[i] = seabed.drift();
+        }
+    }
+}
+impl Material for SeabedShaderMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/seabed.wgsl".into()
+    }
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+}
@@ -0,0 +1,179 @@
+//! Line of sight for fog of war, computed by recursive shadowcasting against `Grid2<Obstacle>`.
+
+use bevy::utils::HashMap;
+
+use crate::prelude::*;
+
+/// Transforms from octant space (depth, offset) to grid space (row, col) for each of the 8 octants.
+const OCTANTS: [(i32, i32, i32, i32); 8] = [
+    (1, 0, 0, 1),
+    (0, 1, 1, 0),
+    (0, -1, 1, 0),
+    (-1, 0, 0, 1),
+    (-1, 0, 0, -1),
+    (0, -1, -1, 0),
+    (0, 1, -1, 0),
+    (1, 0, 0, -1),
+];
+
+/// Source cell and radius of a sightline.
+pub type SightKey = (RowCol, u16);
+
+/// Cells visible from a source cell, shared by every unit looking from it.
+#[derive(Default, Debug, Clone)]
+pub struct Sightline {
+    /// Number of units of each team looking from the source cell.
+    pub viewers: [u32; Team::COUNT],
+    /// Visible cells, excluding the grid boundary.
+    pub cells: Vec<RowCol>,
+}
+impl Sightline {
+    pub fn is_empty(&self) -> bool {
+        self.viewers.iter().all(|&count| count == 0)
+    }
+}
+
+/// Sightlines currently in use.
+/// Visibility is removed with the same cells it was added with, so reference counts stay balanced
+/// even when obstacles change between a unit arriving and leaving.
+#[derive(Resource, Default, Debug, Deref, DerefMut)]
+pub struct Sightlines(pub HashMap<SightKey, Sightline>);
+impl Sightlines {
+    /// Keys of sightlines that may see any of the given cells.
+    pub fn affected_by(&self, changes: &[(RowCol, Obstacle)]) -> Vec<SightKey> {
+        self.keys()
+            .filter(|&&((row, col), radius)| {
+                changes.iter().any(|&((other_row, other_col), _)| {
+                    row.abs_diff(other_row) <= radius && col.abs_diff(other_col) <= radius
+                })
+            })
+            .copied()
+            .collect()
+    }
+}
+
+/// Sightline each entity with vision is currently looking along.
+#[derive(Resource, Default, Debug, Deref, DerefMut)]
+pub struct Viewers(pub HashMap<Entity, SightKey>);
+
+impl Grid2<Obstacle> {
+    /// Returns true if the cell blocks line of sight.
+    pub fn is_opaque(&self, rowcol: RowCol) -> bool {
+        self.get(rowcol) != Some(&Obstacle::Empty)
+    }
+
+    /// Cells within the radius that can be seen from the source cell.
+    /// Opaque cells are visible but hide everything behind them.
+    pub fn line_of_sight(&self, source: RowCol, radius: u16) -> Vec<RowCol> {
+        let mut cells = Vec::new();
+        if !self.in_bounds(source) {
+            return cells;
+        }
+        cells.push(source);
+        for octant in OCTANTS {
+            self.cast_light(source, radius, 1, 1.0, 0.0, octant, &mut cells);
+        }
+        // Cells on the edges between octants are visited twice.
+        cells.sort_unstable();
+        cells.dedup();
+        cells.retain(|&rowcol| !self.is_boundary(rowcol));
+        cells
+    }
+
+    /// Scan one octant row by row, between the start and end slopes.
+    /// Recurses whenever an opaque cell splits the visible area.
+    #[allow(clippy::too_many_arguments)]
+    fn cast_light(
+        &self,
+        source: RowCol,
+        radius: u16,
+        depth: i32,
+        mut start: f32,
+        end: f32,
+        (xx, xy, yx, yy): (i32, i32, i32, i32),
+        cells: &mut Vec<RowCol>,
+    ) {
+        if start < end {
+            return;
+        }
+        let radius = radius as i32;
+        let mut new_start = 0.0;
+        for j in depth..=radius {
+            let dy = -j;
+            let mut blocked = false;
+            for dx in -j..=0 {
+                let left_slope = (dx as f32 - 0.5) / (dy as f32 + 0.5);
+                let right_slope = (dx as f32 + 0.5) / (dy as f32 - 0.5);
+                if start < right_slope {
+                    continue;
+                }
+                if end > left_slope {
+                    break;
+                }
+                let row = source.0 as i32 + dx * yx + dy * yy;
+                let col = source.1 as i32 + dx * xx + dy * xy;
+                let rowcol = (row as u16, col as u16);
+                let in_bounds = row >= 0 && col >= 0 && self.in_bounds(rowcol);
+                if in_bounds && dx * dx + dy * dy < radius * radius {
+                    cells.push(rowcol);
+                }
+                let opaque = !in_bounds || self.is_opaque(rowcol);
+                if blocked {
+                    if opaque {
+                        new_start = right_slope;
+                    } else {
+                        blocked = false;
+                        start = new_start;
+                    }
+                } else if opaque && j < radius {
+                    blocked = true;
+                    self.cast_light(
+                        source,
+                        radius as u16,
+                        j + 1,
+                        start,
+                        left_slope,
+                        (xx, xy, yx, yy),
+                        cells,
+                    );
+                    new_start = right_slope;
+                }
+            }
+            if blocked {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walls_block_line_of_sight() {
+        let mut obstacles = Grid2::<Obstacle> {
+            spec: GridSpec {
+                rows: 32,
+                cols: 32,
+                ..default()
+            },
+            ..default()
+        };
+        obstacles.resize();
+        for row in 10..22 {
+            obstacles[(row, 18)] = Obstacle::Full;
+        }
+        let cells = obstacles.line_of_sight((16, 16), 8);
+
+        assert!(cells.contains(&(16, 16)));
+        // The wall itself is seen, but not what is behind it.
+        assert!(cells.contains(&(16, 18)));
+        assert!(!cells.contains(&(16, 20)));
+        // Open directions see the full radius, matching the discrete radius.
+        assert!(cells.contains(&(16, 9)));
+        assert!(!cells.contains(&(16, 8)));
+        let open = obstacles.get_in_radius_discrete((16, 16), 8);
+        assert!(cells.iter().all(|rowcol| open.contains(rowcol)));
+    }
+}
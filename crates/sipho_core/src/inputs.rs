@@ -46,6 +46,13 @@ pub enum InputAction {
     SpawnBlue,
     Fuse,
     PauseMenu,
+    JumpToAlert,
+
+    // Observer controls
+    ViewBlue,
+    ViewRed,
+    ViewAll,
+    FollowBattle,
 
     // Control groups
     Control1,
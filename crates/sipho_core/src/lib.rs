@@ -9,8 +9,10 @@ pub mod game_state;
 pub mod grid;
 pub mod inputs;
 pub mod nav;
+pub mod object;
 pub mod pool;
 pub mod raycast;
+pub mod rng;
 pub mod shader_plane;
 pub mod smallset;
 pub mod system_sets;
@@ -29,14 +31,21 @@ pub mod prelude {
         error::Error,
         game_state::{AssetLoadState, DebugState, GameState},
         grid::{
-            EntityGridEvent, EntitySet, FogEntity, Grid2, Grid2Plugin, GridEntity, GridSize,
-            GridSpec, Obstacle, RowCol, RowColDistance, SparseGrid2, TeamEntitySets,
-            VisibilityUpdate, VisibilityUpdateEvent,
+            CurrentField, CurrentsSpec, Detectors, EntityGridEvent, EntitySet, FogEntity, Grid2,
+            Grid2Plugin, GridEntity, GridSize, GridSpec, Obstacle, ObstacleChangeEvent,
+            ObstacleEditEvent, RowCol, RowColDistance, Seabed, SeabedSpec, SightKey, Sightline,
+            Sightlines, SparseGrid2, Stealth, TeamDetection, TeamEntitySets, Viewers,
+            VisibilityUpdate, VisibilityUpdateEvent, Vision,
         },
         inputs::{InputAction, InputEvent},
-        nav::{NavigationCostEvent, NavigationGrid2, SparseFlowGrid2},
+        nav::{
+            ClearanceMap, NavigationCostEvent, NavigationGraph, NavigationGrid2, NavigationKey,
+            SparseFlowGrid2,
+        },
+        object::Object,
         pool::EntityPool,
         raycast::{GridRaycastTarget, RaycastCommands, RaycastEvent, RaycastTarget},
+        rng::GameRng,
         shader_plane::{ShaderPlaneAssets, ShaderPlaneMaterial, ShaderPlanePlugin},
         smallset::SmallSet,
         system_sets::{FixedUpdateStage, GameStateSet},
@@ -64,6 +73,8 @@ impl Plugin for CorePlugin {
             controls::ControlActionPlugin,
             grid::GridPlugin,
             nav::NavigationPlugin,
+            object::ObjectTypePlugin,
+            rng::RngPlugin,
             bevy_newtonian2d::PhysicsPlugin,
             cursor::CursorPlugin,
             camera::CameraPlugin,
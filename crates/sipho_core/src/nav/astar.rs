@@ -3,17 +3,19 @@ use std::{
     collections::{BTreeSet, BinaryHeap},
 };
 
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, HashSet};
 
 use crate::prelude::*;
 
+use super::NavigationGraph;
+
 /// State for running A* search to fill out flow cost grid.
 /// See https://doc.rust-lang.org/std/collections/binary_heap/index.html#examples
 #[derive(Copy, Clone, PartialEq)]
 pub struct AStarState {
-    rowcol: RowCol,
-    cost: f32,
-    heuristic: f32,
+    pub rowcol: RowCol,
+    pub cost: f32,
+    pub heuristic: f32,
 }
 impl AStarState {
     // Priority scoring function f.
@@ -75,6 +77,18 @@ impl AStarRunner {
         source: RowCol,
         grid: &SparseFlowGrid2,
         obstacles: &Grid2<Obstacle>,
+    ) {
+        self.a_star_from_source_in(source, grid, obstacles, |_, _| Some(1.));
+    }
+
+    /// Runs A star from the given source to the destination.
+    /// `weight` scales the cost of stepping from one cell into the next, or returns None if the step isn't allowed.
+    pub fn a_star_from_source_in(
+        &mut self,
+        source: RowCol,
+        grid: &SparseFlowGrid2,
+        obstacles: &Grid2<Obstacle>,
+        weight: impl Fn(RowCol, RowCol) -> Option<f32>,
     ) {
         // We're at `start`, with a zero cost
         if grid.is_boundary(self.destination) || !grid.in_bounds(self.destination) {
@@ -110,12 +124,16 @@ impl AStarRunner {
                 if grid.is_boundary(neighbor_rowcol) || !grid.in_bounds(neighbor_rowcol) {
                     continue;
                 }
-                if obstacles[neighbor_rowcol] != Obstacle::Empty {
+                if !obstacles.can_traverse(neighbor_rowcol, rowcol) {
                     continue;
                 }
+                // The search runs from the destination, so units step from the neighbor into this cell.
+                let Some(weight) = weight(neighbor_rowcol, rowcol) else {
+                    continue;
+                };
 
                 self.heap.push(AStarState {
-                    cost: cost + neighbor_cost,
+                    cost: cost + neighbor_cost * weight,
                     rowcol: neighbor_rowcol,
                     heuristic: heuristic_factor * neighbor_rowcol.distance8(source),
                 });
@@ -123,29 +141,113 @@ impl AStarRunner {
         }
     }
 
-    /// Run A* search from destination to reach all sources.
-    pub fn a_star(
-        &self,
+    /// Sources that can be navigated from.
+    fn valid_sources(
         sources: &[RowCol],
-        destination: RowCol,
         grid: &SparseFlowGrid2,
         obstacles: &Grid2<Obstacle>,
-    ) -> HashMap<RowCol, f32> {
-        let sources: BTreeSet<RowCol> = sources
+    ) -> BTreeSet<RowCol> {
+        sources
             .iter()
             .copied()
             .filter(|&rowcol| {
                 !grid.is_boundary(rowcol)
                     && grid.in_bounds(rowcol)
-                    && obstacles[rowcol] == Obstacle::Empty
+                    && obstacles[rowcol] != Obstacle::Full
             })
-            .collect();
+            .collect()
+    }
+
+    /// Run A* search from destination to reach all sources.
+    pub fn a_star(
+        &self,
+        sources: &[RowCol],
+        destination: RowCol,
+        grid: &SparseFlowGrid2,
+        obstacles: &Grid2<Obstacle>,
+    ) -> HashMap<RowCol, f32> {
+        self.a_star_in(sources, destination, grid, obstacles, |_, _| Some(1.))
+    }
+
+    /// Run A* search from destination to reach all sources, with steps weighted as in `a_star_from_source_in`.
+    pub fn a_star_in(
+        &self,
+        sources: &[RowCol],
+        destination: RowCol,
+        grid: &SparseFlowGrid2,
+        obstacles: &Grid2<Obstacle>,
+        weight: impl Fn(RowCol, RowCol) -> Option<f32>,
+    ) -> HashMap<RowCol, f32> {
+        let sources = Self::valid_sources(sources, grid, obstacles);
+        let mut runner = AStarRunner::new(destination);
+        for source in sources {
+            if runner.costs.contains_key(&source) {
+                continue;
+            }
+            runner.a_star_from_source_in(source, grid, obstacles, &weight);
+        }
+        runner.costs
+    }
+
+    /// Run A* search from destination to reach all sources, guided by the cluster graph.
+    /// Cell level search only expands the clusters along the abstract paths,
+    /// falling back to the full grid for sources the corridor doesn't reach.
+    pub fn a_star_hierarchical(
+        &self,
+        sources: &[RowCol],
+        destination: RowCol,
+        grid: &SparseFlowGrid2,
+        obstacles: &Grid2<Obstacle>,
+        graph: &NavigationGraph,
+    ) -> HashMap<RowCol, f32> {
+        self.a_star_hierarchical_in(sources, destination, grid, obstacles, graph, |_, _| {
+            Some(1.)
+        })
+    }
+
+    /// Hierarchical A* with steps weighted as in `a_star_from_source_in`.
+    pub fn a_star_hierarchical_in(
+        &self,
+        sources: &[RowCol],
+        destination: RowCol,
+        grid: &SparseFlowGrid2,
+        obstacles: &Grid2<Obstacle>,
+        graph: &NavigationGraph,
+        weight: impl Fn(RowCol, RowCol) -> Option<f32>,
+    ) -> HashMap<RowCol, f32> {
+        let sources = Self::valid_sources(sources, grid, obstacles);
+        if graph.is_empty() {
+            let sources: Vec<RowCol> = sources.into_iter().collect();
+            return self.a_star_in(&sources, destination, grid, obstacles, weight);
+        }
+
+        let mut corridor = HashSet::new();
+        for &source in &sources {
+            if let Some(path) = graph.find_path(source, destination, obstacles) {
+                corridor.extend(path.into_iter().map(|rowcol| graph.cluster(rowcol)));
+            }
+        }
+
         let mut runner = AStarRunner::new(destination);
+        let mut unreached = Vec::new();
         for source in sources {
             if runner.costs.contains_key(&source) {
                 continue;
             }
-            runner.a_star_from_source(source, grid, obstacles);
+            runner.a_star_from_source_in(source, grid, obstacles, |from, to| {
+                corridor
+                    .contains(&graph.cluster(from))
+                    .then(|| weight(from, to))
+                    .flatten()
+            });
+            if !runner.costs.contains_key(&source) {
+                unreached.push(source);
+            }
+        }
+        if !unreached.is_empty() {
+            for (rowcol, cost) in self.a_star_in(&unreached, destination, grid, obstacles, weight) {
+                runner.costs.entry(rowcol).or_insert(cost);
+            }
         }
         runner.costs
     }
@@ -0,0 +1,161 @@
+//! Clearance maps for navigating units bigger than a cell.
+
+use std::collections::VecDeque;
+
+use bevy::utils::HashSet;
+
+use crate::prelude::*;
+
+/// Clearances are capped so organisms can still squeeze through wide gaps.
+pub const MAX_CLEARANCE: u8 = 4;
+
+/// Distance in cells from each cell to the nearest blocked cell, derived from `Grid2<Obstacle>`.
+/// A unit needing clearance `c` fits in cells with clearance at least `c`, i.e. gaps `2c - 1` cells wide.
+/// Clearance 1 is the default for units that fit in a single cell, which may also use half-blocked cells.
+#[derive(Resource, Default, Clone, Debug, Deref, DerefMut)]
+pub struct ClearanceMap(pub Grid2<u8>);
+impl ClearanceMap {
+    /// Recompute clearances near edited cells, or all of them when obstacles are replaced.
+    pub fn update(
+        mut clearances: ResMut<Self>,
+        obstacles: Res<Grid2<Obstacle>>,
+        mut events: EventReader<ObstacleChangeEvent>,
+    ) {
+        let changed: Vec<RowCol> = events
+            .read()
+            .flat_map(|event| event.changes.iter().map(|&(rowcol, _)| rowcol))
+            .collect();
+        if !changed.is_empty() && clearances.cells.len() == obstacles.cells.len() {
+            clearances.refresh(&obstacles, &changed);
+        } else if obstacles.is_changed() {
+            *clearances = Self::build(&obstacles);
+        }
+    }
+
+    /// Recompute clearances of cells within `MAX_CLEARANCE` of the changed cells.
+    /// Farther cells can't see the change, since clearances are capped.
+    pub fn refresh(&mut self, obstacles: &Grid2<Obstacle>, changed: &[RowCol]) {
+        let reach = MAX_CLEARANCE as u16 - 1;
+        let window = |(row, col): RowCol| {
+            let rows = row.saturating_sub(reach)..=(row + reach).min(obstacles.rows - 1);
+            let cols = col.saturating_sub(reach)..=(col + reach).min(obstacles.cols - 1);
+            rows.flat_map(move |row| cols.clone().map(move |col| (row, col)))
+        };
+        let cells: HashSet<RowCol> = changed.iter().flat_map(|&rowcol| window(rowcol)).collect();
+        for rowcol in cells {
+            self[rowcol] = window(rowcol)
+                .filter(|&cell| Self::is_blocked(obstacles, cell))
+                .map(|cell| rowcol.0.abs_diff(cell.0).max(rowcol.1.abs_diff(cell.1)) as u8)
+                .min()
+                .unwrap_or(MAX_CLEARANCE);
+        }
+    }
+
+    fn is_blocked(obstacles: &Grid2<Obstacle>, rowcol: RowCol) -> bool {
+        obstacles[rowcol] != Obstacle::Empty || obstacles.is_boundary(rowcol)
+    }
+
+    /// Compute clearances by flooding outward from blocked cells.
+    pub fn build(obstacles: &Grid2<Obstacle>) -> Self {
+        let mut map = Grid2::<u8> {
+            spec: obstacles.spec.clone(),
+            cells: vec![MAX_CLEARANCE; obstacles.cells.len()],
+        };
+        if obstacles.cells.is_empty() {
+            return Self(map);
+        }
+        let mut queue = VecDeque::new();
+        for row in 0..obstacles.rows {
+            for col in 0..obstacles.cols {
+                let rowcol = (row, col);
+                if Self::is_blocked(obstacles, rowcol) {
+                    map[rowcol] = 0;
+                    // Cells deeper in the boundary only neighbor other boundary cells.
+                    if !obstacles.is_boundary_n(rowcol, 2) {
+                        queue.push_back(rowcol);
+                    }
+                }
+            }
+        }
+        while let Some(rowcol) = queue.pop_front() {
+            let clearance = map[rowcol] + 1;
+            if clearance >= MAX_CLEARANCE {
+                continue;
+            }
+            for (neighbor, _) in obstacles.neighbors8(rowcol) {
+                if map[neighbor] > clearance {
+                    map[neighbor] = clearance;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        Self(map)
+    }
+
+    /// Clearance needed by a unit with the given radius in world units.
+    pub fn clearance_for(radius: f32, width: f32) -> u8 {
+        // A cell with clearance c has at least (c - 0.5) cells of free space around its center.
+        ((radius / width + 0.5).ceil() as u8).clamp(1, MAX_CLEARANCE)
+    }
+
+    /// Returns true if a unit needing the given clearance fits in the cell.
+    pub fn fits(&self, rowcol: RowCol, clearance: u8) -> bool {
+        clearance <= 1 || self.get(rowcol).is_some_and(|&cell| cell >= clearance)
+    }
+
+    /// Clearance a unit paths with while stuck in a cell narrower than its clearance.
+    /// Returns None once the unit fits again, so it goes back to its full clearance.
+    /// Otherwise keeps the lowest clearance used so far, so the unit follows the same flow until it reaches open space.
+    pub fn relax(&self, clearance: u8, relaxed: Option<u8>, source: RowCol) -> Option<u8> {
+        if self.fits(source, clearance) {
+            return None;
+        }
+        let at = self.get(source).copied().unwrap_or(0).max(1);
+        Some(relaxed.map_or(at, |relaxed| relaxed.min(at)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clearance_narrows_near_walls() {
+        let mut obstacles = Grid2::<Obstacle> {
+            spec: GridSpec {
+                rows: 16,
+                cols: 16,
+                ..default()
+            },
+            ..default()
+        };
+        obstacles.resize();
+        for row in 0..16 {
+            if row != 8 {
+                obstacles[(row, 8)] = Obstacle::Full;
+            }
+        }
+        let clearances = ClearanceMap::build(&obstacles);
+
+        assert_eq!(clearances[(4, 8)], 0);
+        // The one cell gap only fits units needing no extra clearance.
+        assert_eq!(clearances[(8, 8)], 1);
+        assert!(clearances.fits((8, 8), 1));
+        assert!(!clearances.fits((8, 8), 2));
+        assert_eq!(clearances[(8, 5)], 3);
+        // Units in open space keep their clearance, stuck units relax it until they get out.
+        assert_eq!(clearances.relax(3, None, (8, 5)), None);
+        assert_eq!(clearances.relax(3, None, (8, 8)), Some(1));
+        assert_eq!(clearances.relax(3, Some(1), (8, 6)), Some(1));
+        assert_eq!(clearances.relax(3, Some(1), (8, 5)), None);
+        assert_eq!(ClearanceMap::clearance_for(12., 64.), 1);
+        assert_eq!(ClearanceMap::clearance_for(100., 64.), 3);
+
+        // Refreshing around edits matches building from scratch.
+        let mut refreshed = clearances.clone();
+        obstacles[(8, 8)] = Obstacle::Full;
+        obstacles[(4, 8)] = Obstacle::Empty;
+        refreshed.refresh(&obstacles, &[(8, 8), (4, 8)]);
+        assert_eq!(refreshed.cells, ClearanceMap::build(&obstacles).cells);
+    }
+}
@@ -0,0 +1,451 @@
+//! Hierarchical path finding (HPA*).
+//! The grid is split into square clusters connected by portals on their shared borders.
+//! Long paths are first found on the small graph of portals, then refined on cells near that path.
+
+use std::collections::BinaryHeap;
+
+use bevy::utils::{HashMap, HashSet};
+
+use crate::prelude::*;
+
+use super::astar::AStarState;
+
+/// Width of a cluster in cells.
+pub const CLUSTER_SIZE: u16 = 16;
+/// Openings on a cluster border longer than this get a portal at each end instead of the middle.
+const MAX_CENTERED_PORTAL_RUN: u16 = 6;
+
+/// (row, col) of a cluster.
+pub type ClusterId = (u16, u16);
+
+/// Graph of portals between clusters, precomputed from `Grid2<Obstacle>`.
+#[derive(Resource, Clone, Debug)]
+pub struct NavigationGraph {
+    pub cluster_size: u16,
+    /// Portal cells.
+    nodes: Vec<RowCol>,
+    node_indices: HashMap<RowCol, usize>,
+    /// Neighboring nodes and the cost to reach them.
+    edges: Vec<Vec<(usize, f32)>>,
+    cluster_nodes: HashMap<ClusterId, Vec<usize>>,
+}
+impl Default for NavigationGraph {
+    fn default() -> Self {
+        Self {
+            cluster_size: CLUSTER_SIZE,
+            nodes: Vec::new(),
+            node_indices: HashMap::new(),
+            edges: Vec::new(),
+            cluster_nodes: HashMap::new(),
+        }
+    }
+}
+impl NavigationGraph {
+    /// Rebuild clusters near edited cells, or the whole graph when obstacles are replaced.
+    pub fn update(
+        mut graph: ResMut<Self>,
+        obstacles: Res<Grid2<Obstacle>>,
+        mut events: EventReader<ObstacleChangeEvent>,
+    ) {
+        let changed: Vec<RowCol> = events
+            .read()
+            .flat_map(|event| event.changes.iter().map(|&(rowcol, _)| rowcol))
+            .collect();
+        if !changed.is_empty() && !graph.is_empty() {
+            *graph = graph.rebuild(&obstacles, &changed);
+        } else if obstacles.is_changed() {
+            let cluster_size = graph.cluster_size;
+            *graph = Self::build(&obstacles, cluster_size);
+        }
+    }
+
+    /// Build the graph for the given obstacles.
+    pub fn build(obstacles: &Grid2<Obstacle>, cluster_size: u16) -> Self {
+        Self::build_reusing(obstacles, cluster_size, None)
+    }
+
+    /// Build the graph again after the given cells changed.
+    /// Only clusters containing a change and their neighbors, whose shared portals may have moved, are searched again.
+    pub fn rebuild(&self, obstacles: &Grid2<Obstacle>, changed: &[RowCol]) -> Self {
+        let mut dirty = HashSet::new();
+        for &rowcol in changed {
+            let (row, col) = self.cluster(rowcol);
+            dirty.extend([
+                (row, col),
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ]);
+        }
+        Self::build_reusing(obstacles, self.cluster_size, Some((self, &dirty)))
+    }
+
+    /// Build the graph, copying the paths within clean clusters from a previous graph.
+    /// Finding portals only scans cluster borders, so it is redone everywhere.
+    fn build_reusing(
+        obstacles: &Grid2<Obstacle>,
+        cluster_size: u16,
+        previous: Option<(&Self, &HashSet<ClusterId>)>,
+    ) -> Self {
+        let mut graph = Self {
+            cluster_size: cluster_size.max(1),
+            ..default()
+        };
+        if obstacles.cells.is_empty() {
+            return graph;
+        }
+        let size = graph.cluster_size;
+        let cluster_rows = obstacles.rows.div_ceil(size);
+        let cluster_cols = obstacles.cols.div_ceil(size);
+
+        // Portals between horizontally and vertically adjacent clusters.
+        for cluster_row in 0..cluster_rows {
+            for cluster_col in 0..cluster_cols {
+                let (min, max) = graph.cluster_bounds((cluster_row, cluster_col), obstacles);
+                if max.1 + 1 < obstacles.cols {
+                    let border: Vec<(RowCol, RowCol)> = (min.0..=max.0)
+                        .map(|row| ((row, max.1), (row, max.1 + 1)))
+                        .collect();
+                    graph.add_portals(&border, obstacles);
+                }
+                if max.0 + 1 < obstacles.rows {
+                    let border: Vec<(RowCol, RowCol)> = (min.1..=max.1)
+                        .map(|col| ((max.0, col), (max.0 + 1, col)))
+                        .collect();
+                    graph.add_portals(&border, obstacles);
+                }
+            }
+        }
+
+        // Connect the portals within each cluster.
+        let clusters: Vec<(ClusterId, Vec<usize>)> = graph
+            .cluster_nodes
+            .iter()
+            .map(|(&cluster, nodes)| (cluster, nodes.clone()))
+            .collect();
+        for (cluster, nodes) in clusters {
+            let reusable = previous.filter(|(previous, dirty)| {
+                !dirty.contains(&cluster)
+                    && previous.cluster_nodes.get(&cluster).map(Vec::len) == Some(nodes.len())
+                    && nodes
+                        .iter()
+                        .all(|&node| previous.node_indices.contains_key(&graph.nodes[node]))
+            });
+            if let Some((previous, _)) = reusable {
+                for &node in &nodes {
+                    let old = previous.node_indices[&graph.nodes[node]];
+                    for &(other, cost) in &previous.edges[old] {
+                        let other = previous.nodes[other];
+                        if previous.cluster(other) == cluster {
+                            graph.edges[node].push((graph.node_indices[&other], cost));
+                        }
+                    }
+                }
+                continue;
+            }
+            for &node in &nodes {
+                let costs = graph.local_costs(graph.nodes[node], cluster, obstacles);
+                for &other in &nodes {
+                    if other == node {
+                        continue;
+                    }
+                    if let Some(cost) = costs.get(graph.nodes[other]) {
+                        graph.edges[node].push((other, cost));
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    /// True if there are no portals, e.g. before obstacles are loaded.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Number of portal cells.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Cluster containing the cell.
+    pub fn cluster(&self, rowcol: RowCol) -> ClusterId {
+        (rowcol.0 / self.cluster_size, rowcol.1 / self.cluster_size)
+    }
+
+    /// Inclusive (min, max) cells of the cluster.
+    pub fn cluster_bounds(&self, cluster: ClusterId, spec: &GridSpec) -> (RowCol, RowCol) {
+        let min = (cluster.0 * self.cluster_size, cluster.1 * self.cluster_size);
+        let max = (
+            (min.0 + self.cluster_size).min(spec.rows) - 1,
+            (min.1 + self.cluster_size).min(spec.cols) - 1,
+        );
+        (min, max)
+    }
+
+    /// Returns true if objects can stand in the cell.
+    fn is_walkable(obstacles: &Grid2<Obstacle>, rowcol: RowCol) -> bool {
+        obstacles.in_bounds(rowcol)
+            && !obstacles.is_boundary(rowcol)
+            && obstacles[rowcol] != Obstacle::Full
+    }
+
+    fn add_node(&mut self, rowcol: RowCol) -> usize {
+        if let Some(&index) = self.node_indices.get(&rowcol) {
+            return index;
+        }
+        let index = self.nodes.len();
+        self.nodes.push(rowcol);
+        self.edges.push(Vec::new());
+        self.node_indices.insert(rowcol, index);
+        let cluster = self.cluster(rowcol);
+        self.cluster_nodes.entry(cluster).or_default().push(index);
+        index
+    }
+
+    /// Add portals for each opening along a border, given pairs of cells on either side.
+    fn add_portals(&mut self, border: &[(RowCol, RowCol)], obstacles: &Grid2<Obstacle>) {
+        let is_open = |&(a, b): &(RowCol, RowCol)| {
+            Self::is_walkable(obstacles, a)
+                && Self::is_walkable(obstacles, b)
+                && obstacles.can_traverse(a, b)
+        };
+        let mut start = 0;
+        while start < border.len() {
+            if !is_open(&border[start]) {
+                start += 1;
+                continue;
+            }
+            let mut end = start;
+            while end + 1 < border.len() && is_open(&border[end + 1]) {
+                end += 1;
+            }
+            let portals = if (end - start) as u16 + 1 > MAX_CENTERED_PORTAL_RUN {
+                vec![border[start], border[end]]
+            } else {
+                vec![border[(start + end) / 2]]
+            };
+            for (a, b) in portals {
+                let (a, b) = (self.add_node(a), self.add_node(b));
+                self.edges[a].push((b, 1.));
+                self.edges[b].push((a, 1.));
+            }
+            start = end + 1;
+        }
+    }
+
+    /// Costs to reach cells of a cluster from a cell in it, without leaving the cluster.
+    fn local_costs(
+        &self,
+        start: RowCol,
+        cluster: ClusterId,
+        obstacles: &Grid2<Obstacle>,
+    ) -> ClusterCosts {
+        let (min, max) = self.cluster_bounds(cluster, obstacles);
+        let mut costs = ClusterCosts {
+            min,
+            max,
+            costs: vec![f32::INFINITY; self.cluster_size as usize * self.cluster_size as usize],
+            size: self.cluster_size,
+        };
+        let mut finalized = vec![false; costs.costs.len()];
+        let mut heap = BinaryHeap::new();
+        let start_index = costs.index(start);
+        costs.costs[start_index] = 0.;
+        heap.push(AStarState {
+            rowcol: start,
+            cost: 0.,
+            heuristic: 0.,
+        });
+        while let Some(AStarState { rowcol, cost, .. }) = heap.pop() {
+            let index = costs.index(rowcol);
+            if finalized[index] {
+                continue;
+            }
+            finalized[index] = true;
+            for (neighbor, neighbor_cost) in obstacles.neighbors8(rowcol) {
+                if !costs.contains(neighbor)
+                    || !Self::is_walkable(obstacles, neighbor)
+                    || !obstacles.can_traverse(rowcol, neighbor)
+                {
+                    continue;
+                }
+                let neighbor_index = costs.index(neighbor);
+                let neighbor_cost = cost + neighbor_cost;
+                if neighbor_cost >= costs.costs[neighbor_index] {
+                    continue;
+                }
+                costs.costs[neighbor_index] = neighbor_cost;
+                heap.push(AStarState {
+                    rowcol: neighbor,
+                    cost: neighbor_cost,
+                    heuristic: 0.,
+                });
+            }
+        }
+        costs
+    }
+
+    /// Find waypoints from source to destination through the portals.
+    /// Consecutive waypoints are connected within a single cluster.
+    pub fn find_path(
+        &self,
+        source: RowCol,
+        destination: RowCol,
+        obstacles: &Grid2<Obstacle>,
+    ) -> Option<Vec<RowCol>> {
+        if !Self::is_walkable(obstacles, source) || !Self::is_walkable(obstacles, destination) {
+            return None;
+        }
+        let source_cluster = self.cluster(source);
+        let destination_cluster = self.cluster(destination);
+        let source_costs = self.local_costs(source, source_cluster, obstacles);
+        if source_cluster == destination_cluster && source_costs.get(destination).is_some() {
+            return Some(vec![source, destination]);
+        }
+        let destination_costs = self.local_costs(destination, destination_cluster, obstacles);
+
+        // A* over portals, treating the source and destination as extra nodes.
+        let mut search = PortalSearch {
+            destination,
+            ..default()
+        };
+        for &node in self
+            .cluster_nodes
+            .get(&source_cluster)
+            .into_iter()
+            .flatten()
+        {
+            if let Some(cost) = source_costs.get(self.nodes[node]) {
+                search.push(self.nodes[node], cost, source);
+            }
+        }
+        let mut finalized = HashSet::new();
+        while let Some(AStarState { rowcol, cost, .. }) = search.heap.pop() {
+            if rowcol == destination {
+                let mut path = vec![destination];
+                let mut current = destination;
+                while let Some(&parent) = search.parents.get(&current) {
+                    path.push(parent);
+                    current = parent;
+                }
+                if current != source {
+                    path.push(source);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if !finalized.insert(rowcol) {
+                continue;
+            }
+            if self.cluster(rowcol) == destination_cluster {
+                if let Some(remaining) = destination_costs.get(rowcol) {
+                    search.push(destination, cost + remaining, rowcol);
+                }
+            }
+            for &(neighbor, edge_cost) in &self.edges[self.node_indices[&rowcol]] {
+                search.push(self.nodes[neighbor], cost + edge_cost, rowcol);
+            }
+        }
+        None
+    }
+}
+
+/// Costs of reaching cells within one cluster.
+struct ClusterCosts {
+    min: RowCol,
+    max: RowCol,
+    size: u16,
+    costs: Vec<f32>,
+}
+impl ClusterCosts {
+    fn contains(&self, (row, col): RowCol) -> bool {
+        (self.min.0..=self.max.0).contains(&row) && (self.min.1..=self.max.1).contains(&col)
+    }
+
+    fn index(&self, (row, col): RowCol) -> usize {
+        (row - self.min.0) as usize * self.size as usize + (col - self.min.1) as usize
+    }
+
+    /// Cost to reach the cell, if it is reachable within the cluster.
+    fn get(&self, rowcol: RowCol) -> Option<f32> {
+        if !self.contains(rowcol) {
+            return None;
+        }
+        Some(self.costs[self.index(rowcol)]).filter(|cost| cost.is_finite())
+    }
+}
+
+/// Frontier of an A* search over portals.
+#[derive(Default)]
+struct PortalSearch {
+    destination: RowCol,
+    best: HashMap<RowCol, f32>,
+    parents: HashMap<RowCol, RowCol>,
+    heap: BinaryHeap<AStarState>,
+}
+impl PortalSearch {
+    /// Queue a cell if this is the cheapest way found to reach it.
+    fn push(&mut self, rowcol: RowCol, cost: f32, parent: RowCol) {
+        if self.best.get(&rowcol).is_some_and(|&best| best <= cost) {
+            return;
+        }
+        self.best.insert(rowcol, cost);
+        if parent != rowcol {
+            self.parents.insert(rowcol, parent);
+        }
+        self.heap.push(AStarState {
+            rowcol,
+            cost,
+            heuristic: rowcol.distance8(self.destination),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::NavigationGraph;
+
+    #[test]
+    fn test_find_path_through_wall_gap() {
+        let mut obstacles = Grid2::<Obstacle> {
+            spec: GridSpec {
+                rows: 64,
+                cols: 64,
+                width: 10.0,
+                visualize: false,
+                visualize_navigation: false,
+            },
+            ..Default::default()
+        };
+        obstacles.resize();
+        // Vertical wall with a single gap near the top.
+        for row in 0..64 {
+            if row != 50 {
+                obstacles[(row, 32)] = Obstacle::Full;
+            }
+        }
+        let graph = NavigationGraph::build(&obstacles, 16);
+        assert!(!graph.is_empty());
+
+        let path = graph.find_path((10, 10), (10, 54), &obstacles).unwrap();
+        assert_eq!(path.first(), Some(&(10, 10)));
+        assert_eq!(path.last(), Some(&(10, 54)));
+        assert!(path
+            .iter()
+            .any(|&(row, col)| row == 50 && col.abs_diff(32) <= 1));
+
+        // Rebuilding the clusters around an edit matches building from scratch.
+        obstacles[(50, 32)] = Obstacle::Full;
+        let rebuilt = graph.rebuild(&obstacles, &[(50, 32)]);
+        let graph = NavigationGraph::build(&obstacles, 16);
+        assert!(graph.find_path((10, 10), (10, 54), &obstacles).is_none());
+        assert!(rebuilt.find_path((10, 10), (10, 54), &obstacles).is_none());
+        assert_eq!(rebuilt.len(), graph.len());
+        let path = rebuilt.find_path((10, 10), (60, 10), &obstacles);
+        assert_eq!(path, graph.find_path((10, 10), (60, 10), &obstacles));
+    }
+}
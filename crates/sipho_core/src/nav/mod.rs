@@ -1,11 +1,16 @@
 /// Sparse grid flow for path finding.
 use crate::prelude::*;
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, HashSet};
 
 pub mod astar;
+pub mod clearance;
 pub mod debug;
+pub mod hpa;
 
-pub use {astar::AStarRunner, debug::NavigationVisualizerPlugin};
+pub use {
+    astar::AStarRunner, clearance::ClearanceMap, debug::NavigationVisualizerPlugin,
+    hpa::NavigationGraph,
+};
 
 /// Plugin for flow-based navigation.
 pub struct NavigationPlugin;
@@ -13,7 +18,17 @@ impl Plugin for NavigationPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<NavigationCostEvent>()
             .insert_resource(NavigationGrid2::default())
-            .add_systems(FixedUpdate, (NavigationGrid2::resize_on_change,))
+            .init_resource::<NavigationGraph>()
+            .init_resource::<ClearanceMap>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    NavigationGrid2::resize_on_change,
+                    NavigationGraph::update.in_set(FixedUpdateStage::FindNeighbors),
+                    ClearanceMap::update.in_set(FixedUpdateStage::FindNeighbors),
+                    NavigationGrid2::invalidate.in_set(FixedUpdateStage::FindNeighbors),
+                ),
+            )
             .add_plugins(NavigationVisualizerPlugin);
     }
 }
@@ -73,12 +88,15 @@ pub struct NavigationGrid2Entry {
 }
 impl NavigationGrid2Entry {
     /// Add a waypoint given rowcols.
+    /// `weight` scales step costs, see `AStarRunner::a_star_from_source_in`.
     pub fn compute_flow(
         &mut self,
         destination: RowCol,
         sources: &[RowCol],
         obstacles: &Grid2<Obstacle>,
+        graph: &NavigationGraph,
         event_writer: &mut EventWriter<NavigationCostEvent>,
+        weight: impl Fn(RowCol, RowCol) -> Option<f32>,
     ) {
         // TODO: consider if we should also add neighboring cells for each source.
         // let mut expanded_sources: Vec<RowCol> = Vec::with_capacity(sources.len());
@@ -88,28 +106,23 @@ impl NavigationGrid2Entry {
         //     }
         // }
 
-        let costs = self
-            .a_star_runner
-            .a_star(sources, destination, &self.grid, obstacles);
+        let costs = self.a_star_runner.a_star_hierarchical_in(
+            sources,
+            destination,
+            &self.grid,
+            obstacles,
+            graph,
+            weight,
+        );
 
         // Compute flow direction.
         for (&rowcol, &cost) in &costs {
             let mut min_neighbor_rowcol = rowcol;
             let mut min_neighbor_cost = cost;
             for (neighbor_rowcol, _) in self.grid.neighbors8(rowcol) {
-                // Cornering checks for diagonals.
-                if neighbor_rowcol.0 != rowcol.0
-                    && neighbor_rowcol.1 != rowcol.1
-                    && self.grid.in_bounds(neighbor_rowcol)
-                {
-                    if obstacles[(neighbor_rowcol.0, rowcol.1)] != Obstacle::Empty {
-                        // info!("Corner skip! {:?} -> {:?}", rowcol, neighbor_rowcol);
-                        continue;
-                    }
-                    if obstacles[(rowcol.0, neighbor_rowcol.1)] != Obstacle::Empty {
-                        // info!("Corner skip! {:?} -> {:?}", rowcol, neighbor_rowcol);
-                        continue;
-                    }
+                // Checks half-blocked cells and cornering for diagonals.
+                if !obstacles.can_traverse(rowcol, neighbor_rowcol) {
+                    continue;
                 }
                 if let Some(&neighbor_cost) = costs.get(&neighbor_rowcol) {
                     if neighbor_cost < min_neighbor_cost {
@@ -123,12 +136,111 @@ impl NavigationGrid2Entry {
                 .insert(rowcol, Force(rowcol.signed_delta8(min_neighbor_rowcol)));
             event_writer.send(NavigationCostEvent { rowcol, cost });
         }
+        // Keep costs around to know which flows an obstacle change affects.
+        self.a_star_runner.costs.extend(costs);
+    }
+
+    /// Cell that the flow in this cell leads to.
+    fn next_rowcol(&self, rowcol: RowCol) -> Option<RowCol> {
+        let flow = self.grid.get(rowcol)?;
+        let next = (
+            (rowcol.0 as i32 + flow.y as i32) as u16,
+            (rowcol.1 as i32 + flow.x as i32) as u16,
+        );
+        (next != rowcol).then_some(next)
+    }
+
+    /// Returns true if a change is within the footprint of a unit needing `clearance` in any cell this flow uses.
+    fn near_changes(&self, changes: &[(RowCol, Obstacle)], clearance: u8) -> bool {
+        if clearance <= 1 {
+            return false;
+        }
+        let reach = clearance as u16 - 1;
+        let mut cells = self
+            .grid
+            .cells
+            .keys()
+            .chain(self.a_star_runner.costs.keys());
+        cells.any(|&(row, col)| {
+            changes.iter().any(|&((change_row, change_col), _)| {
+                row.abs_diff(change_row) <= reach && col.abs_diff(change_col) <= reach
+            })
+        })
+    }
+
+    /// Remove flows and costs made stale by obstacle changes.
+    /// Removed cells are recomputed for the sources that still need them.
+    pub fn invalidate(&mut self, changes: &[(RowCol, Obstacle)], obstacles: &Grid2<Obstacle>) {
+        let costs = &self.a_star_runner.costs;
+        let mut stale: HashSet<RowCol> = HashSet::new();
+        // Paths through an opened cell can only improve cells costing more than its neighbors.
+        let mut min_opened_cost = f32::INFINITY;
+        for &(rowcol, previous) in changes {
+            // Flows never reach the edge of the grid.
+            if obstacles.is_boundary_n(rowcol, 2) {
+                continue;
+            }
+            let nearby = obstacles
+                .neighbors8(rowcol)
+                .map(|(neighbor, _)| neighbor)
+                .into_iter()
+                .chain([rowcol]);
+            let opened = obstacles[rowcol].opens(previous);
+            for cell in nearby {
+                if opened {
+                    if let Some(&cost) = costs.get(&cell) {
+                        min_opened_cost = min_opened_cost.min(cost);
+                    }
+                }
+                if self.grid.get(cell).is_none() {
+                    continue;
+                }
+                // Steps next to a changed cell may now cross a blocked side or corner.
+                let blocked = match self.next_rowcol(cell) {
+                    Some(next) => !obstacles.can_traverse(cell, next),
+                    None => obstacles[cell] == Obstacle::Full,
+                };
+                if blocked {
+                    stale.insert(cell);
+                }
+            }
+        }
+
+        // Cells whose flow leads through a stale cell are stale too.
+        let mut upstream: HashMap<RowCol, Vec<RowCol>> = HashMap::new();
+        for &cell in self.grid.cells.keys() {
+            if let Some(next) = self.next_rowcol(cell) {
+                upstream.entry(next).or_default().push(cell);
+            }
+        }
+        let mut queue: Vec<RowCol> = stale.iter().copied().collect();
+        while let Some(cell) = queue.pop() {
+            for &previous in upstream.get(&cell).into_iter().flatten() {
+                if stale.insert(previous) {
+                    queue.push(previous);
+                }
+            }
+        }
+        stale.extend(
+            costs
+                .iter()
+                .filter(|(_, &cost)| cost > min_opened_cost + 1.)
+                .map(|(&rowcol, _)| rowcol),
+        );
+
+        for rowcol in stale {
+            self.grid.cells.remove(&rowcol);
+            self.a_star_runner.costs.remove(&rowcol);
+        }
     }
 }
 
-/// Mapping from goal RowCol to a sparse flow grid with forces towards that RowCol.
+/// Goal RowCol and the clearance needed to navigate to it.
+pub type NavigationKey = (RowCol, u8);
+
+/// Mapping from goal RowCol and clearance to a sparse flow grid with forces towards that RowCol.
 #[derive(Default, Resource, DerefMut, Deref)]
-pub struct NavigationGrid2(HashMap<RowCol, NavigationGrid2Entry>);
+pub struct NavigationGrid2(HashMap<NavigationKey, NavigationGrid2Entry>);
 
 /// Stores a flow grid per targeted entity.
 impl NavigationGrid2 {
@@ -148,27 +260,67 @@ impl NavigationGrid2 {
         }
     }
 
+    /// Invalidate flows affected by obstacle, seabed or current changes.
+    pub fn invalidate(
+        mut grid: ResMut<Self>,
+        mut events: EventReader<ObstacleChangeEvent>,
+        obstacles: Res<Grid2<Obstacle>>,
+        seabed: Res<Grid2<Seabed>>,
+        currents: Res<CurrentField>,
+    ) {
+        if seabed.is_changed() || currents.is_changed() {
+            grid.clear();
+        }
+        let changes: Vec<(RowCol, Obstacle)> = events
+            .read()
+            .flat_map(|event| event.changes.iter().copied())
+            .collect();
+        if changes.is_empty() {
+            return;
+        }
+        // Drop destinations that can no longer be reached at all.
+        // Flows for big units are recomputed if a change is close enough to alter whether they fit in a cell they use.
+        grid.retain(|&(destination, clearance), nav| {
+            obstacles[destination] != Obstacle::Full && !nav.near_changes(&changes, clearance)
+        });
+        for nav in grid.values_mut() {
+            nav.invalidate(&changes, &obstacles);
+        }
+    }
+
     /// Compute navigation for from all sources to the destination.
+    /// `weight` should only depend on the key, since flows are shared by all units with that key.
+    #[allow(clippy::too_many_arguments)]
     pub fn compute_flow(
         &mut self,
-        destination: RowCol,
+        key: NavigationKey,
         sources: &[RowCol],
         obstacles: &Grid2<Obstacle>,
+        graph: &NavigationGraph,
         spec: &GridSpec,
         event_writer: &mut EventWriter<NavigationCostEvent>,
+        weight: impl Fn(RowCol, RowCol) -> Option<f32>,
     ) {
-        if let Some(nav) = self.get_mut(&destination) {
+        let (destination, _clearance) = key;
+        if let Some(nav) = self.get_mut(&key) {
             let sources: Vec<RowCol> = sources
                 .iter()
                 .copied()
                 .filter(|source| nav.grid.get(*source).is_none())
                 .collect();
             if !sources.is_empty() {
-                nav.compute_flow(destination, &sources, obstacles, event_writer)
+                nav.compute_flow(
+                    destination,
+                    &sources,
+                    obstacles,
+                    graph,
+                    event_writer,
+                    weight,
+                )
             }
         } else {
             self.insert(
-                destination,
+                key,
                 NavigationGrid2Entry {
                     a_star_runner: AStarRunner::new(destination),
                     grid: SparseFlowGrid2(SparseGrid2 {
@@ -180,3 +332,105 @@ impl NavigationGrid2 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use crate::prelude::*;
+
+    const KEY: NavigationKey = ((16, 26), 1);
+    const SOURCE: RowCol = (16, 6);
+
+    fn compute_flow(world: &mut World) {
+        world.run_system_once(
+            |mut navigation: ResMut<NavigationGrid2>,
+             obstacles: Res<Grid2<Obstacle>>,
+             graph: Res<NavigationGraph>,
+             mut events: EventWriter<NavigationCostEvent>| {
+                let spec = obstacles.spec.clone();
+                // The first call only adds the entry for the key.
+                for _ in 0..2 {
+                    navigation.compute_flow(
+                        KEY,
+                        &[SOURCE],
+                        &obstacles,
+                        &graph,
+                        &spec,
+                        &mut events,
+                        |_, _| Some(1.),
+                    );
+                }
+            },
+        );
+    }
+
+    /// Cells visited by following the flow from the source.
+    fn follow_flow(world: &World) -> Vec<RowCol> {
+        let nav = &world.resource::<NavigationGrid2>()[&KEY];
+        let mut path = vec![SOURCE];
+        while let Some(next) = nav.next_rowcol(*path.last().unwrap()) {
+            assert!(path.len() < 100, "flow has a cycle");
+            path.push(next);
+        }
+        path
+    }
+
+    #[test]
+    fn test_invalidate_blocked_path() {
+        let mut obstacles = Grid2::<Obstacle> {
+            spec: GridSpec {
+                rows: 32,
+                cols: 32,
+                width: 10.0,
+                visualize: false,
+                visualize_navigation: false,
+            },
+            ..Default::default()
+        };
+        obstacles.resize();
+        let mut app = App::new();
+        app.add_event::<ObstacleEditEvent>()
+            .add_event::<ObstacleChangeEvent>()
+            .add_event::<NavigationCostEvent>()
+            .insert_resource(obstacles)
+            .init_resource::<Grid2<Seabed>>()
+            .init_resource::<CurrentField>()
+            .init_resource::<NavigationGraph>()
+            .init_resource::<NavigationGrid2>()
+            .add_systems(
+                Update,
+                (Grid2::<Obstacle>::apply_edits, NavigationGrid2::invalidate).chain(),
+            );
+        // Let change detection settle before computing flows.
+        app.update();
+
+        compute_flow(app.world_mut());
+        let path = follow_flow(app.world());
+        assert_eq!(path.last(), Some(&KEY.0));
+        let blocked = path[path.len() / 2];
+
+        app.world_mut().send_event(ObstacleEditEvent {
+            edits: vec![(blocked, Obstacle::Full)],
+        });
+        app.update();
+
+        let world = app.world();
+        let obstacles = world.resource::<Grid2<Obstacle>>();
+        let nav = &world.resource::<NavigationGrid2>()[&KEY];
+        assert!(nav.grid.get(SOURCE).is_none());
+        for &cell in nav.grid.cells.keys() {
+            assert_ne!(cell, blocked);
+            if let Some(next) = nav.next_rowcol(cell) {
+                assert_ne!(next, blocked);
+                assert!(obstacles.can_traverse(cell, next));
+            }
+        }
+
+        // Recomputed flows go around the blocked cell.
+        compute_flow(app.world_mut());
+        let path = follow_flow(app.world());
+        assert_eq!(path.last(), Some(&KEY.0));
+        assert!(!path.contains(&blocked));
+    }
+}
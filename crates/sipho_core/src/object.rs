@@ -0,0 +1,112 @@
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{Mutex, OnceLock},
+};
+
+use bevy::utils::HashSet;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::prelude::*;
+
+pub struct ObjectTypePlugin;
+impl Plugin for ObjectTypePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Object>();
+    }
+}
+
+/// Type of an object, keyed by its name in the object config.
+/// New types are added by config entries alone; the constants below are the types
+/// that built-in systems spawn or treat specially.
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[reflect_value(Component, Default, Hash, PartialEq, Serialize, Deserialize)]
+pub struct Object(&'static str);
+impl Object {
+    pub const WORKER: Self = Self("Worker");
+    pub const HEAD: Self = Self("Head");
+    pub const PLANKTON: Self = Self("Plankton");
+    pub const FOOD: Self = Self("Food");
+    pub const GEM: Self = Self("Gem");
+    pub const GEM_STONE: Self = Self("GemStone");
+    pub const SHOCKER: Self = Self("Shocker");
+    pub const ARMOR: Self = Self("Armor");
+    /// Placeholder shown while the player places a new object.
+    pub const BUILDER_PREVIEW: Self = Self("BuilderPreview");
+
+    /// Returns the type with the given name.
+    /// Names are interned, so each distinct name is leaked once for the lifetime of the process.
+    pub fn new(name: &str) -> Self {
+        static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+        let mut names = NAMES.get_or_init(Mutex::default).lock().unwrap();
+        if let Some(&interned) = names.get(name) {
+            return Self(interned);
+        }
+        let interned: &'static str = Box::leak(name.to_owned().into_boxed_str());
+        names.insert(interned);
+        Self(interned)
+    }
+
+    pub fn name(self) -> &'static str {
+        self.0
+    }
+}
+impl Default for Object {
+    fn default() -> Self {
+        Self::WORKER
+    }
+}
+impl fmt::Debug for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+impl FromStr for Object {
+    type Err = std::convert::Infallible;
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(name))
+    }
+}
+impl Serialize for Object {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+impl<'de> Deserialize<'de> for Object {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ObjectVisitor;
+        impl de::Visitor<'_> for ObjectVisitor {
+            type Value = Object;
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an object type name")
+            }
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<Object, E> {
+                Ok(Object::new(name))
+            }
+        }
+        deserializer.deserialize_str(ObjectVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interned_names() {
+        let name = String::from("Head");
+        assert_eq!(Object::new(&name), Object::HEAD);
+        let lurker = Object::new("Lurker");
+        assert_eq!(lurker, Object::new("Lurker"));
+        assert_eq!(
+            lurker.name().as_ptr(),
+            Object::new("Lurker").name().as_ptr()
+        );
+        assert_eq!(format!("{:?}", lurker), "Lurker");
+    }
+}
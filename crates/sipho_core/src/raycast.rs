@@ -37,6 +37,7 @@ pub struct RaycastCommands<'w, 's> {
             &'static GridRaycastTarget,
             &'static Handle<Mesh>,
             &'static GlobalTransform,
+            &'static Visibility,
         ),
     >,
     pub assets: Res<'w, Assets<Mesh>>,
@@ -64,9 +65,11 @@ impl RaycastCommands<'_, '_> {
             &all::<Team>().collect::<Vec<Team>>(),
             n,
         );
-        for (entity, _target, mesh_handle, transform) in entities
+        // Entities hidden by fog or stealth can't be hovered or selected.
+        for (entity, _target, mesh_handle, transform, _visibility) in entities
             .iter()
             .filter_map(|&entity| self.grid_meshes.get(entity).ok())
+            .filter(|(_, _, _, _, &visibility)| visibility != Visibility::Hidden)
         {
             let mesh = self.assets.get(mesh_handle).unwrap();
 
@@ -0,0 +1,31 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::prelude::*;
+
+pub struct RngPlugin;
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameRng>();
+    }
+}
+
+/// Random numbers for gameplay.
+/// Insert a seeded generator before adding the game plugins to make matches reproducible.
+#[derive(Resource, Deref, DerefMut)]
+pub struct GameRng(pub StdRng);
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
+impl GameRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+
+    /// Generator for one entity in systems that iterate in parallel.
+    /// Draw `seed` from the shared generator once per run so results don't depend on thread scheduling.
+    pub fn for_entity(seed: u64, entity: Entity) -> StdRng {
+        StdRng::seed_from_u64(seed ^ entity.to_bits())
+    }
+}
@@ -14,14 +14,24 @@ impl Plugin for TeamPlugin {
 #[reflect(Resource)]
 pub struct TeamConfig {
     pub player_team: Team,
+    /// Watch the match without controlling any team.
+    #[reflect(default)]
+    pub observer: bool,
 }
 impl Default for TeamConfig {
     fn default() -> Self {
         Self {
             player_team: Team::Blue,
+            observer: false,
         }
     }
 }
+impl TeamConfig {
+    /// Run condition for systems that turn the player's input into commands.
+    pub fn is_playing(config: Res<TeamConfig>) -> bool {
+        !config.observer
+    }
+}
 
 // Constants for template parameters.
 pub const TEAM_NONE: u8 = 0;
@@ -2,6 +2,7 @@
 pub const FACTOR: f32 = 10.;
 pub const BACKGROUND: f32 = -0.10 * FACTOR;
 pub const SHADER_BACKGROUND: f32 = -0.9 * FACTOR;
+pub const SEABED: f32 = -0.75 * FACTOR;
 pub const OBSTACLES: f32 = -0.7 * FACTOR;
 pub const PLANKTON: f32 = -0.20 * FACTOR;
 pub const ZOOID_HEAD: f32 = 0.0 * FACTOR;
@@ -15,6 +16,7 @@ pub const NAVIGATION_LAYER: f32 = 0.14 * FACTOR;
 pub const HIGHLIGHT: f32 = 0.15 * FACTOR;
 pub const WAYPOINT: f32 = 0.22 * FACTOR;
 pub const SELECTOR: f32 = 0.30 * FACTOR;
+pub const HEALTH_BARS: f32 = 10.0 * FACTOR;
 pub const FOG_OF_WAR: f32 = 15.0 * FACTOR;
 pub const MIN_CAMERA: f32 = 1000.0;
 pub const CAMERA: f32 = 2500.0;
@@ -1,6 +1,10 @@
 use crate::prelude::*;
 
 use bevy_hanabi::prelude::*;
+
+/// Scales the ocean current into the bubbles' acceleration.
+const CURRENT_ACCEL: f32 = 20.;
+
 pub struct BubblesPlugin;
 impl Plugin for BubblesPlugin {
     fn build(&self, app: &mut App) {
@@ -28,8 +32,11 @@ impl BubbleSpawner {
         let lifetime = writer.lit(4.).expr();
         let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, lifetime);
 
+        // Bubbles drift along the ocean current around the camera.
+        let current = writer.add_property("current", Vec3::ZERO.into());
         let accel = (writer.lit((Vec3::Y + Vec3::Z) * 10.)
-            + (writer.time() * writer.lit(2.)).sin() * writer.lit(Vec3::X * 40.))
+            + (writer.time() * writer.lit(2.)).sin() * writer.lit(Vec3::X * 40.)
+            + writer.prop(current) * writer.lit(CURRENT_ACCEL))
         .expr();
         let update_accel = AccelModifier::new(accel);
 
@@ -112,17 +119,22 @@ impl BubbleSpawner {
                 },
                 ..default()
             },
+            // Holds the "current" property set in `update`.
+            EffectProperties::default(),
         ));
     }
 
     pub fn update(
         camera: Query<&GlobalTransform, With<MainCamera>>,
-        mut spawners: Query<&mut Transform, With<BubbleSpawner>>,
+        mut spawners: Query<(&mut Transform, &mut EffectProperties), With<BubbleSpawner>>,
+        currents: Res<CurrentField>,
     ) {
         let camera_transform = camera.single();
-        for mut transform in spawners.iter_mut() {
+        for (mut transform, mut properties) in spawners.iter_mut() {
             transform.translation = camera_transform.translation().xy().extend(0.)
                 + Vec3::Y * (zindex::CAMERA * MainCamera::THETA.tan());
+            let current = currents.at(transform.translation.xy());
+            properties.set("current", current.extend(0.).into());
         }
     }
 }
@@ -0,0 +1,422 @@
+//! Runs headless matches between computer players and reports how each of them did.
+//!
+//! cargo run --release --bin tournament -- --ai normal --ai expert --matches 8 --format csv
+use std::{fmt::Write as _, fs, time::Duration};
+
+use serde::Serialize;
+
+use bevy::{
+    app::PluginsState, ecs::system::RunSystemOnce, tasks::tick_global_task_pools_on_main_thread,
+    time::TimeUpdateStrategy, utils::HashMap,
+};
+use clap::{Parser, ValueEnum};
+use sipho::{
+    ai::{AiDifficulty, AiSettings},
+    prelude::*,
+    scene::ScenePaths,
+    stats::MatchStats,
+};
+
+/// Game time simulated per frame.
+const TIMESTEP: Duration = Duration::from_nanos(1_000_000_000 / 60);
+/// Frames to wait for assets before giving up on a match.
+const MAX_LOADING_FRAMES: usize = 10_000;
+/// Frames past the time limit before giving up on a match whose game time stopped advancing.
+const MAX_EXTRA_FRAMES: usize = 10_000;
+/// Distance between the two starting heads.
+const START_DISTANCE: f32 = 3000.;
+const START_FOOD: usize = 20;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "tournament",
+    about = "Run headless matches between computer players."
+)]
+struct Args {
+    /// Computer players to pit against each other. Each pair plays, or a mirror match if only one is given.
+    #[arg(long = "ai", value_enum, default_values_t = [AiDifficulty::Normal, AiDifficulty::Expert])]
+    ais: Vec<AiDifficulty>,
+    /// Config scenes to compare, relative to the assets folder.
+    #[arg(long = "config", default_values_t = [String::from("scenes/config.scn.ron")])]
+    configs: Vec<String>,
+    /// Matches per pairing and config. Each match uses the next seed and alternates sides.
+    #[arg(short, long, default_value_t = 4)]
+    matches: u64,
+    /// First seed. Seeds pick the starting position of the second colony and seed gameplay randomness.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Matches without a winner after this many seconds of game time are draws.
+    #[arg(long, default_value_t = 600.)]
+    max_seconds: f32,
+    #[arg(short, long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+    /// Report every match instead of totals per computer player.
+    #[arg(long)]
+    per_match: bool,
+    /// Write the report to this file instead of stdout.
+    #[arg(short, long)]
+    output: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format {
+    Csv,
+    Json,
+}
+
+/// Setup of a single match.
+#[derive(Clone, Debug)]
+struct MatchSpec {
+    config: String,
+    seed: u64,
+    blue: AiDifficulty,
+    red: AiDifficulty,
+}
+impl MatchSpec {
+    fn difficulty(&self, team: Team) -> AiDifficulty {
+        if team == Team::Blue {
+            self.blue
+        } else {
+            self.red
+        }
+    }
+
+    /// Start the match once the game is running.
+    fn start(&self, world: &mut World) {
+        world.resource_mut::<AiSettings>().teams =
+            HashMap::from_iter([(Team::Blue, self.blue), (Team::Red, self.red)]);
+
+        // Blue starts at the origin. Vary where red starts by seed.
+        let bounds = world.resource::<GridSpec>().world2d_bounds();
+        let angle = self.seed as f32 * std::f32::consts::PI * (3. - 5f32.sqrt());
+        let margin = Vec2::splat(START_DISTANCE / 4.);
+        let position = (Vec2::from_angle(angle) * START_DISTANCE)
+            .clamp(bounds.min + margin, bounds.max - margin);
+        world.run_system_once(move |mut commands: ObjectCommands| {
+            commands.spawn(ObjectSpec {
+                object: Object::HEAD,
+                team: Team::Red,
+                position: Position(position),
+                ..default()
+            });
+            for _ in 0..START_FOOD {
+                commands.spawn(ObjectSpec {
+                    object: Object::FOOD,
+                    position: Position(position),
+                    ..default()
+                });
+            }
+        });
+    }
+
+    /// Simulate the match until one team is left or time runs out.
+    fn run(&self, max_seconds: f32) -> Result<MatchStats, String> {
+        let mut app = App::new();
+        app.insert_resource(ScenePaths {
+            config: self.config.clone(),
+            ..default()
+        })
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TIMESTEP))
+        .insert_resource(GameRng::seeded(self.seed))
+        .add_plugins(SiphonowarPlugin { headless: true });
+        while app.plugins_state() == PluginsState::Adding {
+            tick_global_task_pools_on_main_thread();
+        }
+        app.finish();
+        app.cleanup();
+
+        let mut frames = 0;
+        loop {
+            app.update();
+            frames += 1;
+            if *app.world().resource::<State<GameState>>().get() == GameState::Running {
+                break;
+            }
+            if frames > MAX_LOADING_FRAMES {
+                return Err(format!("Timed out loading {}", self.config));
+            }
+        }
+        self.start(app.world_mut());
+
+        let max_frames = (max_seconds / TIMESTEP.as_secs_f32()).ceil() as usize + MAX_EXTRA_FRAMES;
+        for _ in 0..max_frames {
+            app.update();
+            let running = *app.world().resource::<State<GameState>>().get() == GameState::Running;
+            let stats = app.world().resource::<MatchStats>();
+            if !running || stats.finished() || stats.elapsed >= max_seconds {
+                return Ok(stats.clone());
+            }
+        }
+        Err(format!(
+            "Timed out after {} frames on {} (seed {})",
+            max_frames, self.config, self.seed
+        ))
+    }
+}
+
+/// Outcome of a finished match.
+struct MatchResult {
+    spec: MatchSpec,
+    stats: MatchStats,
+}
+
+/// Totals for one computer player with one config.
+/// Mirror matches are totalled per side, since both sides are the same player.
+#[derive(Default, Serialize)]
+struct Summary {
+    config: String,
+    ai: String,
+    side: String,
+    matches: usize,
+    wins: usize,
+    losses: usize,
+    draws: usize,
+    win_rate: f32,
+    avg_seconds: f32,
+    avg_units_produced: f32,
+    avg_resources_gathered: f32,
+    #[serde(skip)]
+    seconds: f32,
+    #[serde(skip)]
+    units_produced: usize,
+    #[serde(skip)]
+    resources_gathered: usize,
+}
+impl Summary {
+    fn new(config: &str, ai: AiDifficulty, side: Option<Team>) -> Self {
+        Self {
+            config: config.to_string(),
+            ai: name(ai),
+            side: side.map(side_name).unwrap_or_default(),
+            ..default()
+        }
+    }
+
+    fn add(&mut self, result: &MatchResult, team: Team) {
+        let stats = &result.stats;
+        self.matches += 1;
+        match stats.winner {
+            Some(winner) if winner == team => self.wins += 1,
+            Some(_) => self.losses += 1,
+            None => self.draws += 1,
+        }
+        self.seconds += stats.elapsed;
+        self.units_produced += stats.team(team).units_produced;
+        self.resources_gathered += stats.team(team).resources_gathered;
+
+        let matches = self.matches as f32;
+        self.win_rate = self.wins as f32 / matches;
+        self.avg_seconds = self.seconds / matches;
+        self.avg_units_produced = self.units_produced as f32 / matches;
+        self.avg_resources_gathered = self.resources_gathered as f32 / matches;
+    }
+}
+
+/// One row of the per match report.
+#[derive(Serialize)]
+struct MatchRow {
+    config: String,
+    seed: u64,
+    blue: String,
+    red: String,
+    winner: String,
+    seconds: f32,
+    blue_units: usize,
+    red_units: usize,
+    blue_resources: usize,
+    red_resources: usize,
+}
+impl MatchRow {
+    fn new(result: &MatchResult) -> Self {
+        let (spec, stats) = (&result.spec, &result.stats);
+        let (blue, red) = (stats.team(Team::Blue), stats.team(Team::Red));
+        Self {
+            config: spec.config.clone(),
+            seed: spec.seed,
+            blue: name(spec.blue),
+            red: name(spec.red),
+            winner: winner_name(result),
+            seconds: stats.elapsed,
+            blue_units: blue.units_produced,
+            red_units: red.units_produced,
+            blue_resources: blue.resources_gathered,
+            red_resources: red.resources_gathered,
+        }
+    }
+}
+
+fn side_name(team: Team) -> String {
+    if team == Team::Blue {
+        "blue".to_string()
+    } else {
+        "red".to_string()
+    }
+}
+
+fn name(difficulty: AiDifficulty) -> String {
+    difficulty
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn winner_name(result: &MatchResult) -> String {
+    result
+        .stats
+        .winner
+        .map(|team| name(result.spec.difficulty(team)))
+        .unwrap_or_else(|| "draw".to_string())
+}
+
+/// Every match to play, in order.
+fn schedule(args: &Args) -> Vec<MatchSpec> {
+    let mut pairings = Vec::new();
+    for (i, &a) in args.ais.iter().enumerate() {
+        for &b in &args.ais[i + 1..] {
+            pairings.push((a, b));
+        }
+    }
+    if let [ai] = args.ais[..] {
+        pairings.push((ai, ai));
+    }
+
+    let mut specs = Vec::new();
+    for config in &args.configs {
+        for &(a, b) in &pairings {
+            for seed in args.seed..args.seed + args.matches {
+                // Alternate sides to cancel out any advantage of starting at the origin.
+                let (blue, red) = if seed % 2 == 0 { (a, b) } else { (b, a) };
+                specs.push(MatchSpec {
+                    config: config.clone(),
+                    seed,
+                    blue,
+                    red,
+                });
+            }
+        }
+    }
+    specs
+}
+
+fn to_json(rows: &impl Serialize) -> String {
+    let mut out = serde_json::to_string_pretty(rows).unwrap();
+    out.push('\n');
+    out
+}
+
+fn per_match_report(results: &[MatchResult], format: Format) -> String {
+    let rows: Vec<MatchRow> = results.iter().map(MatchRow::new).collect();
+    if let Format::Json = format {
+        return to_json(&rows);
+    }
+    let mut out = String::new();
+    out.push_str(
+        "config,seed,blue,red,winner,seconds,blue_units,red_units,blue_resources,red_resources\n",
+    );
+    for row in rows {
+        writeln!(
+            out,
+            "{},{},{},{},{},{:.1},{},{},{},{}",
+            row.config,
+            row.seed,
+            row.blue,
+            row.red,
+            row.winner,
+            row.seconds,
+            row.blue_units,
+            row.red_units,
+            row.blue_resources,
+            row.red_resources,
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn summary_report(results: &[MatchResult], format: Format) -> String {
+    // Keep the order players first appear in.
+    let mut keys: Vec<(String, AiDifficulty, Option<Team>)> = Vec::new();
+    let mut summaries: HashMap<(String, AiDifficulty, Option<Team>), Summary> = HashMap::new();
+    for result in results {
+        let spec = &result.spec;
+        let mirror = spec.blue == spec.red;
+        for team in [Team::Blue, Team::Red] {
+            let ai = spec.difficulty(team);
+            let side = mirror.then_some(team);
+            let key = (spec.config.clone(), ai, side);
+            if !summaries.contains_key(&key) {
+                keys.push(key.clone());
+            }
+            summaries
+                .entry(key)
+                .or_insert_with(|| Summary::new(&spec.config, ai, side))
+                .add(result, team);
+        }
+    }
+    let rows: Vec<&Summary> = keys.iter().map(|key| &summaries[key]).collect();
+
+    if let Format::Json = format {
+        return to_json(&rows);
+    }
+    let mut out = String::new();
+    out.push_str("config,ai,side,matches,wins,losses,draws,win_rate,avg_seconds,avg_units_produced,avg_resources_gathered\n");
+    for summary in rows {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{:.3},{:.1},{:.1},{:.1}",
+            summary.config,
+            summary.ai,
+            summary.side,
+            summary.matches,
+            summary.wins,
+            summary.losses,
+            summary.draws,
+            summary.win_rate,
+            summary.avg_seconds,
+            summary.avg_units_produced,
+            summary.avg_resources_gathered,
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn main() -> Result<(), String> {
+    let args = Args::parse();
+    let specs = schedule(&args);
+    let total = specs.len();
+
+    let mut results = Vec::with_capacity(total);
+    for (i, spec) in specs.into_iter().enumerate() {
+        eprint!(
+            "[{}/{}] {} vs {} on {} (seed {}): ",
+            i + 1,
+            total,
+            name(spec.blue),
+            name(spec.red),
+            spec.config,
+            spec.seed
+        );
+        let stats = spec.run(args.max_seconds)?;
+        let result = MatchResult { spec, stats };
+        eprintln!(
+            "{} after {:.0}s",
+            winner_name(&result),
+            result.stats.elapsed
+        );
+        results.push(result);
+    }
+
+    let report = if args.per_match {
+        per_match_report(&results, args.format)
+    } else {
+        summary_report(&results, args.format)
+    };
+    match &args.output {
+        Some(path) => fs::write(path, report).map_err(|error| error.to_string()),
+        None => {
+            print!("{}", report);
+            Ok(())
+        }
+    }
+}
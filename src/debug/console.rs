@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_console::{reply, AddConsoleCommand, ConsoleCommand, ConsolePlugin};
 use clap::Parser;
-use sipho::objects::ai::EnemyAI;
+use sipho::ai::{AiDifficulty, AiSettings};
 use sipho::prelude::*;
 use sipho::scene::SaveEvent;
 
@@ -33,8 +33,9 @@ struct SpawnCommand {
     count: usize,
     team: Team,
     object: Object,
-    #[arg(short, long, default_value_t = false)]
-    ai: bool,
+    /// Hand control of the team to the computer.
+    #[arg(short, long)]
+    ai: Option<AiDifficulty>,
 }
 impl SpawnCommand {
     pub fn update(
@@ -42,6 +43,7 @@ impl SpawnCommand {
         mut commands: ObjectCommands,
         cursor: CursorParam,
         raycast: RaycastCommands,
+        mut ai_settings: ResMut<AiSettings>,
     ) {
         if let Some(Ok(SpawnCommand {
             object,
@@ -51,6 +53,9 @@ impl SpawnCommand {
         })) = log.take()
         {
             reply!(log, "spawning {} {:?}", count, object);
+            if let Some(difficulty) = ai {
+                ai_settings.teams.insert(team, difficulty);
+            }
             if let Some(ray) = cursor.ray3d() {
                 if let Some(raycast_event) = raycast.raycast(ray) {
                     let sqrt_count = (count as f32).sqrt() as usize;
@@ -63,23 +68,22 @@ impl SpawnCommand {
                                         y: (j * 40) as f32,
                                     },
                             );
-                            if let Some(mut entity_command) = commands.spawn(ObjectSpec {
-                                object,
-                                team,
-                                position,
-                                ..default()
-                            }) {
-                                if ai {
-                                    entity_command.insert(EnemyAI::default());
-                                }
-                                if object == Object::Head {
-                                    for _ in 0..20 {
-                                        commands.spawn(ObjectSpec {
-                                            object: Object::Food,
-                                            position,
-                                            ..default()
-                                        });
-                                    }
+                            if commands
+                                .spawn(ObjectSpec {
+                                    object,
+                                    team,
+                                    position,
+                                    ..default()
+                                })
+                                .is_some()
+                                && object == Object::HEAD
+                            {
+                                for _ in 0..20 {
+                                    commands.spawn(ObjectSpec {
+                                        object: Object::FOOD,
+                                        position,
+                                        ..default()
+                                    });
                                 }
                             }
                         }
@@ -118,7 +122,7 @@ impl BattleCommand {
                                     },
                             );
                             commands.spawn(ObjectSpec {
-                                object: Object::Worker,
+                                object: Object::WORKER,
                                 team: Team::Blue,
                                 position: blue_position,
                                 ..default()
@@ -131,7 +135,7 @@ impl BattleCommand {
                                     },
                             );
                             commands.spawn(ObjectSpec {
-                                object: Object::Worker,
+                                object: Object::WORKER,
                                 team: Team::Red,
                                 position: red_position,
                                 ..default()
@@ -1,11 +1,46 @@
-use sipho::prelude::*;
+use clap::Parser;
+use sipho::{
+    ai::AiSettings,
+    prelude::*,
+    scene::{ScenePaths, TUTORIAL_FILE_PATH},
+};
 
 #[cfg(feature = "debug")]
 mod debug;
 
+#[derive(Parser, Debug)]
+#[command(name = "siphonowar", about = "RTS game based on siphonophores.")]
+struct Args {
+    /// Play the tutorial scenario instead of an open match.
+    #[arg(long)]
+    tutorial: bool,
+    /// Watch the computer play both teams instead of controlling one.
+    #[arg(long)]
+    observe: bool,
+}
+
+/// Hand every team to the computer and show the observer HUD.
+/// Runs after the config scene is loaded, since that sets `TeamConfig` and `AiSettings`.
+fn observe(mut team_config: ResMut<TeamConfig>, mut ai_settings: ResMut<AiSettings>) {
+    team_config.observer = true;
+    for team in [Team::Blue, Team::Red] {
+        ai_settings.teams.entry(team).or_default();
+    }
+}
+
 fn main() {
+    let args = Args::parse();
     let mut app = App::new();
-    app.add_plugins(SiphonowarPlugin);
+    if args.tutorial {
+        app.insert_resource(ScenePaths {
+            scenario: TUTORIAL_FILE_PATH.to_string(),
+            ..default()
+        });
+    }
+    app.add_plugins(SiphonowarPlugin::default());
+    if args.observe {
+        app.add_systems(OnExit(GameState::Loading), observe);
+    }
     #[cfg(feature = "debug")]
     {
         app.add_plugins(debug::DebugPlugin);